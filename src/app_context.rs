@@ -0,0 +1,108 @@
+use anyhow::{Context, Result, bail};
+use log::warn;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::cli::CliArgs;
+use crate::config_manager::ConfigManager;
+use crate::descriptions::Descriptions;
+use crate::file_manager::FileManager;
+use crate::settings::AppSettings;
+
+/// Everything the GUI needs, built from the command line arguments.
+/// Keeps the application wiring out of `main`, so it can be tested
+/// without opening a window.
+pub struct AppContext {
+    pub settings: Rc<RefCell<AppSettings>>,
+    pub file_manager: FileManager,
+    pub config_manager: ConfigManager,
+    /// Directory or WAV file given on the command line, already checked to exist
+    pub initial_path: Option<PathBuf>,
+}
+
+impl AppContext {
+    /// Loads the settings and descriptions, creates the managers and
+    /// resolves the scan path from the command line.
+    pub fn build(args: &CliArgs) -> Result<AppContext> {
+        let initial_path = match &args.path {
+            Some(path) if !path.exists() => bail!("Path {} does not exist", path.display()),
+            Some(path) => Some(path.clone()),
+            None => None,
+        };
+
+        let mut temp_settings = AppSettings::default();
+        temp_settings.dev_mode = cfg!(debug_assertions);
+        temp_settings.config_dir = args.config_dir.clone();
+
+        // Load application settings using the temp settings to determine path
+        let loaded_settings = match temp_settings.load() {
+            Ok(v) => v,
+            Err(e) => {
+                warn!("Failed to load settings: {}, using defaults", e);
+                temp_settings
+            }
+        };
+        let settings = Rc::new(RefCell::new(loaded_settings));
+
+        // Descriptions, loads HRTF descriptions from embedded CSV and the
+        // user's own one.
+        let descriptions = Descriptions::load(settings.borrow().descriptions_file.as_deref());
+
+        // File manager, scans for WAV files.
+        let file_manager = FileManager::new(settings.clone(), descriptions);
+
+        // Config manager, writes and deletes the PipeWire config
+        let config_manager =
+            ConfigManager::new(settings.clone()).context("Can not process config file")?;
+
+        Ok(AppContext {
+            settings,
+            file_manager,
+            config_manager,
+            initial_path,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_with_valid_directory() {
+        let config = tempfile::tempdir().expect("Failed to create temp dir");
+        let wav_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let args = CliArgs {
+            path: Some(wav_dir.path().to_path_buf()),
+            config_dir: Some(config.path().to_path_buf()),
+            tui: false,
+            command: None,
+        };
+
+        let context = AppContext::build(&args).expect("Building the context should succeed");
+        assert_eq!(context.initial_path.as_deref(), Some(wav_dir.path()));
+        assert!(
+            context
+                .config_manager
+                .config_path()
+                .starts_with(config.path())
+        );
+    }
+
+    #[test]
+    fn test_build_with_missing_path() {
+        let config = tempfile::tempdir().expect("Failed to create temp dir");
+        let args = CliArgs {
+            path: Some(config.path().join("missing")),
+            config_dir: Some(config.path().to_path_buf()),
+            tui: false,
+            command: None,
+        };
+
+        let Err(err) = AppContext::build(&args) else {
+            panic!("Building with a missing path should fail");
+        };
+        assert!(err.to_string().contains("does not exist"));
+    }
+}
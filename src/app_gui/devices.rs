@@ -0,0 +1,100 @@
+use eframe::egui;
+use log::{error, info};
+
+use super::AppGUI;
+use super::main_gui::ModalAction;
+use super::strings::{Msg, tr};
+use crate::config_manager::InstalledDevice;
+
+impl<'a> AppGUI<'a> {
+    /// Renders the devices installed under all identifiers, each with
+    /// buttons to manage it and to remove it.
+    pub(crate) fn render_installed_devices(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr(Msg::InstalledDevicesHeading));
+        if self.installed_devices.is_empty() {
+            ui.label(tr(Msg::NoInstalledDevices));
+            return;
+        }
+        let current_id = self.config_manager.device_id();
+        let mut switch_to = None;
+        let mut remove = None;
+        egui::Grid::new("installed_devices")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                for device in &self.installed_devices {
+                    let id = if device.device_id.is_empty() {
+                        tr(Msg::DefaultDeviceId)
+                    } else {
+                        device.device_id.as_str()
+                    };
+                    let current = device.device_id == current_id;
+                    // The device the Files tab buttons manage stands out
+                    let id = egui::RichText::new(id);
+                    ui.label(if current { id.strong() } else { id });
+                    ui.label(&device.name);
+                    let file = self
+                        .find_wav_by_checksum(device.checksum)
+                        .map(|wav| wav.relative_path.display().to_string())
+                        .unwrap_or_else(|| tr(Msg::UnknownIrFile).to_string());
+                    ui.label(file);
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(!current, egui::Button::new(tr(Msg::ManageDevice)))
+                            .clicked()
+                        {
+                            switch_to = Some(device.device_id.clone());
+                        }
+                        if ui.button(tr(Msg::RemoveInstalledDevice)).clicked() {
+                            remove = Some(device.clone());
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        if let Some(device_id) = switch_to {
+            self.on_apply_device_id_click(Some(device_id));
+        }
+        if let Some(device) = remove {
+            self.on_remove_installed_device_click(device);
+        }
+    }
+
+    /// Asks for confirmation before removing an installed device, like the
+    /// Remove button does for the configured one.
+    fn on_remove_installed_device_click(&mut self, device: InstalledDevice) {
+        if device.device_id == self.config_manager.device_id() {
+            return self.on_remove_device_click();
+        }
+        if !self.settings.borrow().confirm_device_removal {
+            return self.remove_installed_device(&device);
+        }
+        self.show_modal_with_action(
+            tr(Msg::RemoveDeviceHeader),
+            &tr(Msg::RemoveDeviceMessage).replace("{}", &device.name),
+            ModalAction::RemoveDevice,
+        );
+        self.pending_removal = Some(device);
+    }
+
+    /// Removes a device of another identifier than the configured one.
+    pub(crate) fn remove_installed_device(&mut self, device: &InstalledDevice) {
+        match self.config_manager.delete_installed_device(device) {
+            Ok(()) => info!("Config {} deleted", device.config_path.display()),
+            Err(e) => error!("Failed to delete config: {}", e),
+        }
+        self.refresh_installed_devices();
+    }
+
+    /// Lists the installed devices again, after a config was written or
+    /// removed.
+    pub(crate) fn refresh_installed_devices(&mut self) {
+        self.installed_devices = match self.config_manager.installed_devices() {
+            Ok(devices) => devices,
+            Err(e) => {
+                error!("Failed to list installed devices: {:#}", e);
+                Vec::new()
+            }
+        };
+    }
+}
@@ -1,14 +1,64 @@
+use anyhow::Context;
 use eframe::egui;
 use egui_extras::{Column, TableBuilder};
+use rfd::FileDialog;
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::SystemTime;
 
 use super::AppGUI;
-use crate::file_manager::{WavFileData, WaveSampleRate};
+use super::main_gui::ModalAction;
+use super::strings::{Msg, tr};
+use crate::dir_watcher::DirWatcher;
+use crate::export::{write_files_csv, write_files_json};
+use crate::file_manager::{
+    BackgroundScan, FileManager, ScanOutput, WavFileData, WaveSampleRate, format_checksum,
+};
+use crate::preview;
+use crate::settings::{ComparedPair, SortColumn, Tab};
 use crate::wav_file_index::WavFileIndex;
-use log::info;
+use log::{error, info, warn};
+use regex::{Regex, RegexBuilder};
 use walkdir::WalkDir;
 
+/// A rescan in flight, with what to do once it is over.
+pub(crate) struct RunningScan {
+    pub(crate) scan: BackgroundScan,
+    /// Whether the directory is written to the settings after the scan
+    persist_directory: bool,
+    /// File to select after the scan
+    file_to_select: Option<PathBuf>,
+    /// Whether this is the scan at application start
+    pub(crate) startup: bool,
+}
+
+/// Formats a file size for the table and the metadata view, in binary units.
+fn format_size(bytes: u64) -> String {
+    const KIB: u64 = 1024;
+    const MIB: u64 = 1024 * KIB;
+    if bytes < KIB {
+        tr(Msg::SizeBytes).replace("{}", &bytes.to_string())
+    } else if bytes < MIB {
+        tr(Msg::SizeKib).replace("{}", &format!("{:.1}", bytes as f64 / KIB as f64))
+    } else {
+        tr(Msg::SizeMib).replace("{}", &format!("{:.1}", bytes as f64 / MIB as f64))
+    }
+}
+
+/// Formats a file modification time for the table, in local time.
+fn format_modified(modified: Option<SystemTime>) -> String {
+    modified
+        .map(|time| {
+            chrono::DateTime::<chrono::Local>::from(time)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
 impl<'a> AppGUI<'a> {
     /// Get HRTF metadata for the currently selected file, if any.
     fn selected_metadata(&self) -> Option<&crate::descriptions::HRTFMetadata> {
@@ -17,26 +67,54 @@ impl<'a> AppGUI<'a> {
         wave.metadata.as_deref()
     }
 
+    /// Longest description shown in full without expanding it.
+    const DESCRIPTION_MAX_LEN: usize = 240;
+
     /// Truncate a description to approximately three lines.
     fn truncate_description(description: &str) -> String {
-        const MAX_LEN: usize = 240;
-        if description.len() <= MAX_LEN {
+        if !Self::is_description_long(description) {
             return description.to_string();
         }
-        let truncated = &description[..MAX_LEN - 3];
-        format!("{}...", truncated.trim_end())
+        // Cut at a character boundary, descriptions are not ASCII-only
+        let mut end = Self::DESCRIPTION_MAX_LEN - 3;
+        while !description.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}...", description[..end].trim_end())
+    }
+
+    /// Shows the full description, source and credits of a file when the
+    /// pointer rests on its Description cell. Files without one get none.
+    fn description_tooltip(
+        response: egui::Response,
+        metadata: Option<&crate::descriptions::HRTFMetadata>,
+    ) {
+        let Some(metadata) = metadata else {
+            return;
+        };
+        response.on_hover_ui(|ui| {
+            ui.set_max_width(400.0);
+            ui.label(&metadata.description);
+            if !metadata.source.is_empty() {
+                ui.label(format!("{} {}", tr(Msg::SourcePrefix), metadata.source));
+            }
+            if !metadata.credits.is_empty() {
+                ui.label(format!("{} {}", tr(Msg::CreditsPrefix), metadata.credits));
+            }
+        });
+    }
+
+    /// Checks whether `truncate_description` shortens the description.
+    fn is_description_long(description: &str) -> bool {
+        description.len() > Self::DESCRIPTION_MAX_LEN
     }
 
     /// Auto‑select the file that matches the installed config (if any).
     fn apply_auto_selection(&mut self) {
         let old_checksum = self.selected_checksum;
         match self.config_installed {
-            Some(checksum) if checksum != 0 => {
-                if self.find_wav_by_checksum(checksum).is_some() {
-                    self.selected_checksum = Some(checksum);
-                } else {
-                    self.selected_checksum = None;
-                }
+            Some(checksum) if checksum != 0 && self.find_wav_by_checksum(checksum).is_some() => {
+                self.selected_checksum = Some(checksum);
             }
             _ => {
                 self.selected_checksum = None;
@@ -53,11 +131,33 @@ impl<'a> AppGUI<'a> {
         }
     }
 
+    /// Compiles the search text as a case-insensitive regex, reusing the
+    /// last result while the text is unchanged. None if it doesn't compile.
+    fn compiled_search_regex(&mut self) -> Option<Regex> {
+        let up_to_date = self
+            .search_regex_cache
+            .as_ref()
+            .is_some_and(|(pattern, _)| *pattern == self.search_text);
+        if !up_to_date {
+            let regex = RegexBuilder::new(&self.search_text)
+                .case_insensitive(true)
+                .build()
+                .ok();
+            self.search_regex_cache = Some((self.search_text.clone(), regex));
+        }
+        self.search_regex_cache
+            .as_ref()
+            .and_then(|(_, regex)| regex.clone())
+    }
+
     /// Gives access to filtered items index, recreating it if it is None.
     fn get_filtered_wav_files(&mut self) -> &WavFileIndex {
-        if self.filtered_wav_index.is_some() {
-            return self.filtered_wav_index.as_ref().unwrap();
+        if let Some(ref filtered) = self.filtered_wav_index {
+            return filtered;
         }
+        let search_regex = self.search_regex.then(|| self.compiled_search_regex());
+        let settings = self.settings.borrow();
+        let hide_duplicates = settings.hide_duplicates;
         let filter_predicate = |wave: &&WavFileData| {
             let sample_rate_ok = match self.sample_rate_filter {
                 WaveSampleRate::Unknown => true,
@@ -68,12 +168,74 @@ impl<'a> AppGUI<'a> {
                 true
             } else {
                 let search_lower = self.search_text.to_lowercase();
-                let path_lower = wave.relative_path.to_string_lossy().to_lowercase();
-                path_lower.contains(&search_lower)
+                let matches = |text: &str| match &search_regex {
+                    // An invalid pattern matches nothing
+                    Some(regex) => regex.as_ref().is_some_and(|r| r.is_match(text)),
+                    None => text.to_lowercase().contains(&search_lower),
+                };
+                matches(&wave.relative_path.to_string_lossy())
+                    || (self.search_descriptions
+                        && wave.metadata.as_ref().is_some_and(|m| {
+                            matches(&m.hrtf)
+                                || matches(&m.description)
+                                || matches(&m.source)
+                                || matches(&m.credits)
+                        }))
             };
-            sample_rate_ok && search_ok
+            let described_ok = !self.described_only || wave.metadata.is_some();
+            let favorite_ok =
+                !self.favorites_only || settings.is_favorite(wave.checksum, wave.library_path());
+            let family_ok = self
+                .family_filter
+                .as_ref()
+                .is_none_or(|family| wave.metadata.as_ref().is_some_and(|m| m.hrtf == *family));
+            let duplicate_ok = !hide_duplicates
+                || self
+                    .duplicates
+                    .get(&wave.checksum)
+                    .is_none_or(|kept| *kept == wave.relative_path);
+            sample_rate_ok && search_ok && described_ok && favorite_ok && family_ok && duplicate_ok
         };
-        self.filtered_wav_index = Some(self.all_wav_index.filtered_clone(filter_predicate));
+        let mut filtered: Vec<WavFileData> = self
+            .all_wav_index
+            .iter()
+            .filter(filter_predicate)
+            .cloned()
+            .collect();
+        // Apply the column sort chosen in the table header, if any
+        if let Some(column) = self.sort_column {
+            filtered.sort_by(|a, b| {
+                let ordering = match column {
+                    SortColumn::Path => a.relative_path.cmp(&b.relative_path),
+                    SortColumn::Modified => a.modified.cmp(&b.modified),
+                    SortColumn::Channels => a.channels.cmp(&b.channels),
+                    SortColumn::Points => {
+                        let points_a = a.metadata.as_ref().and_then(|m| m.points);
+                        let points_b = b.metadata.as_ref().and_then(|m| m.points);
+                        points_a.cmp(&points_b)
+                    }
+                    SortColumn::Size => a.size_bytes.cmp(&b.size_bytes),
+                    SortColumn::Description => {
+                        let description_a = a.metadata.as_ref().map(|m| m.description.as_str());
+                        let description_b = b.metadata.as_ref().map(|m| m.description.as_str());
+                        description_a.cmp(&description_b)
+                    }
+                };
+                if self.sort_descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+        // Favorites go above everything, regardless of the other sort
+        if settings.favorites_first {
+            filtered.sort_by_key(|wave| !settings.is_favorite(wave.checksum, wave.library_path()));
+        }
+        drop(settings);
+        let mut filtered_index = WavFileIndex::from_vec(filtered);
+        filtered_index.shrink_to_fit();
+        self.filtered_wav_index = Some(filtered_index);
         // After recreating the filtered index, scroll to the selected row if present
         if let Some(checksum) = self.selected_checksum
             && let Some(row) = self
@@ -87,19 +249,33 @@ impl<'a> AppGUI<'a> {
         self.filtered_wav_index.as_ref().unwrap()
     }
 
-    /// Renders the file table with two columns: "Files" and "Description".
+    /// Renders the file table with columns "Files", "Modified", "Channels",
+    /// "Points" (optional) and "Description".
     fn render_file_table(&mut self, ui: &mut egui::Ui) {
         // Wrap the table in its own frame
         let table_frame = egui::Frame::group(ui.style());
         table_frame.show(ui, |ui| {
             // Create a two-column table using rows() for better performance
-            let row_height = 20.0;
+            // Rows have a fixed height, so scroll_to_row stays exact at any density
+            let density = self.settings.borrow().table_density;
+            let row_height = density.row_height();
             let num_rows = self.get_filtered_wav_files().len();
             let available_width = ui.available_width();
             let available_height: f32 = ui.available_height() - Self::METADATA_FRAME_HEIGHT;
+            let show_points = self.settings.borrow().show_points_column;
+            let show_size = self.settings.borrow().show_size_column;
 
             let mut table_builder = TableBuilder::new(ui)
-                .column(Column::initial(available_width * 0.6)) // "Files" column - auto width
+                .column(Column::initial(available_width * 0.5)) // "Files" column - auto width
+                .column(Column::initial(120.0)) // "Modified" column - fits a date and time
+                .column(Column::exact(60.0)); // "Channels" column - a short number
+            if show_points {
+                table_builder = table_builder.column(Column::exact(60.0)); // "Points" column - a short number
+            }
+            if show_size {
+                table_builder = table_builder.column(Column::exact(80.0)); // "Size" column - like "12.3 MiB"
+            }
+            table_builder = table_builder
                 .column(Column::remainder().clip(true)) // "Description" column - takes remaining width
                 .max_scroll_height(available_height)
                 .auto_shrink([false, false]) // Vertical auto_shrink false to always use available height
@@ -116,25 +292,64 @@ impl<'a> AppGUI<'a> {
             }
 
             table_builder
-                .header(20.0, |mut header| {
+                .header(density.header_height(), |mut header| {
+                    header.col(|ui| {
+                        self.sortable_header(ui, tr(Msg::ColumnFiles), SortColumn::Path);
+                    });
+                    header.col(|ui| {
+                        self.sortable_header(ui, tr(Msg::ColumnModified), SortColumn::Modified);
+                    });
                     header.col(|ui| {
-                        ui.heading("Files");
+                        self.sortable_header(ui, tr(Msg::ColumnChannels), SortColumn::Channels);
                     });
+                    if show_points {
+                        header.col(|ui| {
+                            self.sortable_header(ui, tr(Msg::ColumnPoints), SortColumn::Points);
+                        });
+                    }
+                    if show_size {
+                        header.col(|ui| {
+                            self.sortable_header(ui, tr(Msg::ColumnSize), SortColumn::Size);
+                        });
+                    }
                     header.col(|ui| {
-                        ui.heading("Description");
+                        self.sortable_header(
+                            ui,
+                            tr(Msg::ColumnDescription),
+                            SortColumn::Description,
+                        );
                     });
                 })
                 .body(|body| {
                     // Table rows are generated here
                     body.rows(row_height, num_rows, |mut row| {
                         let selected_checksum: Option<u128> = self.selected_checksum;
+                        let compare_checksum: Option<u128> = self.compare_checksum;
                         let wave: &WavFileData = self
                             .get_filtered_wav_files()
                             .get_by_index(row.index())
                             .expect("Index out of bounds in table.rows()");
                         let rel_path: &PathBuf = &wave.relative_path;
-                        let is_selected: bool = selected_checksum == Some(wave.checksum);
+                        let abs_path: PathBuf = wave.path.clone();
+                        let checksum: u128 = wave.checksum;
+                        let is_selected: bool = selected_checksum == Some(checksum);
+                        let is_damaged: bool = wave.sample_rate == WaveSampleRate::Damaged;
+                        let modified_text: String = format_modified(wave.modified);
+                        let channels_text: String = Self::channels_text(wave.channels);
+                        let points_text: String = wave
+                            .metadata
+                            .as_ref()
+                            .and_then(|m| m.points)
+                            .map(|p| p.to_string())
+                            .unwrap_or_default();
+                        let size_text: String = match wave.size_bytes {
+                            0 => String::new(),
+                            bytes => format_size(bytes),
+                        };
                         let mut label_text: String = rel_path.to_string_lossy().to_string();
+                        if compare_checksum == Some(checksum) {
+                            label_text.insert_str(0, "⇄ ");
+                        }
 
                         // Get HRTF metadata for this file (cheap lookup)
                         let description_text: String = wave
@@ -150,110 +365,517 @@ impl<'a> AppGUI<'a> {
                                     .to_string()
                             })
                             .unwrap_or_default();
+                        let metadata = wave.metadata.clone();
+                        let rel_path: PathBuf = rel_path.clone();
+                        let library_path: PathBuf = wave.library_path().to_path_buf();
+                        if self.marked_checksums.contains(&checksum) {
+                            label_text.insert_str(0, "✔ ");
+                        }
+                        if self.duplicates.contains_key(&checksum) {
+                            label_text.push_str(tr(Msg::DuplicateTag));
+                        }
+                        let is_favorite: bool =
+                            self.settings.borrow().is_favorite(checksum, &library_path);
+                        let mut favorite_clicked = false;
 
                         // Set selection state for the row
                         row.set_selected(is_selected);
 
-                        if wave.sample_rate == WaveSampleRate::Damaged {
-                            label_text.insert_str(0, "(Damaged)");
+                        if is_damaged {
                             row.col(|ui| {
+                                favorite_clicked = Self::favorite_star(ui, is_favorite);
+                                let damaged_color = ui.visuals().weak_text_color();
+                                let damaged_text = format!("{}{}", tr(Msg::DamagedTag), label_text);
                                 ui.add(
                                     egui::Label::new(
-                                        egui::RichText::new(label_text).color(egui::Color32::GRAY),
+                                        egui::RichText::new(damaged_text).color(damaged_color),
                                     )
                                     .truncate(),
                                 );
                             });
                             row.col(|ui| {
+                                let damaged_color = ui.visuals().weak_text_color();
                                 ui.add(
                                     egui::Label::new(
-                                        egui::RichText::new(description_text)
-                                            .color(egui::Color32::GRAY),
+                                        egui::RichText::new(modified_text).color(damaged_color),
                                     )
                                     .truncate(),
                                 );
                             });
+                            row.col(|ui| {
+                                let damaged_color = ui.visuals().weak_text_color();
+                                ui.add(
+                                    egui::Label::new(
+                                        egui::RichText::new(channels_text).color(damaged_color),
+                                    )
+                                    .truncate(),
+                                );
+                            });
+                            if show_points {
+                                row.col(|ui| {
+                                    let damaged_color = ui.visuals().weak_text_color();
+                                    Self::right_aligned(
+                                        ui,
+                                        egui::Label::new(
+                                            egui::RichText::new(points_text).color(damaged_color),
+                                        ),
+                                    );
+                                });
+                            }
+                            if show_size {
+                                row.col(|ui| {
+                                    let damaged_color = ui.visuals().weak_text_color();
+                                    Self::right_aligned(
+                                        ui,
+                                        egui::Label::new(
+                                            egui::RichText::new(size_text).color(damaged_color),
+                                        ),
+                                    );
+                                });
+                            }
+                            row.col(|ui| {
+                                let damaged_color = ui.visuals().weak_text_color();
+                                let response = ui.add(
+                                    egui::Label::new(
+                                        egui::RichText::new(description_text).color(damaged_color),
+                                    )
+                                    .truncate(),
+                                );
+                                Self::description_tooltip(response, metadata.as_deref());
+                            });
                         } else {
                             row.col(|ui| {
+                                favorite_clicked = Self::favorite_star(ui, is_favorite);
                                 ui.add(egui::Label::new(label_text).truncate().selectable(false));
                             });
                             row.col(|ui| {
                                 ui.add(
+                                    egui::Label::new(modified_text).truncate().selectable(false),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.add(
+                                    egui::Label::new(channels_text).truncate().selectable(false),
+                                );
+                            });
+                            if show_points {
+                                row.col(|ui| {
+                                    Self::right_aligned(
+                                        ui,
+                                        egui::Label::new(points_text).selectable(false),
+                                    );
+                                });
+                            }
+                            if show_size {
+                                row.col(|ui| {
+                                    Self::right_aligned(
+                                        ui,
+                                        egui::Label::new(size_text).selectable(false),
+                                    );
+                                });
+                            }
+                            row.col(|ui| {
+                                let response = ui.add(
                                     egui::Label::new(description_text)
                                         .truncate()
                                         .selectable(false),
                                 );
+                                Self::description_tooltip(response, metadata.as_deref());
                             });
                         }
 
-                        // Handle row click
-                        if row.response().clicked() {
-                            self.selected_checksum = Some(wave.checksum);
+                        row.response().context_menu(|ui| {
+                            Self::row_context_menu(ui, &abs_path, &rel_path);
+                        });
+
+                        // Handle star and row clicks
+                        if favorite_clicked {
+                            self.toggle_favorite(checksum, &library_path);
+                        } else if row.response().clicked() {
+                            let modifiers = row.response().ctx.input(|i| i.modifiers);
+                            let shift = modifiers.shift;
+                            if modifiers.command {
+                                // Ctrl-click marks files for export, leaving the selection alone
+                                self.on_mark_click(checksum, shift);
+                            } else if !shift {
+                                self.selected_checksum = Some(checksum);
+                            } else if compare_checksum == Some(checksum)
+                                || selected_checksum == Some(checksum)
+                            {
+                                // Shift-click on a compared file ends the comparison
+                                self.compare_checksum = None;
+                            } else {
+                                self.compare_checksum = Some(checksum);
+                            }
                         }
                     });
                 });
         });
     }
 
+    /// Adds a label to a table cell, aligned to its right edge.
+    fn right_aligned(ui: &mut egui::Ui, label: egui::Label) {
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.add(label.truncate());
+        });
+    }
+
+    /// Renders the right-click menu of a table row.
+    fn row_context_menu(ui: &mut egui::Ui, path: &Path, relative_path: &Path) {
+        if ui.button(tr(Msg::CopyAbsolutePath)).clicked() {
+            ui.ctx().copy_text(path.display().to_string());
+            ui.close();
+        }
+        if ui.button(tr(Msg::CopyRelativePath)).clicked() {
+            ui.ctx().copy_text(relative_path.display().to_string());
+            ui.close();
+        }
+        if ui.button(tr(Msg::OpenContainingFolder)).clicked() {
+            if let Some(folder) = path.parent() {
+                Self::open_folder(folder.to_path_buf());
+            }
+            ui.close();
+        }
+    }
+
+    /// Opens a folder in the file manager with `xdg-open`.
+    fn open_folder(folder: PathBuf) {
+        // Wait for xdg-open on a thread so it doesn't linger as a zombie
+        thread::spawn(
+            move || match Command::new("xdg-open").arg(&folder).status() {
+                Ok(status) if status.success() => {}
+                Ok(status) => warn!("xdg-open {} exited with {}", folder.display(), status),
+                Err(e) => error!("Failed to run xdg-open: {}", e),
+            },
+        );
+    }
+
+    /// Moves the selection with the Up and Down keys and creates the device
+    /// with Enter. Keys are left alone while a text field has focus or a
+    /// modal dialog is open.
+    fn handle_file_keys(&mut self, ui: &egui::Ui) {
+        if self.modal.is_some() || self.config_preview.is_some() || ui.ctx().wants_keyboard_input()
+        {
+            return;
+        }
+        let (up, down, enter) = ui.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            )
+        });
+        if up || down {
+            let selected_checksum = self.selected_checksum;
+            let files = self.get_filtered_wav_files();
+            let last = files.len().saturating_sub(1);
+            let row = match selected_checksum.and_then(|c| files.index_of_checksum(c)) {
+                None => 0,
+                Some(row) if down => (row + 1).min(last),
+                Some(row) => row.saturating_sub(1),
+            };
+            if let Some(checksum) = files.get_by_index(row).map(|wave| wave.checksum) {
+                self.selected_checksum = Some(checksum);
+                self.scroll_to_row = Some(row);
+            }
+        }
+        if enter && self.selected_checksum.is_some() {
+            self.on_write_config_click();
+        }
+    }
+
+    /// Marks or unmarks a file for bulk export. With `range`, marks all
+    /// visible files between the last marked file and this one instead.
+    fn on_mark_click(&mut self, checksum: u128, range: bool) {
+        // Damaged files have no checksum to tell them apart
+        if checksum == 0 {
+            return;
+        }
+        let anchor = self.last_marked.filter(|_| range);
+        self.last_marked = Some(checksum);
+        if let Some(anchor) = anchor {
+            let filtered = self.get_filtered_wav_files();
+            if let (Some(a), Some(b)) = (
+                filtered.index_of_checksum(anchor),
+                filtered.index_of_checksum(checksum),
+            ) {
+                let checksums: Vec<u128> = (a.min(b)..=a.max(b))
+                    .filter_map(|i| filtered.get_by_index(i))
+                    .map(|w| w.checksum)
+                    .filter(|&c| c != 0)
+                    .collect();
+                self.marked_checksums.extend(checksums);
+                return;
+            }
+        }
+        if !self.marked_checksums.remove(&checksum) {
+            self.marked_checksums.insert(checksum);
+        }
+    }
+
+    /// Renders the marked files counter with the export and clear buttons.
+    /// Shows nothing while no file is marked.
+    fn render_marked_bar(&mut self, ui: &mut egui::Ui) {
+        if self.marked_checksums.is_empty() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label(tr(Msg::MarkedFiles).replace("{}", &self.marked_checksums.len().to_string()));
+            if ui.button(tr(Msg::ExportMarked)).clicked() {
+                self.on_export_marked_click();
+            }
+            if ui.button(tr(Msg::ClearMarks)).clicked() {
+                self.marked_checksums.clear();
+                self.last_marked = None;
+            }
+        });
+    }
+
+    /// Asks for a file name and exports all scanned files to JSON.
+    fn on_export_list_click(&mut self) {
+        let Some(path) = FileDialog::new()
+            .set_title(tr(Msg::ExportListDialogTitle))
+            .set_file_name("irate_goose_files.json")
+            .add_filter("JSON", &["json"])
+            .save_file()
+        else {
+            return;
+        };
+        let result = fs::File::create(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))
+            .and_then(|file| write_files_json(file, self.all_wav_index.iter()));
+        match result {
+            Ok(()) => info!(
+                "Exported {} files to {}",
+                self.all_wav_index.len(),
+                path.display()
+            ),
+            Err(e) => self.show_modal(
+                tr(Msg::ExportErrorHeader),
+                &tr(Msg::ExportErrorMessage).replace("{}", &format!("{e:#}")),
+            ),
+        }
+    }
+
+    /// Asks for a file name and exports the marked files to CSV, in list order.
+    fn on_export_marked_click(&mut self) {
+        let Some(path) = FileDialog::new()
+            .set_title(tr(Msg::ExportDialogTitle))
+            .set_file_name("irate_goose_files.csv")
+            .add_filter("CSV", &["csv"])
+            .save_file()
+        else {
+            return;
+        };
+        let marked: Vec<&WavFileData> = self
+            .all_wav_index
+            .iter()
+            .filter(|w| self.marked_checksums.contains(&w.checksum))
+            .collect();
+        let count = marked.len();
+        let result = fs::File::create(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))
+            .and_then(|file| write_files_csv(file, marked));
+        match result {
+            Ok(()) => info!("Exported {} files to {}", count, path.display()),
+            Err(e) => self.show_modal(
+                tr(Msg::ExportErrorHeader),
+                &tr(Msg::ExportErrorMessage).replace("{}", &format!("{e:#}")),
+            ),
+        }
+    }
+
+    /// Renders a clickable column header that sorts the table by `column`.
+    /// Clicking the active column again reverses the direction.
+    fn sortable_header(&mut self, ui: &mut egui::Ui, title: &str, column: SortColumn) {
+        let arrow = match (self.sort_column == Some(column), self.sort_descending) {
+            (false, _) => "",
+            (true, false) => " ⏶",
+            (true, true) => " ⏷",
+        };
+        let text = egui::RichText::new(format!("{title}{arrow}")).heading();
+        if ui.add(egui::Button::new(text).frame(false)).clicked() {
+            if self.sort_column == Some(column) {
+                self.sort_descending = !self.sort_descending;
+            } else {
+                self.sort_column = Some(column);
+                // Newest files are the interesting ones
+                self.sort_descending = column == SortColumn::Modified;
+            }
+            self.filtered_wav_index = None;
+            {
+                let mut settings = self.settings.borrow_mut();
+                settings.sort_column = self.sort_column;
+                settings.sort_descending = self.sort_descending;
+            }
+            self.write_settings();
+        }
+    }
+
+    /// Renders the favorite star toggle of a table row.
+    /// Returns true if the star was clicked.
+    fn favorite_star(ui: &mut egui::Ui, is_favorite: bool) -> bool {
+        let (glyph, hint) = if is_favorite {
+            ("★", tr(Msg::RemoveFavorite))
+        } else {
+            ("☆", tr(Msg::AddFavorite))
+        };
+        ui.add(egui::Button::new(glyph).frame(false).small())
+            .on_hover_text(hint)
+            .clicked()
+    }
+
+    /// Toggles the favorite mark of a file, persists it and re-sorts the list.
+    fn toggle_favorite(&mut self, checksum: u128, relative_path: &Path) {
+        self.settings
+            .borrow_mut()
+            .toggle_favorite(checksum, relative_path);
+        self.write_settings();
+        self.filtered_wav_index = None;
+    }
+
     /// Renders the file list table with two columns: "Files" and "Description".
     pub(crate) fn render_file_list_and_metadata(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Located IR Files");
+        ui.heading(tr(Msg::LocatedIrFiles));
 
         // Radio buttons for sample rate filter
         ui.horizontal(|ui| {
-            ui.label("Sample rate:");
+            ui.label(tr(Msg::SampleRateLabel));
             let old_filter = self.sample_rate_filter;
-            ui.radio_value(
-                &mut self.sample_rate_filter,
+            for rate in [
                 WaveSampleRate::F48000,
-                "48000",
-            );
-            ui.radio_value(
-                &mut self.sample_rate_filter,
                 WaveSampleRate::F44100,
-                "44100",
-            );
+                WaveSampleRate::F96000,
+                WaveSampleRate::F88200,
+                WaveSampleRate::F176400,
+                WaveSampleRate::F192000,
+            ] {
+                ui.radio_value(
+                    &mut self.sample_rate_filter,
+                    rate,
+                    Self::sample_rate_text(rate),
+                );
+            }
             ui.radio_value(
                 &mut self.sample_rate_filter,
-                WaveSampleRate::F96000,
-                "96000",
+                WaveSampleRate::Unknown,
+                tr(Msg::SampleRateAll),
             );
-            ui.radio_value(&mut self.sample_rate_filter, WaveSampleRate::Unknown, "All");
 
             // Check if filter changed
             if old_filter != self.sample_rate_filter {
                 // Invalidate cached filtered items
                 self.filtered_wav_index = None;
+                self.settings.borrow_mut().sample_rate_filter = self.sample_rate_filter.hz();
+                self.write_settings();
             }
         });
 
+        // HRTF family filter
+        if !self.hrtf_families.is_empty() {
+            ui.horizontal(|ui| {
+                ui.label(tr(Msg::FamilyLabel));
+                let old_family = self.family_filter.clone();
+                let selected_text = self
+                    .family_filter
+                    .clone()
+                    .unwrap_or_else(|| tr(Msg::AllFamilies).to_string());
+                egui::ComboBox::from_id_salt("family_filter")
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.family_filter, None, tr(Msg::AllFamilies));
+                        for family in &self.hrtf_families {
+                            ui.selectable_value(
+                                &mut self.family_filter,
+                                Some(family.clone()),
+                                family,
+                            );
+                        }
+                    });
+                if old_family != self.family_filter {
+                    self.filtered_wav_index = None;
+                }
+            });
+        }
+
         // Search field
         ui.horizontal(|ui| {
             let old_search = self.search_text.clone();
-            ui.add(
-                egui::TextEdit::singleline(&mut self.search_text).hint_text("Search IR files..."),
-            );
-            if ui.button("Clear").clicked() {
+            let invalid_regex = self.search_regex
+                && !self.search_text.is_empty()
+                && self.compiled_search_regex().is_none();
+            ui.scope(|ui| {
+                if invalid_regex {
+                    // Red border tells the pattern doesn't compile
+                    let stroke = egui::Stroke::new(1.0, ui.visuals().error_fg_color);
+                    let visuals = ui.visuals_mut();
+                    visuals.widgets.inactive.bg_stroke = stroke;
+                    visuals.widgets.hovered.bg_stroke = stroke;
+                    visuals.selection.stroke = stroke;
+                }
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.search_text)
+                        .hint_text(tr(Msg::SearchHint)),
+                );
+            });
+            if ui.button(tr(Msg::ClearSearch)).clicked() {
                 self.search_text.clear();
             }
+            if ui
+                .checkbox(&mut self.search_regex, tr(Msg::SearchRegex))
+                .on_hover_text(tr(Msg::SearchRegexHint))
+                .changed()
+            {
+                self.filtered_wav_index = None;
+            }
             // If search text changed, invalidate cached filtered items
             if old_search != self.search_text {
                 self.filtered_wav_index = None;
             }
+            if ui
+                .checkbox(&mut self.search_descriptions, tr(Msg::SearchDescriptions))
+                .changed()
+            {
+                self.filtered_wav_index = None;
+            }
+            if ui
+                .checkbox(&mut self.described_only, tr(Msg::DescribedOnly))
+                .changed()
+            {
+                self.filtered_wav_index = None;
+            }
+            if ui
+                .checkbox(&mut self.favorites_only, tr(Msg::FavoritesOnly))
+                .changed()
+            {
+                self.filtered_wav_index = None;
+            }
+            if ui
+                .add_enabled(
+                    !self.all_wav_index.is_empty(),
+                    egui::Button::new(tr(Msg::ExportList)),
+                )
+                .clicked()
+            {
+                self.on_export_list_click();
+            }
         });
 
-        if self.all_wav_index.len() == 0 {
+        if self.all_wav_index.is_empty() {
             ui.label("");
-            ui.label("Irate Goose needs IR (Impulse Response) files to create a virtual surround sound effect.");
-            ui.label("No IR files were found in the selected directory.");
-            ui.label("Look at the Help tab for ways to obtain IR files.");
+            ui.label(tr(Msg::NoFilesIntro));
+            ui.label(tr(Msg::NoFilesFound));
+            ui.label(tr(Msg::NoFilesHelpHint));
             ui.label("");
-            ui.hyperlink_to(" Irate Goose GitHub", "https://github.com/Barafu/IrateGoose");
-        } else if self.get_filtered_wav_files().len() == 0 {
+            ui.hyperlink_to(tr(Msg::GitHubLink), "https://github.com/Barafu/IrateGoose");
+        } else if self.get_filtered_wav_files().is_empty() {
             ui.label("");
-            ui.label("No .wav files matching this filter were found in the directory.");
+            ui.label(tr(Msg::NoFilesMatchFilter));
         } else {
+            self.render_marked_bar(ui);
+            self.handle_file_keys(ui);
             self.render_file_table(ui);
+            self.persist_comparison();
             // HRTF metadata frame (detailed view for selected file)
             let frame = egui::Frame::group(ui.style());
             frame.show(ui, |ui| {
@@ -263,23 +885,354 @@ impl<'a> AppGUI<'a> {
                     .max_height(Self::METADATA_FRAME_HEIGHT)
                     .auto_shrink(false)
                     .show(ui, |ui| {
-                        if let Some(metadata) = self.selected_metadata() {
-                            ui.heading(&metadata.hrtf);
-                            ui.label(Self::truncate_description(&metadata.description));
-                            if !metadata.source.is_empty() {
-                                ui.label(format!("Source: {}", metadata.source));
-                            }
-                            if !metadata.credits.is_empty() {
-                                ui.label(format!("By: {}", metadata.credits));
-                            }
-                        } else {
-                            ui.label("No description for the selected files.");
+                        if !self.render_comparison(ui) {
+                            self.render_selected_metadata(ui);
                         }
                     });
             });
         }
     }
 
+    /// Renders the editable note of the selected file. Edits are saved at once.
+    fn render_note_editor(&mut self, ui: &mut egui::Ui, checksum: u128) {
+        let Some(library_path) = self
+            .find_wav_by_checksum(checksum)
+            .map(|w| w.library_path().to_path_buf())
+        else {
+            return;
+        };
+        let Some(notes) = self.notes.as_mut() else {
+            return;
+        };
+        // Load the note when the selection changes
+        if self.note_checksum != Some(checksum) {
+            self.note_text = notes
+                .get(checksum, &library_path)
+                .unwrap_or_default()
+                .to_string();
+            self.note_checksum = Some(checksum);
+        }
+
+        let mut cleared = false;
+        ui.horizontal(|ui| {
+            ui.label(tr(Msg::NotesLabel));
+            let has_note = !self.note_text.is_empty();
+            if ui
+                .add_enabled(has_note, egui::Button::new(tr(Msg::ClearNote)).small())
+                .clicked()
+            {
+                self.note_text.clear();
+                cleared = true;
+            }
+        });
+        let response = ui.add(
+            egui::TextEdit::multiline(&mut self.note_text)
+                .hint_text(tr(Msg::NotesHint))
+                .desired_rows(2)
+                .desired_width(f32::INFINITY),
+        );
+        if response.changed() || cleared {
+            notes.set(checksum, &library_path, &self.note_text);
+            if let Err(e) = notes.save() {
+                error!("Failed to save notes: {:#}", e);
+            }
+        }
+    }
+
+    /// Renders metadata and checksum of the selected file.
+    fn render_selected_metadata(&mut self, ui: &mut egui::Ui) {
+        let expanded =
+            self.selected_checksum.is_some() && self.description_expanded == self.selected_checksum;
+        let mut toggle_clicked = false;
+        if let Some(metadata) = self.selected_metadata() {
+            ui.heading(&metadata.hrtf);
+            if expanded {
+                ui.label(&metadata.description);
+            } else {
+                ui.label(Self::truncate_description(&metadata.description));
+            }
+            if Self::is_description_long(&metadata.description) {
+                let toggle_text = match expanded {
+                    true => tr(Msg::ShowLess),
+                    false => tr(Msg::ShowMore),
+                };
+                toggle_clicked = ui.link(toggle_text).clicked();
+            }
+            if !metadata.source.is_empty() {
+                ui.label(format!("{} {}", tr(Msg::SourcePrefix), metadata.source));
+            }
+            if !metadata.credits.is_empty() {
+                ui.label(format!("{} {}", tr(Msg::CreditsPrefix), metadata.credits));
+            }
+        } else {
+            ui.label(tr(Msg::NoDescription));
+        }
+        if toggle_clicked {
+            // Another selection collapses the description again
+            self.description_expanded = match expanded {
+                true => None,
+                false => self.selected_checksum,
+            };
+        }
+        if let Some(checksum) = self.selected_checksum {
+            ui.horizontal(|ui| {
+                ui.label(tr(Msg::ChecksumLabel));
+                if checksum == 0 {
+                    // Damaged files have no checksum
+                    ui.label("—");
+                } else {
+                    let hex = format_checksum(checksum);
+                    ui.monospace(&hex);
+                    if ui.small_button(tr(Msg::CopyChecksum)).clicked() {
+                        ui.ctx().copy_text(hex);
+                    }
+                }
+            });
+            if let Some(wave) = self.find_wav_by_checksum(checksum)
+                && wave.bit_depth > 0
+            {
+                let format = tr(Msg::FormatValue)
+                    .replace("{rate}", &Self::sample_rate_text(wave.sample_rate))
+                    .replace("{bits}", &wave.bit_depth.to_string());
+                ui.label(format!("{} {}", tr(Msg::FormatLabel), format));
+            }
+            if let Some(wave) = self.find_wav_by_checksum(checksum)
+                && wave.duration_ms > 0
+            {
+                ui.label(format!(
+                    "{} {}",
+                    tr(Msg::CompareDuration),
+                    Self::duration_text(wave.duration_ms)
+                ));
+            }
+            if let Some(wave) = self.find_wav_by_checksum(checksum)
+                && wave.size_bytes > 0
+            {
+                ui.label(format!(
+                    "{} {}",
+                    tr(Msg::SizeLabel),
+                    format_size(wave.size_bytes)
+                ));
+            }
+            self.render_play_test(ui, checksum);
+            // Informational: the installed copy keeps only the file name
+            if let Some(wave) = self.find_wav_by_checksum(checksum)
+                && self.all_wav_index.has_file_name_collision(&wave.path)
+            {
+                let name = wave
+                    .path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    tr(Msg::FileNameCollision).replace("{}", &name),
+                );
+            }
+            self.render_rate_mismatch(ui, checksum);
+            self.render_note_editor(ui, checksum);
+        }
+    }
+
+    /// Renders the "Play test" button of the selected file. It is disabled
+    /// while a preview plays and for files the preview can't handle.
+    fn render_play_test(&mut self, ui: &mut egui::Ui, checksum: u128) {
+        let Some(wave) = self.find_wav_by_checksum(checksum) else {
+            return;
+        };
+        let path = wave.path.clone();
+        let supported = wave.sample_rate != WaveSampleRate::Damaged
+            && preview::supports_channels(wave.channels);
+        let playing = self.preview.as_ref().is_some_and(|t| !t.is_finished());
+        let button = ui.add_enabled(supported && !playing, egui::Button::new(tr(Msg::PlayTest)));
+        let button = if supported {
+            button.on_hover_text(tr(Msg::PlayTestHint))
+        } else {
+            button.on_disabled_hover_text(tr(Msg::PlayTestUnsupported))
+        };
+        if button.clicked() {
+            info!("Playing test preview of {}", path.display());
+            self.preview = Some(thread::spawn(move || {
+                if let Err(e) = preview::play(&path) {
+                    error!("Test preview failed: {:#}", e);
+                }
+            }));
+        }
+    }
+
+    /// Warns when the sample rate of the file differs from the PipeWire graph.
+    fn render_rate_mismatch(&mut self, ui: &mut egui::Ui, checksum: u128) {
+        let Some(graph_rate) = self.graph_sample_rate else {
+            return;
+        };
+        let Some(file_rate) = self
+            .find_wav_by_checksum(checksum)
+            .and_then(|w| w.sample_rate.hz())
+        else {
+            return;
+        };
+        if file_rate == graph_rate {
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                tr(Msg::SampleRateMismatch)
+                    .replace("{file}", &file_rate.to_string())
+                    .replace("{graph}", &graph_rate.to_string()),
+            );
+            if ui.link(tr(Msg::SampleRateMismatchHelp)).clicked() {
+                self.selected_tab = Tab::Help;
+            }
+        });
+    }
+
+    /// Renders a side by side comparison of the selected and the compare file,
+    /// highlighting the rows that differ. Returns false if there is nothing to compare.
+    fn render_comparison(&mut self, ui: &mut egui::Ui) -> bool {
+        let (Some(selected), Some(compare)) = (self.selected_checksum, self.compare_checksum)
+        else {
+            return false;
+        };
+        let Some((a, b)) = self.all_wav_index.get_pair(selected, compare) else {
+            return false;
+        };
+
+        let points = |w: &WavFileData| {
+            w.metadata
+                .as_ref()
+                .and_then(|m| m.points)
+                .map(|p| p.to_string())
+                .unwrap_or_default()
+        };
+        let description = |w: &WavFileData| {
+            w.metadata
+                .as_ref()
+                .map(|m| {
+                    m.description
+                        .lines()
+                        .next()
+                        .unwrap_or("")
+                        .trim()
+                        .to_string()
+                })
+                .unwrap_or_default()
+        };
+        let rows = [
+            (
+                tr(Msg::ColumnFiles),
+                a.relative_path.to_string_lossy().to_string(),
+                b.relative_path.to_string_lossy().to_string(),
+            ),
+            (
+                tr(Msg::SampleRateLabel),
+                Self::sample_rate_text(a.sample_rate),
+                Self::sample_rate_text(b.sample_rate),
+            ),
+            (
+                tr(Msg::CompareChannels),
+                Self::channels_text(a.channels),
+                Self::channels_text(b.channels),
+            ),
+            (
+                tr(Msg::CompareDuration),
+                Self::duration_text(a.duration_ms),
+                Self::duration_text(b.duration_ms),
+            ),
+            (tr(Msg::ComparePoints), points(a), points(b)),
+            (tr(Msg::ColumnDescription), description(a), description(b)),
+        ];
+
+        ui.horizontal(|ui| {
+            ui.heading(tr(Msg::CompareHeading));
+            if ui.small_button(tr(Msg::ClearCompare)).clicked() {
+                self.compare_checksum = None;
+            }
+        });
+
+        let highlight = ui.visuals().warn_fg_color;
+        egui::Grid::new("compare_grid")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for (label, left, right) in rows {
+                    ui.label(label);
+                    if left == right {
+                        ui.label(left);
+                        ui.label(right);
+                    } else {
+                        ui.label(egui::RichText::new(left).color(highlight));
+                        ui.label(egui::RichText::new(right).color(highlight));
+                    }
+                    ui.end_row();
+                }
+            });
+        true
+    }
+
+    /// Restores the compared pair saved in settings. If one of the files
+    /// is gone after a rescan, the comparison is dropped.
+    fn restore_comparison(&mut self) {
+        let saved = self.settings.borrow().compared_pair;
+        self.compare_checksum = None;
+        let Some(pair) = saved else {
+            return;
+        };
+        if self
+            .all_wav_index
+            .get_pair(pair.first, pair.second)
+            .is_some()
+        {
+            self.selected_checksum = Some(pair.first);
+            self.compare_checksum = Some(pair.second);
+        } else {
+            info!("A compared file is no longer present, ending the comparison");
+            self.settings.borrow_mut().compared_pair = None;
+            self.write_settings();
+        }
+    }
+
+    /// Saves the current comparison to settings when it has changed.
+    fn persist_comparison(&mut self) {
+        let current = match (self.selected_checksum, self.compare_checksum) {
+            (Some(first), Some(second)) => Some(ComparedPair { first, second }),
+            _ => None,
+        };
+        if self.settings.borrow().compared_pair != current {
+            self.settings.borrow_mut().compared_pair = current;
+            self.write_settings();
+        }
+    }
+
+    /// Text of a sample rate for the filter and the comparison view.
+    fn sample_rate_text(sample_rate: WaveSampleRate) -> String {
+        match sample_rate {
+            WaveSampleRate::Unknown => tr(Msg::SampleRateOther).to_string(),
+            WaveSampleRate::Damaged => tr(Msg::DamagedTag).to_string(),
+            rate => rate.hz().map(|hz| hz.to_string()).unwrap_or_default(),
+        }
+    }
+
+    /// Text of a channel count for the table and the comparison view,
+    /// a dash for damaged files.
+    fn channels_text(channels: u16) -> String {
+        if channels == 0 {
+            "—".to_string()
+        } else {
+            channels.to_string()
+        }
+    }
+
+    /// Text of an audio duration for the metadata and comparison views,
+    /// empty if unknown.
+    fn duration_text(duration_ms: u32) -> String {
+        if duration_ms == 0 {
+            String::new()
+        } else {
+            tr(Msg::DurationMs).replace("{}", &duration_ms.to_string())
+        }
+    }
+
     /// Handles the "Rescan" button click for WAV directory.
     pub(crate) fn on_rescan_click(&mut self) {
         let dir_text = self.directory_text.trim().to_string();
@@ -287,19 +1240,35 @@ impl<'a> AppGUI<'a> {
             return;
         }
 
-        let path = PathBuf::from(&dir_text);
+        let mut path = PathBuf::from(&dir_text);
 
         // Check if directory exists and is a directory
         if !path.exists() {
             self.show_modal(
-                "Directory Not Found",
-                "The specified directory does not exist.",
+                tr(Msg::DirectoryNotFoundHeader),
+                tr(Msg::DirectoryNotFoundMessage),
             );
             return;
         }
 
+        // A single WAV file: scan its parent directory and pre-select the file
+        let mut file_to_select = None;
+        if path.is_file() {
+            if !FileManager::is_ir_file(&path) {
+                self.show_modal(tr(Msg::NotAWavFileHeader), tr(Msg::NotAWavFileMessage));
+                return;
+            }
+            let Some(parent) = path.parent().map(Path::to_path_buf) else {
+                self.show_modal(tr(Msg::NotADirectoryHeader), tr(Msg::NotADirectoryMessage));
+                return;
+            };
+            file_to_select = Some(path);
+            path = parent;
+            self.directory_text = path.to_string_lossy().to_string();
+        }
+
         if !path.is_dir() {
-            self.show_modal("Not a Directory", "The specified path is not a directory.");
+            self.show_modal(tr(Msg::NotADirectoryHeader), tr(Msg::NotADirectoryMessage));
             return;
         }
 
@@ -311,45 +1280,67 @@ impl<'a> AppGUI<'a> {
         // However, safe_rescan expects wav_directory to already be set.
         self.settings.borrow_mut().set_wav_directory(Some(path));
 
-        match self.safe_rescan() {
-            Ok(_) => {
-                info!(
-                    "Scanned IR directory: {} ({} files found)",
-                    dir_text,
-                    self.all_wav_index.len()
-                );
-            }
-            Err(e) => {
-                self.show_modal(
-                    "Rescan Error",
-                    &format!("Failed to rescan directory: {}", e),
-                );
+        self.safe_rescan(file_to_select);
+    }
+
+    /// Selects the scanned entry matching the given file, identified by its checksum.
+    fn select_wav_file(&mut self, file: &Path) {
+        let checksum = FileManager::file_checksum(file)
+            .filter(|c| self.all_wav_index.get_by_checksum(*c).is_some());
+        match checksum {
+            Some(checksum) => {
+                self.selected_checksum = Some(checksum);
+                self.selected_tab = Tab::Files;
             }
+            None => warn!("Could not select {}: not found in scan", file.display()),
+        }
+    }
+
+    /// Shows a modal describing a failed rescan. The previously loaded
+    /// file list stays intact. If the directory itself could not be
+    /// accessed, the user is offered to pick another one.
+    pub(crate) fn report_rescan_error(&mut self, e: &anyhow::Error) {
+        if FileManager::is_access_error(e) {
+            self.show_modal_with_action(
+                tr(Msg::DirectoryNotAccessibleHeader),
+                &tr(Msg::DirectoryNotAccessibleMessage).replace("{}", &e.to_string()),
+                ModalAction::PickDirectory,
+            );
+        } else {
+            self.show_modal(
+                tr(Msg::RescanErrorHeader),
+                &tr(Msg::RescanErrorMessage).replace("{}", &e.to_string()),
+            );
         }
     }
 
     /// Performs a safe rescan. The purpose is to make sure that if
     /// application crashes during rescan, then the faulty directory
     /// is not saved into settings and will not be scanned on restart.
-    pub(crate) fn safe_rescan(&mut self) -> anyhow::Result<()> {
+    /// The scan runs in the background and replaces any scan in flight;
+    /// `poll_scan` picks up its result.
+    pub(crate) fn safe_rescan(&mut self, file_to_select: Option<PathBuf>) {
         // Clean filtered_items, just to be sure
         self.filtered_wav_index = None;
+        // A scan of the previous directory is stale now
+        if self.scan.is_some() {
+            self.on_cancel_scan_click();
+        }
 
         // Auto-descend: if the selected directory has no WAV files at root level
         // and exactly one subfolder, descend into that subfolder
         {
             let dir = self.settings.borrow().get_wav_directory();
-            if let Some(ref dir_path) = dir {
-                if dir_path.is_dir()
-                    && !Self::dir_has_wav_files(dir_path)
-                    && Self::subdir_count(dir_path) == 1
-                {
-                    let new_dir = Self::find_single_subdir(dir_path);
-                    self.directory_text = new_dir.to_string_lossy().to_string();
-                    self.settings.borrow_mut().set_wav_directory(Some(new_dir));
-                    self.write_settings();
-                    return self.safe_rescan();
-                }
+            if let Some(ref dir_path) = dir
+                && dir_path.is_dir()
+                && !Self::dir_has_wav_files(dir_path)
+                && Self::subdir_count(dir_path) == 1
+            {
+                let new_dir = Self::find_single_subdir(dir_path);
+                self.directory_text = new_dir.to_string_lossy().to_string();
+                self.settings.borrow_mut().set_wav_directory(Some(new_dir));
+                self.write_settings();
+                return self.safe_rescan(file_to_select);
             }
         }
 
@@ -357,23 +1348,170 @@ impl<'a> AppGUI<'a> {
         let original_path = self.settings.borrow().get_wav_directory();
         if original_path.is_none() {
             self.all_wav_index.clear();
-            return Ok(());
+            self.duplicates.clear();
+            self.update_hrtf_families();
+            return;
         }
 
         // If settings.active_wav_directory is used, simply scan
-        if !self.settings.borrow().is_wav_directory_set() {
-            self.all_wav_index = self.file_manager.rescan_configured_directory()?;
-        } else {
+        let persist_directory = self.settings.borrow().is_wav_directory_set();
+        if persist_directory {
             // Temporarily set wav_directory to None and persist
             self.settings.borrow_mut().set_wav_directory(None);
             self.write_settings();
 
             // Restore original path in memory (but not persisted yet)
             self.settings.borrow_mut().set_wav_directory(original_path);
+        }
 
-            // Perform the actual scan
-            self.all_wav_index = self.file_manager.rescan_configured_directory()?;
+        self.scan = Some(RunningScan {
+            scan: self.file_manager.start_background_scan(),
+            persist_directory,
+            file_to_select,
+            startup: false,
+        });
+    }
 
+    /// Starts, moves or stops the directory watcher to follow the settings,
+    /// and rescans once it reports a change.
+    pub(crate) fn update_watcher(&mut self, ctx: &egui::Context) {
+        let (wanted, depth, follow_symlinks) = {
+            let settings = self.settings.borrow();
+            let wanted = settings
+                .watch_directory
+                .then(|| settings.get_wav_directory())
+                .flatten()
+                // A single file stands for the directory it lives in
+                .and_then(|dir| match dir.is_file() {
+                    true => dir.parent().map(Path::to_path_buf),
+                    false => Some(dir),
+                });
+            (wanted, settings.scan_depth, settings.follow_symlinks)
+        };
+        let watching = self
+            .watcher
+            .as_ref()
+            .map(|w| (w.dir(), w.depth(), w.follow_symlinks()));
+        if watching != wanted.as_deref().map(|dir| (dir, depth, follow_symlinks)) {
+            self.watcher = wanted.map(|dir| DirWatcher::new(dir, depth, follow_symlinks));
+        }
+        let Some(watcher) = &self.watcher else {
+            return;
+        };
+        if watcher.take_change() {
+            info!("IR directory changed, rescanning");
+            self.safe_rescan(None);
+        }
+        ctx.request_repaint_after(Self::WATCH_CHECK_INTERVAL);
+    }
+
+    /// Finds the groups of identical files in the scan. The file with the
+    /// shortest relative path represents its group.
+    fn update_duplicates(&mut self) {
+        let index = &self.all_wav_index;
+        self.duplicates = index
+            .duplicate_groups()
+            .into_iter()
+            .filter_map(|group| {
+                let kept = group
+                    .iter()
+                    .filter_map(|&i| index.get_by_index(i))
+                    .min_by_key(|wave| wave.relative_path.as_os_str().len())?;
+                Some((kept.checksum, kept.relative_path.clone()))
+            })
+            .collect();
+    }
+
+    /// Collects the HRTF families of the scanned files for the family filter.
+    /// A chosen family that is gone after the scan shows all files again.
+    fn update_hrtf_families(&mut self) {
+        let families: BTreeSet<&str> = self
+            .all_wav_index
+            .iter()
+            .filter_map(|wave| wave.metadata.as_ref())
+            .map(|m| m.hrtf.as_str())
+            .filter(|hrtf| !hrtf.is_empty())
+            .collect();
+        self.hrtf_families = families.into_iter().map(String::from).collect();
+        if let Some(family) = &self.family_filter
+            && !self.hrtf_families.contains(family)
+        {
+            self.family_filter = None;
+        }
+    }
+
+    /// Stops the scan in flight. The files loaded before it stay listed.
+    pub(crate) fn on_cancel_scan_click(&mut self) {
+        if let Some(running) = self.scan.take() {
+            running.scan.cancel();
+            info!("Scan cancelled");
+            self.restore_persisted_directory(&running);
+        }
+    }
+
+    /// Writes back the directory `safe_rescan` cleared on disk while the
+    /// scan runs. A scan that didn't finish didn't crash on the directory,
+    /// so it stays configured.
+    fn restore_persisted_directory(&mut self, running: &RunningScan) {
+        if running.persist_directory {
+            self.write_settings();
+        }
+    }
+
+    /// Stops the scan in flight when the app exits, keeping the directory.
+    pub(crate) fn abandon_scan(&mut self) {
+        if let Some(running) = self.scan.take() {
+            running.scan.cancel();
+            self.restore_persisted_directory(&running);
+        }
+    }
+
+    /// Checks the scan in flight and applies its result once it is over.
+    pub(crate) fn poll_scan(&mut self, ctx: &egui::Context) {
+        let Some(running) = &mut self.scan else {
+            return;
+        };
+        let Some(result) = running.scan.poll() else {
+            // Keep the progress bar moving
+            ctx.request_repaint_after(Self::SCAN_POLL_INTERVAL);
+            return;
+        };
+        if let Some(running) = self.scan.take() {
+            self.on_scan_finished(running, result);
+        }
+    }
+
+    /// Swaps in the scanned files, or reports why the scan failed.
+    fn on_scan_finished(&mut self, running: RunningScan, result: anyhow::Result<ScanOutput>) {
+        let output = match result {
+            Ok(output) => output,
+            Err(e) => {
+                error!("Could not rescan wav directory. Reason: {}", e);
+                self.report_rescan_error(&e);
+                return;
+            }
+        };
+        self.filtered_wav_index = None;
+        self.all_wav_index = self.file_manager.finish_scan(output);
+        // An undo made for the files of another directory would be confusing
+        if self
+            .undo
+            .as_ref()
+            .is_some_and(|undo| undo.directory != self.settings.borrow().get_wav_directory())
+        {
+            self.undo = None;
+        }
+        self.update_duplicates();
+        self.update_hrtf_families();
+        info!(
+            "Scanned IR directory: {} ({} files found)",
+            self.directory_text,
+            self.all_wav_index.len()
+        );
+        let scanned_dir = self.settings.borrow().get_wav_directory();
+        let recent_changed =
+            scanned_dir.is_some_and(|dir| self.settings.borrow_mut().add_recent_directory(dir));
+        if running.persist_directory || recent_changed {
             // Persist the directory after successful scan
             self.write_settings();
         }
@@ -387,18 +1525,19 @@ impl<'a> AppGUI<'a> {
         }
         // Auto‑select the file that matches the installed config (if any)
         self.apply_auto_selection();
+        self.restore_comparison();
+        if let Some(file) = running.file_to_select {
+            self.select_wav_file(&file);
+        }
+        if running.startup {
+            // The startup selection is not a user choice, don't auto-apply it
+            self.auto_apply_seen = self.selected_checksum;
+        }
 
         // If no WAV files found, check if the directory contains .tar.zstd archives
-        if self.all_wav_index.len() == 0 && self.contains_tar_zstd() {
-            self.show_modal(
-                "Archives Found",
-                "No IR files were found in the directory, but .tar.zstd archives were detected.\n\n\
-                You need to unpack the archive files before Irate Goose can use them.\n\
-                Navigate to the project page for instructions on how to obtain and install IR files.",
-            );
+        if self.all_wav_index.is_empty() && self.contains_tar_zstd() {
+            self.show_modal(tr(Msg::ArchivesFoundHeader), tr(Msg::ArchivesFoundMessage));
         }
-
-        Ok(())
     }
 
     /// Checks the configured WAV directory for `.tar.zstd` archives.
@@ -425,19 +1564,14 @@ impl<'a> AppGUI<'a> {
             })
     }
 
-    /// Checks if a directory has any `.wav` files at the root level (non-recursive).
+    /// Checks if a directory has any IR files at the root level (non-recursive).
     fn dir_has_wav_files(dir: &Path) -> bool {
         let Ok(entries) = fs::read_dir(dir) else {
             return false;
         };
-        entries.flatten().any(|entry| {
-            entry.path().is_file()
-                && entry
-                    .path()
-                    .extension()
-                    .and_then(|e| e.to_str())
-                    .is_some_and(|e| e.eq_ignore_ascii_case("wav"))
-        })
+        entries
+            .flatten()
+            .any(|entry| entry.path().is_file() && FileManager::is_ir_file(&entry.path()))
     }
 
     /// Counts immediate subdirectories in a directory.
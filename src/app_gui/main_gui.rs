@@ -1,29 +1,152 @@
+use anyhow::Context;
 use eframe::egui;
 use egui_extras::{Column, TableBuilder};
 use rfd::FileDialog;
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::rc::Rc;
 
-use crate::app_gui::theme::{detect_system_theme, DetectedTheme};
-use crate::config_manager::ConfigManager;
+use crate::app_gui::files::RunningScan;
+use crate::app_gui::profiles::ProfileSwitch;
+use crate::app_gui::strings::{Msg, preflight_error_text, set_language, sound_server_text, tr};
+use crate::app_gui::theme::{DetectedTheme, detect_system_theme, high_contrast_visuals};
+use crate::config_manager::{
+    ConfigManager, DeviceHealth, InstalledDevice, PreflightError, SoundServer,
+};
+use crate::descriptions::Descriptions;
+use crate::diagnostics::build_problem_report;
+use crate::dir_watcher::DirWatcher;
 use crate::file_manager::{FileManager, WavFileData, WaveSampleRate};
-use crate::settings::{AppSettings, DEFAULT_VIRTUAL_DEVICE_NAME};
+use crate::logging::{self, SharedLogBuffer};
+use crate::notes::NotesStore;
+use crate::settings::{
+    AppSettings, AppTheme, ChannelLayout, DEFAULT_RESTART_UNITS, DEFAULT_VIRTUAL_DEVICE_NAME,
+    Language, OUTPUT_GAIN_RANGE, SortColumn, Tab, TableDensity,
+};
 use crate::wav_file_index::WavFileIndex;
 use egui_commonmark::{CommonMarkCache, commonmark_str};
-use log::{error, info, warn};
-use std::sync::{Arc, Mutex};
+use log::{LevelFilter, error, info, warn};
+use regex::Regex;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 const REPOSITORY: &str = env!("CARGO_PKG_REPOSITORY");
 
+/// The last change of the installed config, kept so it can be undone.
+pub(crate) struct ConfigUndo {
+    /// True if the device was written, false if it was removed
+    wrote: bool,
+    /// Config file installed before the change, None if there was none
+    previous: Option<String>,
+    /// IR directory at the time of the change
+    pub(crate) directory: Option<PathBuf>,
+}
+
+/// Additional action offered by the modal dialog next to the "Continue" button.
 #[derive(PartialEq, Eq, Clone, Copy)]
-/// Represents the selected tab in the main window.
-enum Tab {
-    Files,
-    Options,
-    Log,
-    Help,
+pub(crate) enum ModalAction {
+    /// Switch to the Options tab and open the directory picker.
+    PickDirectory,
+    /// Replace all settings with defaults, after confirmation.
+    ResetSettings,
+    /// Remove the virtual device, after confirmation.
+    RemoveDevice,
+    /// Write a config with a file of the wrong channel count.
+    WriteAnyway,
+}
+
+impl ModalAction {
+    /// Text of the button that triggers the action.
+    fn label(self) -> &'static str {
+        match self {
+            ModalAction::PickDirectory => tr(Msg::PickAnotherDirectory),
+            ModalAction::ResetSettings => tr(Msg::ResetSettingsConfirm),
+            ModalAction::RemoveDevice => tr(Msg::RemoveDeviceConfirm),
+            ModalAction::WriteAnyway => tr(Msg::WriteAnyway),
+        }
+    }
+
+    /// Text of the button that closes the modal without the action.
+    fn dismiss_label(self) -> &'static str {
+        match self {
+            ModalAction::PickDirectory => tr(Msg::Continue),
+            ModalAction::ResetSettings | ModalAction::RemoveDevice | ModalAction::WriteAnyway => {
+                tr(Msg::Cancel)
+            }
+        }
+    }
+}
+
+/// An open modal dialog: a header, a message and a button that closes it,
+/// optionally with a second button that confirms an action.
+pub(crate) struct ModalState {
+    header: String,
+    message: String,
+    /// Action of the confirm button, None for a single-button dialog
+    action: Option<ModalAction>,
+    /// State of the "Don't ask again" checkbox of a confirmation
+    dont_ask: bool,
+}
+
+impl ModalState {
+    /// Creates a dialog with only a "Continue" button.
+    pub(crate) fn message(header: &str, message: &str) -> Self {
+        Self {
+            header: header.to_string(),
+            message: message.to_string(),
+            action: None,
+            dont_ask: false,
+        }
+    }
+
+    /// Creates a dialog whose confirm button triggers `action`.
+    pub(crate) fn confirm(header: &str, message: &str, action: ModalAction) -> Self {
+        Self {
+            action: Some(action),
+            ..Self::message(header, message)
+        }
+    }
+
+    /// Draws the dialog. Returns whether it should close and the action
+    /// confirmed by the user, if any.
+    fn show(&mut self, ctx: &egui::Context) -> (bool, Option<ModalAction>) {
+        let mut confirmed = None;
+        let modal = egui::Modal::new(egui::Id::new("message_modal")).show(ctx, |ui| {
+            ui.set_width(300.0);
+
+            // Header
+            ui.heading(&self.header);
+
+            // Message body
+            ui.label(&self.message);
+
+            if self.action == Some(ModalAction::RemoveDevice) {
+                ui.checkbox(&mut self.dont_ask, tr(Msg::DontAskAgain));
+            }
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                // Continue (or Cancel) button
+                let dismiss_label = self
+                    .action
+                    .map_or(tr(Msg::Continue), ModalAction::dismiss_label);
+                if ui.button(dismiss_label).clicked() {
+                    ui.close();
+                }
+                // Confirm button, if any
+                if let Some(action) = self.action
+                    && ui.button(action.label()).clicked()
+                {
+                    confirmed = Some(action);
+                    ui.close();
+                }
+            });
+        });
+        (modal.should_close(), confirmed)
+    }
 }
 
 pub struct AppGUI<'a> {
@@ -33,34 +156,92 @@ pub struct AppGUI<'a> {
     // Manages a collection of WAV files
     pub(crate) file_manager: &'a mut FileManager,
     // Manages writing Pipewire configuration
-    config_manager: &'a ConfigManager,
+    pub(crate) config_manager: &'a ConfigManager,
     // Contains data about WAV files
     pub(crate) all_wav_index: WavFileIndex,
     // Cached filtered items (None when dirty)
     pub(crate) filtered_wav_index: Option<WavFileIndex>,
     // Shared log buffer
-    log_buffer: Arc<Mutex<Vec<String>>>,
+    log_buffer: SharedLogBuffer,
+    // User notes for IR files (None if the notes file could not be loaded)
+    pub(crate) notes: Option<NotesStore>,
 
     // === UI state ===
     // Checksum of selected file (None if none selected)
     pub(crate) selected_checksum: Option<u128>,
+    // Checksum of the file compared with the selected one (None if no comparison)
+    pub(crate) compare_checksum: Option<u128>,
+    // Checksums of the files marked for bulk export, separate from the selection
+    pub(crate) marked_checksums: HashSet<u128>,
+    // Last file marked with ctrl-click, start of a ctrl+shift-click range
+    pub(crate) last_marked: Option<u128>,
     // Currently selected sample rate filter
     pub(crate) sample_rate_filter: WaveSampleRate,
     // Checksum of the WAV file set in installed Pipewire config file if any
     // None = no config, Some(0) = config exists but file is damaged, Some(nonzero) = valid checksum
     pub(crate) config_installed: Option<u128>,
+    // Result of the startup self-check of the installed device
+    // None = no device, or the device was changed since startup
+    device_health: Option<DeviceHealth>,
+    // Sample rate of the PipeWire graph, None if unknown or in dev mode
+    pub(crate) graph_sample_rate: Option<u32>,
+    // Files the sample rate warning was shown for in this session
+    rate_warned: HashSet<u128>,
     // Search filter text
     pub(crate) search_text: String,
+    // Note being edited in the metadata panel
+    pub(crate) note_text: String,
+    // Checksum of the file note_text belongs to
+    pub(crate) note_checksum: Option<u128>,
+    // Whether to show only files that have a description
+    pub(crate) described_only: bool,
+    // Whether to show only favorite files
+    pub(crate) favorites_only: bool,
+    // Distinct HRTF families of the scanned files, sorted
+    pub(crate) hrtf_families: Vec<String>,
+    // Family the file list is restricted to (None = all families)
+    pub(crate) family_filter: Option<String>,
+    // Whether the search also matches descriptions, not just paths
+    pub(crate) search_descriptions: bool,
+    // Whether the search text is a regular expression
+    pub(crate) search_regex: bool,
+    // Last pattern compiled for the regex search, None inside if invalid
+    pub(crate) search_regex_cache: Option<(String, Option<Regex>)>,
+    // File whose full description is shown (None = all truncated)
+    pub(crate) description_expanded: Option<u128>,
+    // Checksums shared by several files, with the relative path of the
+    // file that stays listed when duplicates are hidden
+    pub(crate) duplicates: HashMap<u128, PathBuf>,
     // Currently selected tab (Files/Options)
-    selected_tab: Tab,
+    pub(crate) selected_tab: Tab,
     // Directory path displayed in edit field in options tab
     pub(crate) directory_text: String,
     // Virtual device name displayed in edit field in options tab
-    device_name_text: String,
+    pub(crate) device_name_text: String,
+    // Name typed for a new or renamed profile
+    pub(crate) profile_name_text: String,
+    // Settings to restore if the config of the profile switched to is not written
+    pub(crate) profile_switch: Option<ProfileSwitch>,
+    // Device identifier displayed in edit field in options tab
+    pub(crate) device_id_text: String,
     // UI theme preference (local copy for radio buttons)
-    theme_preference: eframe::egui::ThemePreference,
+    theme_preference: AppTheme,
+    // Last selection seen by auto-apply
+    pub(crate) auto_apply_seen: Option<u128>,
+    // Selection waiting to be auto-applied, and when it was made
+    auto_apply_pending: Option<(u128, Instant)>,
     // Row index to scroll to (None if no scroll requested)
     pub(crate) scroll_to_row: Option<usize>,
+    // Rescan running in the background (None if idle)
+    pub(crate) scan: Option<RunningScan>,
+    // Watcher of the IR directory (None if watching is off)
+    pub(crate) watcher: Option<DirWatcher>,
+    // Thread playing the test preview (None if nothing was played yet)
+    pub(crate) preview: Option<JoinHandle<()>>,
+    // Column the file table is sorted by (None = scan order)
+    pub(crate) sort_column: Option<SortColumn>,
+    // Whether the sort goes in descending order
+    pub(crate) sort_descending: bool,
 
     // === Output device selection ===
     // List of audio sinks (each a HashMap of properties)
@@ -74,28 +255,54 @@ pub struct AppGUI<'a> {
     help_scroll_to_top: bool,
 
     // === Modal state ===
-    // Whether modal dialog is open
-    modal_open: bool,
-    // Modal dialog header text
-    modal_header: String,
-    // Modal dialog message text
-    modal_message: String,
+    // Modal dialog being shown, None while there is none
+    pub(crate) modal: Option<ModalState>,
+    // Whether a previous config can be restored
+    backup_available: bool,
+    // Last config change, for the Undo button
+    pub(crate) undo: Option<ConfigUndo>,
+    // Lowest level of the lines shown in the Log tab
+    log_level_filter: LevelFilter,
+    // Text of the field to add a service unit to restart
+    new_unit_text: String,
+    // Sound server found at startup, shown in the Help tab
+    sound_server: SoundServer,
+    // Devices installed under any identifier
+    pub(crate) installed_devices: Vec<InstalledDevice>,
+    // Device of another identifier the removal confirmation is about
+    pub(crate) pending_removal: Option<InstalledDevice>,
+    // Config shown in the preview window, None while it is closed
+    pub(crate) config_preview: Option<String>,
 }
 
 impl<'a> AppGUI<'a> {
     pub(crate) const METADATA_FRAME_HEIGHT: f32 = 120.0;
+    /// How often the UI checks a running scan
+    pub(crate) const SCAN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+    /// How often the UI checks the directory watcher
+    pub(crate) const WATCH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+    /// Depth offered when the scan depth limit is switched on
+    const DEFAULT_SCAN_DEPTH: u32 = 2;
+    /// Largest scan depth accepted in the Options tab
+    const MAX_SCAN_DEPTH: u32 = 64;
+    /// Width of the scan progress bar in the status bar
+    const SCAN_PROGRESS_WIDTH: f32 = 220.0;
+    /// Width of the config preview window
+    const CONFIG_PREVIEW_WIDTH: f32 = 640.0;
 
     pub fn new(
         cc: &eframe::CreationContext<'_>,
         settings: Rc<RefCell<AppSettings>>,
         file_manager: &'a mut FileManager,
         config_manager: &'a ConfigManager,
-        log_buffer: Arc<Mutex<Vec<String>>>,
+        log_buffer: SharedLogBuffer,
+        initial_path: Option<PathBuf>,
     ) -> Self {
         // Customize egui here with cc.egui_ctx.set_fonts and cc.egui_ctx.set_visuals.
 
         let config_installed = Self::check_config_exists(config_manager);
-        let sample_rate_filter = WaveSampleRate::F48000;
+        let sample_rate_filter = Self::saved_sample_rate_filter(&settings.borrow());
+        let selected_tab = settings.borrow().selected_tab;
 
         // Initialize directory_text from settings
         let current_dir = settings.borrow().get_wav_directory();
@@ -106,52 +313,120 @@ impl<'a> AppGUI<'a> {
 
         // Initialize device_name_text from settings
         let device_name_text = settings.borrow().virtual_device_name.clone();
+        let device_id_text = config_manager.device_id();
+        let sort_column = settings.borrow().sort_column;
+        let sort_descending = settings.borrow().sort_descending;
 
         // Initialize theme preference from settings, resolving System via D-Bus
         let theme_preference = settings.borrow().theme_preference;
-        let resolved = resolve_theme(theme_preference);
-        cc.egui_ctx.set_theme(resolved);
+        apply_theme(&cc.egui_ctx, theme_preference);
+
+        // Initialize interface language from settings
+        set_language(settings.borrow().language);
 
         // Load sinks and compute selected index
-        let sinks = match config_manager.list_audio_devices() {
-            Ok(devices) => ConfigManager::filter_audio_sinks(&devices),
+        let devices = match config_manager.list_audio_devices() {
+            Ok(devices) => devices,
             Err(e) => {
                 error!("Failed to list audio devices: {}", e);
                 Vec::new()
             }
         };
+        let sinks = ConfigManager::filter_audio_sinks(&devices);
+        let device_health = Self::check_device_health(config_manager, &devices);
+        let graph_sample_rate = match config_manager.graph_sample_rate() {
+            Ok(rate) => rate,
+            Err(e) => {
+                warn!("Failed to read the PipeWire sample rate: {}", e);
+                None
+            }
+        };
         let saved_output_device = settings.borrow().output_device.clone();
         let selected_sink_index = Self::find_sink_index_by_name(&sinks, &saved_output_device)
             .map(|idx| idx + 1) // +1 because index 0 is Auto
             .unwrap_or(0);
 
+        // Load user notes. On failure the notes are disabled, so that the
+        // existing notes file is not overwritten.
+        let notes = match NotesStore::load(&settings.borrow()) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                warn!("Failed to load notes, notes are disabled: {:#}", e);
+                None
+            }
+        };
+
         let mut result = Self {
             settings,
             file_manager,
             config_manager,
             all_wav_index: WavFileIndex::new(),
             log_buffer,
+            notes,
             selected_checksum: None,
+            compare_checksum: None,
+            marked_checksums: HashSet::new(),
+            last_marked: None,
             sample_rate_filter,
             config_installed,
+            device_health,
+            graph_sample_rate,
+            rate_warned: HashSet::new(),
             search_text: String::new(),
-            selected_tab: Tab::Files,
-            modal_open: false,
-            modal_header: String::new(),
-            modal_message: String::new(),
+            note_text: String::new(),
+            note_checksum: None,
+            described_only: false,
+            favorites_only: false,
+            hrtf_families: Vec::new(),
+            family_filter: None,
+            search_descriptions: true,
+            search_regex: false,
+            search_regex_cache: None,
+            description_expanded: None,
+            duplicates: HashMap::new(),
+            selected_tab,
+            modal: None,
+            backup_available: config_manager.has_backup(),
+            undo: None,
+            new_unit_text: String::new(),
+            log_level_filter: LevelFilter::Trace,
+            sound_server: ConfigManager::detect_sound_server(),
+            installed_devices: Vec::new(),
+            pending_removal: None,
+            config_preview: None,
             directory_text,
             device_name_text,
+            profile_name_text: String::new(),
+            profile_switch: None,
+            device_id_text,
             theme_preference,
             filtered_wav_index: None,
+            auto_apply_seen: None,
+            auto_apply_pending: None,
             scroll_to_row: None,
+            scan: None,
+            watcher: None,
+            preview: None,
+            sort_column,
+            sort_descending,
             sinks,
             selected_sink_index,
             help_cache: CommonMarkCache::default(),
             help_scroll_to_top: true,
         };
 
-        if let Err(e) = result.safe_rescan() {
-            error!("Could not rescan wav directory on startup!. Reason: {}", e);
+        // A path given on the command line replaces the configured directory
+        if let Some(path) = initial_path {
+            result.directory_text = path.to_string_lossy().to_string();
+            result.on_rescan_click();
+        } else {
+            result.safe_rescan(None);
+        }
+        result.refresh_installed_devices();
+        // The startup selection is not a user choice, don't auto-apply it
+        result.auto_apply_seen = result.selected_checksum;
+        if let Some(running) = &mut result.scan {
+            running.startup = true;
         }
         result
     }
@@ -176,15 +451,15 @@ impl<'a> AppGUI<'a> {
             .get("node.name")
             .filter(|s| !s.is_empty())
             .map(String::as_str)
-            .unwrap_or("UNKNOWN DEVICE");
+            .unwrap_or(tr(Msg::UnknownDevice));
         let first_line = sink
             .get("node.nick")
             .map(String::as_str)
             .unwrap_or(node_name);
         if first_line == node_name {
-            return format!("{}", node_name);
+            node_name.to_string()
         } else {
-            return format!("{}\n{}", first_line, node_name);
+            format!("{}\n{}", first_line, node_name)
         }
     }
 
@@ -222,11 +497,11 @@ impl<'a> AppGUI<'a> {
     /// Generate display text for the currently selected sink (including Auto).
     fn selected_sink_display_text(&self) -> String {
         if self.selected_sink_index == 0 {
-            "Auto\nLet Pipewire decide".to_string()
+            tr(Msg::AutoOutputDevice).to_string()
         } else if let Some(sink) = self.sinks.get(self.selected_sink_index - 1) {
             Self::sink_display_text(sink)
         } else {
-            "Invalid".to_string()
+            tr(Msg::InvalidDevice).to_string()
         }
     }
 
@@ -243,7 +518,65 @@ impl<'a> AppGUI<'a> {
         }
     }
 
-    fn on_write_config_click(&mut self) {
+    /// Runs the self-check of the installed device and logs the result.
+    fn check_device_health(
+        config_manager: &ConfigManager,
+        devices: &[std::collections::HashMap<String, String>],
+    ) -> Option<DeviceHealth> {
+        match config_manager.check_device_health(devices) {
+            Ok(Some(DeviceHealth::Healthy)) => {
+                info!("Installed device is healthy");
+                Some(DeviceHealth::Healthy)
+            }
+            Ok(Some(health)) => {
+                warn!("Installed device self-check failed: {:?}", health);
+                Some(health)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                error!("Error checking installed device: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Writes the config of the selected file after the checks of
+    /// `ConfigManager::preflight`. A file whose channels don't match the
+    /// layout of the device is written once the user confirms.
+    pub(crate) fn on_write_config_click(&mut self) {
+        let Some(wave) = self
+            .selected_checksum
+            .and_then(|checksum| self.find_wav_by_checksum(checksum))
+        else {
+            return self.write_selected_config();
+        };
+        let file = wave.relative_path.display().to_string();
+        let result = self
+            .config_manager
+            .preflight(&wave.path, wave.channels, false);
+        let Err(error) = result else {
+            return self.write_selected_config();
+        };
+        warn!("Config not written: {}", error);
+        if let PreflightError::NoPipeWire(server) = &error {
+            self.sound_server = server.clone();
+        }
+        let (header, message) = preflight_error_text(&error, &file);
+        match error {
+            PreflightError::ChannelMismatch { .. } => {
+                self.show_modal_with_action(header, &message, ModalAction::WriteAnyway)
+            }
+            PreflightError::NoPipeWire(_) => self.show_modal(header, &message),
+            // The write button tells why it is disabled, the log is enough
+            // for Enter and auto-apply
+            PreflightError::NotWav => {}
+        }
+    }
+
+    /// Writes the config of the selected file.
+    fn write_selected_config(&mut self) {
+        // The startup self-check no longer describes the device
+        self.device_health = None;
         if let Some(checksum) = self.selected_checksum {
             let selected_wav = match self.find_wav_by_checksum(checksum) {
                 Some(wave) => wave,
@@ -254,13 +587,36 @@ impl<'a> AppGUI<'a> {
             };
             let absolute_path = selected_wav.path.as_path();
             let display_path = absolute_path.display().to_string();
-            match self.config_manager.write_config(absolute_path) {
+            let file_rate = selected_wav.sample_rate.hz();
+            let previous = self.config_manager.read_config_text();
+            match self
+                .config_manager
+                .write_config(absolute_path, selected_wav.checksum)
+            {
                 Ok(()) => {
+                    self.undo = Some(ConfigUndo {
+                        wrote: true,
+                        previous,
+                        directory: self.settings.borrow().get_wav_directory(),
+                    });
                     // Double-check that config was written correctly and extract the checksum from config
                     match self.config_manager.config_exists() {
                         Ok(Some(checksum)) => {
                             info!("Config written using {}", display_path);
                             self.config_installed = Some(checksum);
+                            if !self.settings.borrow().restart_services {
+                                self.show_modal(
+                                    tr(Msg::ReloadPipewireHeader),
+                                    tr(Msg::ReloadPipewireMessage),
+                                );
+                            }
+                            // A profile being switched to is now active
+                            self.profile_switch = None;
+                            if self.settings.borrow().active_profile().is_some() {
+                                self.update_active_profile();
+                                self.write_settings();
+                            }
+                            self.check_graph_rate(checksum, file_rate);
                         }
                         Ok(None) => {
                             // Config file doesn't exist after writing - something went wrong
@@ -278,29 +634,476 @@ impl<'a> AppGUI<'a> {
                     error!("Failed to write config: {}", e);
                 }
             }
+            self.backup_available = self.config_manager.has_backup();
+            self.refresh_installed_devices();
         } else {
             warn!("No file selected");
         }
     }
 
+    /// Reverts the last write or removal of the device.
+    fn on_undo_click(&mut self) {
+        let Some(undo) = self.undo.take() else {
+            return;
+        };
+        self.device_health = None;
+        let result = match (undo.wrote, &undo.previous) {
+            // A created device is removed again
+            (true, None) => self.config_manager.delete_config(),
+            // The replaced config and its IR file are in the backup
+            (true, Some(_)) => self.config_manager.restore_backup(),
+            // A removal leaves the IR file in place, only the config is gone
+            (false, Some(text)) => self.config_manager.restore_config_text(text),
+            (false, None) => Ok(()),
+        };
+        match result {
+            Ok(()) => info!("Last config change undone"),
+            Err(e) => error!("Failed to undo the last config change: {}", e),
+        }
+        self.config_installed = Self::check_config_exists(self.config_manager);
+        self.backup_available = self.config_manager.has_backup();
+        self.refresh_installed_devices();
+    }
+
+    /// Renders a checkbox for each service unit that can be restarted, and
+    /// a field to add another unit.
+    fn render_restart_units(&mut self, ui: &mut egui::Ui) {
+        ui.label(tr(Msg::RestartUnitsExplanation));
+        let selected = self.settings.borrow().restart_units.clone();
+        // The default and the added units stay listed after they are unchecked
+        let mut shown: Vec<String> = DEFAULT_RESTART_UNITS.map(String::from).to_vec();
+        for unit in self.settings.borrow().custom_units.iter().chain(&selected) {
+            if !shown.contains(unit) {
+                shown.push(unit.clone());
+            }
+        }
+        let mut changed = false;
+        let mut units = Vec::new();
+        ui.horizontal_wrapped(|ui| {
+            for unit in shown {
+                let mut checked = selected.contains(&unit);
+                changed |= ui.checkbox(&mut checked, unit.as_str()).changed();
+                if checked {
+                    units.push(unit);
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr(Msg::AddUnitLabel));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_unit_text).hint_text(tr(Msg::AddUnitHint)),
+            );
+            let unit = self.new_unit_text.trim().to_string();
+            let valid = ConfigManager::is_valid_unit_name(&unit) && !units.contains(&unit);
+            if ui
+                .add_enabled(valid, egui::Button::new(tr(Msg::AddUnit)))
+                .clicked()
+            {
+                {
+                    let mut settings = self.settings.borrow_mut();
+                    if !settings.custom_units.contains(&unit) {
+                        settings.custom_units.push(unit.clone());
+                    }
+                }
+                units.push(unit);
+                self.new_unit_text.clear();
+                changed = true;
+            }
+        });
+        if changed {
+            if units.is_empty() {
+                warn!("No service units selected, config changes will not be applied");
+            }
+            self.settings.borrow_mut().restart_units = units;
+            self.write_settings();
+        }
+    }
+
+    /// Tells the user after a write that PipeWire runs at another rate than
+    /// the file, so the IR is resampled. Only the first write of a file
+    /// tells, and the rate read at startup is used, since querying PipeWire
+    /// right after the restart would block the UI.
+    fn check_graph_rate(&mut self, checksum: u128, file_rate: Option<u32>) {
+        let (Some(file_rate), Some(graph_rate)) = (file_rate, self.graph_sample_rate) else {
+            return;
+        };
+        // Auto-apply writes on every selection, the note next to the file
+        // is enough there
+        if file_rate == graph_rate
+            || self.modal.is_some()
+            || self.settings.borrow().auto_apply
+            || self.rate_warned.contains(&checksum)
+        {
+            return;
+        }
+        self.rate_warned.insert(checksum);
+        self.show_modal(
+            tr(Msg::SampleRateMismatchHeader),
+            &tr(Msg::SampleRateMismatchMessage)
+                .replace("{file}", &file_rate.to_string())
+                .replace("{graph}", &graph_rate.to_string()),
+        );
+    }
+
+    /// Renders the directories scanned together with the IR directory, with
+    /// buttons to add and remove them. Each change rescans.
+    fn render_extra_directories(&mut self, ui: &mut egui::Ui) {
+        let dirs = self.settings.borrow().extra_wav_directories.clone();
+        let idle = self.scan.is_none();
+        let mut removed = None;
+        ui.label(tr(Msg::ExtraDirectoriesLabel));
+        for (index, dir) in dirs.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(dir.to_string_lossy());
+                if ui
+                    .add_enabled(
+                        idle,
+                        egui::Button::new(tr(Msg::RemoveExtraDirectory)).small(),
+                    )
+                    .clicked()
+                {
+                    removed = Some(index);
+                }
+            });
+        }
+        let added = ui
+            .add_enabled(idle, egui::Button::new(tr(Msg::AddExtraDirectory)))
+            .clicked()
+            .then(|| {
+                FileDialog::new()
+                    .set_title(tr(Msg::SelectExtraDirectoryDialogTitle))
+                    .pick_folder()
+            })
+            .flatten();
+        if removed.is_none() && added.is_none() {
+            return;
+        }
+        {
+            let mut settings = self.settings.borrow_mut();
+            let extra = &mut settings.extra_wav_directories;
+            if let Some(index) = removed {
+                extra.remove(index);
+            }
+            if let Some(dir) = added
+                && !extra.contains(&dir)
+            {
+                extra.push(dir);
+            }
+        }
+        self.write_settings();
+        self.safe_rescan(None);
+    }
+
+    /// Restarts the PipeWire services on request, for users who turned the
+    /// automatic restart off.
+    fn on_apply_now_click(&mut self) {
+        self.device_health = None;
+        match self.config_manager.restart_services() {
+            Ok(()) => info!("PipeWire services restarted"),
+            Err(e) => {
+                error!("Failed to restart PipeWire services: {}", e);
+                self.show_modal(
+                    tr(Msg::RestartErrorHeader),
+                    &tr(Msg::RestartErrorMessage).replace("{}", &format!("{e:#}")),
+                );
+            }
+        }
+    }
+
+    /// Text of the hint of the Undo button.
+    fn undo_hint(&self) -> &'static str {
+        match &self.undo {
+            Some(ConfigUndo {
+                wrote: true,
+                previous: None,
+                ..
+            }) => tr(Msg::UndoCreateHint),
+            Some(ConfigUndo { wrote: true, .. }) => tr(Msg::UndoUpdateHint),
+            Some(ConfigUndo { wrote: false, .. }) => tr(Msg::UndoRemoveHint),
+            None => tr(Msg::NothingToUndo),
+        }
+    }
+
+    /// Swaps the backup of the previous config back in.
+    fn on_restore_config_click(&mut self) {
+        self.device_health = None;
+        // The config the undo would go back to is replaced now
+        self.undo = None;
+        match self.config_manager.restore_backup() {
+            Ok(()) => info!("Previous config restored"),
+            Err(e) => error!("Failed to restore the previous config: {}", e),
+        }
+        self.config_installed = Self::check_config_exists(self.config_manager);
+        self.backup_available = self.config_manager.has_backup();
+        self.refresh_installed_devices();
+    }
+
+    /// Shows the config that creating the device would install.
+    fn on_preview_config_click(&mut self) {
+        let Some(wave) = self
+            .selected_checksum
+            .and_then(|checksum| self.find_wav_by_checksum(checksum))
+        else {
+            warn!("No file selected");
+            return;
+        };
+        match self.config_manager.preview_config(&wave.path) {
+            Ok(text) => self.config_preview = Some(text),
+            Err(e) => error!("Failed to preview config: {}", e),
+        }
+    }
+
+    /// Renders the config preview window while there is a preview.
+    fn render_config_preview(&mut self, ctx: &egui::Context) {
+        let Some(text) = &self.config_preview else {
+            return;
+        };
+        let modal = egui::Modal::new(egui::Id::new("config_preview_modal")).show(ctx, |ui| {
+            ui.set_width(Self::CONFIG_PREVIEW_WIDTH);
+            ui.heading(tr(Msg::ConfigPreviewHeader));
+            ui.label(
+                self.config_manager
+                    .config_path()
+                    .to_string_lossy()
+                    .to_string(),
+            );
+            egui::ScrollArea::vertical()
+                .max_height(ctx.content_rect().height() * 0.6)
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut text.as_str())
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button(tr(Msg::Continue)).clicked() {
+                    ui.close();
+                }
+                if ui.button(tr(Msg::CopyToClipboard)).clicked() {
+                    ui.ctx().copy_text(text.clone());
+                }
+            });
+        });
+        if modal.should_close() {
+            self.config_preview = None;
+        }
+    }
+
+    /// Asks for confirmation before removing the device, unless the user
+    /// turned the question off.
+    pub(crate) fn on_remove_device_click(&mut self) {
+        if !self.settings.borrow().confirm_device_removal {
+            return self.on_delete_config_click();
+        }
+        let device_name = self.settings.borrow().virtual_device_name.clone();
+        self.show_modal_with_action(
+            tr(Msg::RemoveDeviceHeader),
+            &tr(Msg::RemoveDeviceMessage).replace("{}", &device_name),
+            ModalAction::RemoveDevice,
+        );
+    }
+
     fn on_delete_config_click(&mut self) {
+        // The startup self-check no longer describes the device
+        self.device_health = None;
+        let previous = self.config_manager.read_config_text();
         match self.config_manager.delete_config() {
             Ok(()) => {
                 info!("Config deleted");
                 self.config_installed = None;
+                self.undo = Some(ConfigUndo {
+                    wrote: false,
+                    previous,
+                    directory: self.settings.borrow().get_wav_directory(),
+                });
             }
             Err(e) => {
                 error!("Failed to delete config: {}", e);
             }
         }
+        self.refresh_installed_devices();
     }
 
     /// Shows a modal dialog with a header, message body, and a "Continue" button.
     /// The modal will be displayed until the user clicks "Continue" or closes it.
     pub(crate) fn show_modal(&mut self, header: &str, message: &str) {
-        self.modal_open = true;
-        self.modal_header = header.to_string();
-        self.modal_message = message.to_string();
+        self.modal = Some(ModalState::message(header, message));
+    }
+
+    /// Shows a modal dialog like `show_modal`, with an extra button that
+    /// triggers the given action.
+    pub(crate) fn show_modal_with_action(
+        &mut self,
+        header: &str,
+        message: &str,
+        action: ModalAction,
+    ) {
+        self.modal = Some(ModalState::confirm(header, message, action));
+        self.pending_removal = None;
+    }
+
+    /// Whether a config write waits for the user to confirm a warning.
+    pub(crate) fn write_awaits_confirmation(&self) -> bool {
+        self.modal
+            .as_ref()
+            .is_some_and(|modal| modal.action == Some(ModalAction::WriteAnyway))
+    }
+
+    /// Performs the action confirmed in a modal. `dont_ask` is the state of
+    /// its "Don't ask again" checkbox.
+    fn on_modal_action(&mut self, ctx: &egui::Context, action: ModalAction, dont_ask: bool) {
+        match action {
+            ModalAction::PickDirectory => {
+                self.selected_tab = Tab::Options;
+                self.on_select_directory_click();
+            }
+            ModalAction::ResetSettings => {
+                self.reset_settings(ctx);
+            }
+            ModalAction::RemoveDevice => {
+                if dont_ask {
+                    self.settings.borrow_mut().confirm_device_removal = false;
+                    self.write_settings();
+                }
+                match self.pending_removal.take() {
+                    Some(device) => self.remove_installed_device(&device),
+                    None => self.on_delete_config_click(),
+                }
+            }
+            ModalAction::WriteAnyway => {
+                self.write_selected_config();
+                self.finish_profile_switch();
+            }
+        }
+    }
+
+    /// Replaces all settings with defaults (keeping runtime-only flags), saves them
+    /// and brings the UI in line with the new settings.
+    fn reset_settings(&mut self, ctx: &egui::Context) {
+        self.apply_settings(ctx, AppSettings::default());
+        info!("Settings reset to defaults");
+    }
+
+    /// Replaces all settings (keeping runtime-only flags), saves them and
+    /// brings the UI in line with the new settings.
+    fn apply_settings(&mut self, ctx: &egui::Context, mut new_settings: AppSettings) {
+        new_settings.dev_mode = self.settings.borrow().dev_mode;
+        new_settings.config_dir = self.settings.borrow().config_dir.clone();
+        *self.settings.borrow_mut() = new_settings;
+        self.write_settings();
+
+        let settings = self.settings.borrow().clone();
+        self.directory_text = settings
+            .get_wav_directory()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.sample_rate_filter = Self::saved_sample_rate_filter(&settings);
+        self.device_name_text = settings.virtual_device_name;
+        self.profile_name_text.clear();
+        self.device_id_text = self.config_manager.device_id();
+        self.config_installed = Self::check_config_exists(self.config_manager);
+        self.device_health = None;
+        self.undo = None;
+        self.backup_available = self.config_manager.has_backup();
+        self.refresh_installed_devices();
+        self.selected_sink_index =
+            Self::find_sink_index_by_name(&self.sinks, &settings.output_device)
+                .map(|idx| idx + 1) // +1 because index 0 is Auto
+                .unwrap_or(0);
+        self.theme_preference = settings.theme_preference;
+        apply_theme(ctx, self.theme_preference);
+        self.sort_column = settings.sort_column;
+        self.sort_descending = settings.sort_descending;
+        set_language(settings.language);
+
+        // Scan the new directory; with none configured this clears the list
+        self.selected_checksum = None;
+        // The compared pair was reset too
+        self.compare_checksum = None;
+        self.safe_rescan(None);
+    }
+
+    /// Shows the directory picker dialog and rescans the chosen directory.
+    fn on_select_directory_click(&mut self) {
+        // Create file dialog for directory selection
+        let mut dialog = FileDialog::new().set_title(tr(Msg::SelectDirectoryDialogTitle));
+
+        // Try to set starting directory from current directory_text if it's a valid path
+        let current_dir = self.directory_text.trim();
+        if !current_dir.is_empty() {
+            let path = PathBuf::from(current_dir);
+            if path.exists() && path.is_dir() {
+                dialog = dialog.set_directory(path);
+            }
+        }
+
+        // Show directory picker dialog
+        if let Some(selected_folder) = dialog.pick_folder() {
+            // Update directory text field with selected path
+            self.directory_text = selected_folder.to_string_lossy().to_string();
+            // Automatically trigger rescan for the newly selected directory
+            self.on_rescan_click();
+        }
+    }
+
+    /// Shows the file picker dialog, then scans the directory of the chosen
+    /// WAV file and selects it.
+    fn on_select_file_click(&mut self) {
+        let mut dialog = FileDialog::new()
+            .set_title(tr(Msg::SelectFileDialogTitle))
+            .add_filter("WAV", &["wav", "WAV"]);
+
+        // Start in the current directory if it's a valid path
+        let current_dir = PathBuf::from(self.directory_text.trim());
+        if current_dir.is_dir() {
+            dialog = dialog.set_directory(current_dir);
+        }
+
+        if let Some(selected_file) = dialog.pick_file() {
+            self.directory_text = selected_file.to_string_lossy().to_string();
+            self.on_rescan_click();
+        }
+    }
+
+    /// Shows the directory picker for the IR file storage location.
+    fn on_select_hrir_directory_click(&mut self) {
+        let mut dialog = FileDialog::new().set_title(tr(Msg::SelectHrirDirectoryDialogTitle));
+        if let Some(dir) = &self.settings.borrow().hrir_directory
+            && dir.is_dir()
+        {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(selected_folder) = dialog.pick_folder() else {
+            return;
+        };
+        // The app manages the files in the storage, keep the library out of it
+        let library = self
+            .settings
+            .borrow()
+            .wav_directories()
+            .into_iter()
+            .find(|dir| ConfigManager::storage_contains(&selected_folder, dir));
+        if let Some(library) = library {
+            self.show_modal(
+                tr(Msg::HrirStorageRefusedHeader),
+                &tr(Msg::HrirStorageRefusedMessage).replace("{}", &library.display().to_string()),
+            );
+            return;
+        }
+        self.settings.borrow_mut().hrir_directory = Some(selected_folder);
+        self.write_settings();
+    }
+
+    /// Saves the external descriptions file and reloads the descriptions,
+    /// rescanning so the files get them.
+    fn set_descriptions_file(&mut self, file: Option<PathBuf>) {
+        self.settings.borrow_mut().descriptions_file = file.clone();
+        self.write_settings();
+        self.file_manager
+            .set_descriptions(Descriptions::load(file.as_deref()));
+        self.safe_rescan(None);
     }
 
     /// Find wav data by checksum.
@@ -308,58 +1111,107 @@ impl<'a> AppGUI<'a> {
         self.all_wav_index.get_by_checksum(checksum)
     }
 
+    /// Whether the selected file is a FLAC file, which the device can't use
+    /// until it is converted to WAV.
+    fn selected_is_flac(&self) -> bool {
+        self.selected_checksum
+            .and_then(|checksum| self.find_wav_by_checksum(checksum))
+            .is_some_and(|wave| FileManager::is_flac_file(&wave.path))
+    }
+
+    /// Sample-rate filter stored in the settings.
+    fn saved_sample_rate_filter(settings: &AppSettings) -> WaveSampleRate {
+        settings
+            .sample_rate_filter
+            .map_or(WaveSampleRate::Unknown, WaveSampleRate::from_hz)
+    }
+
+    /// Renders the list of recently scanned directories. Picking one
+    /// rescans it. Directories that are gone are not listed.
+    fn recent_directories_combo(&mut self, ui: &mut egui::Ui) {
+        let recent: Vec<PathBuf> = self
+            .settings
+            .borrow()
+            .recent_directories
+            .iter()
+            .filter(|dir| dir.is_dir())
+            .cloned()
+            .collect();
+        if recent.is_empty() {
+            return;
+        }
+        let mut picked = None;
+        egui::ComboBox::from_id_salt("recent_directories")
+            .selected_text(tr(Msg::RecentDirectories))
+            .show_ui(ui, |ui| {
+                for dir in &recent {
+                    if ui.selectable_label(false, dir.to_string_lossy()).clicked() {
+                        picked = Some(dir.clone());
+                    }
+                }
+            });
+        if let Some(dir) = picked {
+            self.directory_text = dir.to_string_lossy().to_string();
+            self.on_rescan_click();
+        }
+    }
+
     /// Renders the options tab content.
     fn render_options(&mut self, ui: &mut egui::Ui) {
-        ui.heading("IR files Directory");
-        ui.label("Set the directory containing IR files for surround sound:");
+        ui.heading(tr(Msg::IrDirectoryHeading));
+        ui.label(tr(Msg::IrDirectoryExplanation));
 
         ui.horizontal(|ui| {
-            ui.label("Directory:");
+            ui.label(tr(Msg::DirectoryLabel));
             ui.add(
-                egui::TextEdit::singleline(&mut self.directory_text).hint_text("Path to IR files"),
+                egui::TextEdit::singleline(&mut self.directory_text)
+                    .hint_text(tr(Msg::DirectoryHint)),
             );
-            if ui.button("Select").clicked() {
-                // Create file dialog for directory selection
-                let mut dialog = FileDialog::new().set_title("Select IR Files Directory");
-
-                // Try to set starting directory from current directory_text if it's a valid path
-                let current_dir = self.directory_text.trim();
-                if !current_dir.is_empty() {
-                    let path = PathBuf::from(current_dir);
-                    if path.exists() && path.is_dir() {
-                        dialog = dialog.set_directory(path);
-                    }
-                }
-
-                // Show directory picker dialog
-                if let Some(selected_folder) = dialog.pick_folder() {
-                    // Update directory text field with selected path
-                    self.directory_text = selected_folder.to_string_lossy().to_string();
-                    // Automatically trigger rescan for the newly selected directory
-                    self.on_rescan_click();
-                }
+            // A new directory can't be chosen while the current one is scanned
+            let idle = self.scan.is_none();
+            ui.add_enabled_ui(idle, |ui| self.recent_directories_combo(ui));
+            if ui
+                .add_enabled(idle, egui::Button::new(tr(Msg::SelectDirectory)))
+                .clicked()
+            {
+                self.on_select_directory_click();
+            }
+            if ui
+                .add_enabled(idle, egui::Button::new(tr(Msg::SelectFile)))
+                .clicked()
+            {
+                self.on_select_file_click();
             }
-            let rescan_enabled = !self.directory_text.trim().is_empty();
-            let rescan_button = ui.add_enabled(rescan_enabled, egui::Button::new("Rescan"));
+            let rescan_enabled = idle && !self.directory_text.trim().is_empty();
+            let rescan_button = ui.add_enabled(rescan_enabled, egui::Button::new(tr(Msg::Rescan)));
             if rescan_button.clicked() {
                 self.on_rescan_click();
             }
         });
+        self.render_extra_directories(ui);
 
         ui.separator();
 
-        ui.heading("Virtual Device Name");
-        ui.label("Set the name of the virtual audio device that will appear in your system audio settings:");
+        self.render_profiles(ui);
+
+        ui.separator();
+
+        ui.heading(tr(Msg::DeviceNameHeading));
+        ui.label(tr(Msg::DeviceNameExplanation));
 
         // Display currently configured device name
         let current_device_name = self.settings.borrow().virtual_device_name.clone();
-        ui.label(format!("Currently configured: {}", current_device_name));
+        ui.label(format!(
+            "{} {}",
+            tr(Msg::CurrentlyConfigured),
+            current_device_name
+        ));
 
         ui.horizontal(|ui| {
-            ui.label("Device name:");
+            ui.label(tr(Msg::DeviceNameLabel));
             ui.add(
                 egui::TextEdit::singleline(&mut self.device_name_text)
-                    .hint_text("Virtual device name"),
+                    .hint_text(tr(Msg::DeviceNameHint)),
             );
 
             // Get current device name inside the closure to avoid borrowing issues
@@ -369,7 +1221,7 @@ impl<'a> AppGUI<'a> {
             // Apply button should be disabled when text matches current settings
             let trimmed_text = self.device_name_text.trim().to_string();
             let apply_enabled = !trimmed_text.is_empty() && trimmed_text != current_device_name;
-            let apply_button = ui.add_enabled(apply_enabled, egui::Button::new("Apply"));
+            let apply_button = ui.add_enabled(apply_enabled, egui::Button::new(tr(Msg::Apply)));
             if apply_button.clicked() {
                 self.on_apply_device_name_click(&trimmed_text);
             }
@@ -379,7 +1231,7 @@ impl<'a> AppGUI<'a> {
             let default_button_enabled =
                 !(trimmed_text == default_value && current_device_name == default_value);
             let default_button =
-                ui.add_enabled(default_button_enabled, egui::Button::new("Default"));
+                ui.add_enabled(default_button_enabled, egui::Button::new(tr(Msg::Default)));
             if default_button.clicked() {
                 self.on_default_device_name_click();
             }
@@ -387,17 +1239,49 @@ impl<'a> AppGUI<'a> {
 
         ui.separator();
 
-        ui.heading("Output Device");
-        ui.label("Select the audio sink where the virtual surround device will output sound:");
+        ui.heading(tr(Msg::DeviceIdHeading));
+        ui.label(tr(Msg::DeviceIdExplanation));
+        ui.horizontal(|ui| {
+            ui.label(tr(Msg::DeviceIdLabel));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.device_id_text)
+                    .hint_text(tr(Msg::DeviceIdHint)),
+            );
+
+            let current_id = self.config_manager.device_id();
+            let new_id = ConfigManager::sanitize_device_id(&self.device_id_text);
+            let apply_button =
+                ui.add_enabled(new_id != current_id, egui::Button::new(tr(Msg::Apply)));
+            if apply_button.clicked() {
+                self.on_apply_device_id_click(Some(new_id));
+            }
+            // The default identifier follows the device name
+            let default_button = ui.add_enabled(
+                self.settings.borrow().device_id.is_some(),
+                egui::Button::new(tr(Msg::Default)),
+            );
+            if default_button.clicked() {
+                self.on_apply_device_id_click(None);
+            }
+        });
+
+        ui.separator();
+
+        self.render_installed_devices(ui);
+
+        ui.separator();
+
+        ui.heading(tr(Msg::OutputDeviceHeading));
+        ui.label(tr(Msg::OutputDeviceExplanation));
         ui.horizontal_top(|ui| {
-            ui.label("Output device:");
+            ui.label(tr(Msg::OutputDeviceLabel));
             // Combobox for sink selection
             let total_items = self.sinks.len() + 1; // +1 for Auto
             let response = egui::ComboBox::from_label("")
                 .selected_text(self.selected_sink_display_text())
                 .show_index(ui, &mut self.selected_sink_index, total_items, |i| {
                     if i == 0 {
-                        "Auto\nLet Pipewire decide".to_string()
+                        tr(Msg::AutoOutputDevice).to_string()
                     } else {
                         let sink = &self.sinks[i - 1];
                         Self::sink_display_text(sink)
@@ -416,44 +1300,423 @@ impl<'a> AppGUI<'a> {
                 self.write_settings();
             }
             // Reload button
-            if ui.button("Reload").clicked() {
+            if ui.button(tr(Msg::ReloadDevices)).clicked() {
                 self.refresh_sinks();
             }
         });
 
         ui.separator();
 
-        ui.heading("UI Theme");
-        ui.label("Select the application visual theme:");
+        ui.heading(tr(Msg::HrirStorageHeading));
+        ui.label(tr(Msg::HrirStorageExplanation));
+        let hrir_directory = self.settings.borrow().hrir_directory.clone();
+        ui.horizontal(|ui| {
+            let location = match &hrir_directory {
+                Some(dir) => dir.to_string_lossy().to_string(),
+                None => tr(Msg::HrirStorageDefault).to_string(),
+            };
+            ui.label(format!("{} {}", tr(Msg::CurrentlyConfigured), location));
+            if ui.button(tr(Msg::SelectDirectory)).clicked() {
+                self.on_select_hrir_directory_click();
+            }
+            let default_button = ui.add_enabled(
+                hrir_directory.is_some(),
+                egui::Button::new(tr(Msg::Default)),
+            );
+            if default_button.clicked() {
+                self.settings.borrow_mut().hrir_directory = None;
+                self.write_settings();
+            }
+        });
+        let mut copy_ir_file = self.settings.borrow().copy_ir_file;
+        if ui
+            .checkbox(&mut copy_ir_file, tr(Msg::CopyIrFile))
+            .on_hover_text(tr(Msg::CopyIrFileHint))
+            .changed()
+        {
+            self.settings.borrow_mut().copy_ir_file = copy_ir_file;
+            self.write_settings();
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::DescriptionsFileHeading));
+        ui.label(tr(Msg::DescriptionsFileExplanation));
+        let descriptions_file = self.settings.borrow().descriptions_file.clone();
+        ui.horizontal(|ui| {
+            let location = match &descriptions_file {
+                Some(file) => file.to_string_lossy().to_string(),
+                None => tr(Msg::DescriptionsFileNone).to_string(),
+            };
+            ui.label(format!("{} {}", tr(Msg::CurrentlyConfigured), location));
+            if ui.button(tr(Msg::SelectFile)).clicked()
+                && let Some(file) = FileDialog::new()
+                    .set_title(tr(Msg::SelectDescriptionsFileDialogTitle))
+                    .add_filter("CSV", &["csv"])
+                    .pick_file()
+            {
+                self.set_descriptions_file(Some(file));
+            }
+            let default_button = ui.add_enabled(
+                descriptions_file.is_some(),
+                egui::Button::new(tr(Msg::Default)),
+            );
+            if default_button.clicked() {
+                self.set_descriptions_file(None);
+            }
+        });
+
+        ui.separator();
+
+        ui.heading(tr(Msg::ThemeHeading));
+        ui.label(tr(Msg::ThemeExplanation));
         let old_preference = self.theme_preference;
         ui.horizontal(|ui| {
-            ui.selectable_value(&mut self.theme_preference, egui::ThemePreference::Light, "🌞 Light");
-            ui.selectable_value(&mut self.theme_preference, egui::ThemePreference::Dark, "🌙 Dark");
-            ui.selectable_value(&mut self.theme_preference, egui::ThemePreference::System, "🌟 System");
+            ui.selectable_value(
+                &mut self.theme_preference,
+                AppTheme::Light,
+                tr(Msg::ThemeLight),
+            );
+            ui.selectable_value(
+                &mut self.theme_preference,
+                AppTheme::Dark,
+                tr(Msg::ThemeDark),
+            );
+            ui.selectable_value(
+                &mut self.theme_preference,
+                AppTheme::HighContrast,
+                tr(Msg::ThemeHighContrast),
+            );
+            ui.selectable_value(
+                &mut self.theme_preference,
+                AppTheme::System,
+                tr(Msg::ThemeSystem),
+            );
         });
         if self.theme_preference != old_preference {
             // Update settings
             self.settings.borrow_mut().theme_preference = self.theme_preference;
             self.write_settings();
             // Resolve and apply immediately
-            let resolved = resolve_theme(self.theme_preference);
-            ui.ctx().set_theme(resolved);
+            apply_theme(ui.ctx(), self.theme_preference);
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::LanguageHeading));
+        ui.label(tr(Msg::LanguageExplanation));
+        let old_language = self.settings.borrow().language;
+        let mut language = old_language;
+        ui.horizontal(|ui| {
+            for option in Language::ALL {
+                ui.selectable_value(&mut language, option, option.native_name());
+            }
+        });
+        if language != old_language {
+            self.settings.borrow_mut().language = language;
+            self.write_settings();
+            // Applies from the next frame on, no restart needed
+            set_language(language);
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::DensityHeading));
+        ui.label(tr(Msg::DensityExplanation));
+        let old_density = self.settings.borrow().table_density;
+        let mut density = old_density;
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut density, TableDensity::Compact, tr(Msg::DensityCompact));
+            ui.selectable_value(
+                &mut density,
+                TableDensity::Comfortable,
+                tr(Msg::DensityComfortable),
+            );
+        });
+        if density != old_density {
+            self.settings.borrow_mut().table_density = density;
+            self.write_settings();
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::AutoApplyHeading));
+        let mut auto_apply = self.settings.borrow().auto_apply;
+        if ui
+            .checkbox(&mut auto_apply, tr(Msg::AutoApplyExplanation))
+            .changed()
+        {
+            self.settings.borrow_mut().auto_apply = auto_apply;
+            self.write_settings();
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::RestartServicesHeading));
+        let mut restart_services = self.settings.borrow().restart_services;
+        if ui
+            .checkbox(&mut restart_services, tr(Msg::RestartServicesExplanation))
+            .changed()
+        {
+            self.settings.borrow_mut().restart_services = restart_services;
+            self.write_settings();
+        }
+        self.render_restart_units(ui);
+
+        ui.separator();
+
+        ui.heading(tr(Msg::OutputGainHeading));
+        ui.label(tr(Msg::OutputGainExplanation));
+        let mut gain = self.settings.borrow().output_gain_db;
+        ui.horizontal(|ui| {
+            ui.label(tr(Msg::OutputGainLabel));
+            let response = ui.add(
+                egui::DragValue::new(&mut gain)
+                    .range(OUTPUT_GAIN_RANGE)
+                    .speed(0.1)
+                    .fixed_decimals(1)
+                    .suffix(" dB"),
+            );
+            if response.changed() {
+                self.settings.borrow_mut().output_gain_db = gain;
+            }
+            // Save once the value is set, not on every step of a drag
+            if response.drag_stopped() || response.lost_focus() {
+                self.write_settings();
+            }
+            let reset = ui.add_enabled(gain != 0.0, egui::Button::new(tr(Msg::Default)));
+            if reset.clicked() {
+                self.settings.borrow_mut().output_gain_db = 0.0;
+                self.write_settings();
+            }
+        });
+
+        ui.separator();
+
+        ui.heading(tr(Msg::ChannelLayoutHeading));
+        ui.label(tr(Msg::ChannelLayoutExplanation));
+        let old_layout = self.settings.borrow().channel_layout;
+        let mut layout = old_layout;
+        ui.horizontal(|ui| {
+            for option in ChannelLayout::ALL {
+                ui.selectable_value(&mut layout, option, option.name());
+            }
+        });
+        if layout != old_layout {
+            self.settings.borrow_mut().channel_layout = layout;
+            self.write_settings();
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::TableColumnsHeading));
+        let mut show_points = self.settings.borrow().show_points_column;
+        if ui
+            .checkbox(&mut show_points, tr(Msg::ShowPointsColumn))
+            .changed()
+        {
+            {
+                let mut settings = self.settings.borrow_mut();
+                settings.show_points_column = show_points;
+                // A hidden column can't show that the list is sorted by it
+                if !show_points && self.sort_column == Some(SortColumn::Points) {
+                    self.sort_column = None;
+                    settings.sort_column = None;
+                    self.filtered_wav_index = None;
+                }
+            }
+            self.write_settings();
+        }
+        let mut show_size = self.settings.borrow().show_size_column;
+        if ui
+            .checkbox(&mut show_size, tr(Msg::ShowSizeColumn))
+            .changed()
+        {
+            {
+                let mut settings = self.settings.borrow_mut();
+                settings.show_size_column = show_size;
+                if !show_size && self.sort_column == Some(SortColumn::Size) {
+                    self.sort_column = None;
+                    settings.sort_column = None;
+                    self.filtered_wav_index = None;
+                }
+            }
+            self.write_settings();
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::LogFileHeading));
+        let mut log_to_file = self.settings.borrow().log_to_file;
+        if ui.checkbox(&mut log_to_file, tr(Msg::LogToFile)).changed() {
+            self.settings.borrow_mut().log_to_file = log_to_file;
+            self.write_settings();
+            logging::apply_log_file_setting(&self.settings.borrow());
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::ConfirmationsHeading));
+        let mut confirm_device_removal = self.settings.borrow().confirm_device_removal;
+        if ui
+            .checkbox(&mut confirm_device_removal, tr(Msg::ConfirmDeviceRemoval))
+            .changed()
+        {
+            self.settings.borrow_mut().confirm_device_removal = confirm_device_removal;
+            self.write_settings();
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::FavoritesHeading));
+        let mut favorites_first = self.settings.borrow().favorites_first;
+        if ui
+            .checkbox(&mut favorites_first, tr(Msg::FavoritesFirst))
+            .changed()
+        {
+            self.settings.borrow_mut().favorites_first = favorites_first;
+            self.filtered_wav_index = None;
+            self.write_settings();
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::DuplicatesHeading));
+        let mut hide_duplicates = self.settings.borrow().hide_duplicates;
+        if ui
+            .checkbox(&mut hide_duplicates, tr(Msg::HideDuplicates))
+            .changed()
+        {
+            self.settings.borrow_mut().hide_duplicates = hide_duplicates;
+            self.filtered_wav_index = None;
+            self.write_settings();
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::ScanDepthHeading));
+        ui.label(tr(Msg::ScanDepthExplanation));
+        ui.horizontal(|ui| {
+            let current = self.settings.borrow().scan_depth;
+            let mut limited = current.is_some();
+            let mut depth = current.unwrap_or(Self::DEFAULT_SCAN_DEPTH);
+            let toggled = ui.checkbox(&mut limited, tr(Msg::ScanDepthLimit)).changed();
+            let edited = ui
+                .add_enabled(
+                    limited,
+                    egui::DragValue::new(&mut depth).range(0..=Self::MAX_SCAN_DEPTH),
+                )
+                .changed();
+            if toggled || edited {
+                self.settings.borrow_mut().scan_depth = limited.then_some(depth);
+                self.write_settings();
+            }
+        });
+
+        ui.separator();
+
+        let mut follow_symlinks = self.settings.borrow().follow_symlinks;
+        if ui
+            .checkbox(&mut follow_symlinks, tr(Msg::FollowSymlinks))
+            .changed()
+        {
+            self.settings.borrow_mut().follow_symlinks = follow_symlinks;
+            self.write_settings();
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::WatchDirectoryHeading));
+        let mut watch_directory = self.settings.borrow().watch_directory;
+        if ui
+            .checkbox(&mut watch_directory, tr(Msg::WatchDirectoryExplanation))
+            .changed()
+        {
+            self.settings.borrow_mut().watch_directory = watch_directory;
+            self.write_settings();
+        }
+
+        ui.separator();
+
+        ui.heading(tr(Msg::ScanCacheHeading));
+        ui.label(tr(Msg::ScanCacheExplanation));
+        if ui.button(tr(Msg::ClearScanCache)).clicked() {
+            match self.file_manager.clear_cache() {
+                Ok(()) => info!("Scan cache cleared"),
+                Err(e) => error!("Failed to clear scan cache: {:#}", e),
+            }
         }
 
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            if ui.button(tr(Msg::ExportSettings)).clicked() {
+                self.on_export_settings_click();
+            }
+            if ui.button(tr(Msg::ImportSettings)).clicked() {
+                self.on_import_settings_click(ui.ctx());
+            }
+            if ui.button(tr(Msg::ResetSettings)).clicked() {
+                self.show_modal_with_action(
+                    tr(Msg::ResetSettingsHeader),
+                    tr(Msg::ResetSettingsMessage),
+                    ModalAction::ResetSettings,
+                );
+            }
+        });
+
         if self.settings.borrow().dev_mode {
             // Developer-only buttons
             ui.separator();
-            if ui.button("Show modal test message").clicked() {
-                self.show_modal("Test Modal", "This is a test message to demonstrate the modal dialog functionality. Click 'Continue' to close this dialog.");
+            if ui.button(tr(Msg::ShowTestModal)).clicked() {
+                self.show_modal(tr(Msg::TestModalHeader), tr(Msg::TestModalMessage));
             }
         }
     }
 
     /// Renders the log tab content.
     fn render_log(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr(Msg::LogLevelLabel));
+            // Nothing below Info is logged, so an Info filter would match All
+            for (filter, label) in [
+                (LevelFilter::Trace, Msg::LogLevelAll),
+                (LevelFilter::Warn, Msg::LogLevelWarn),
+                (LevelFilter::Error, Msg::LogLevelError),
+            ] {
+                ui.selectable_value(&mut self.log_level_filter, filter, tr(label));
+            }
+            ui.separator();
+            let empty = self
+                .log_buffer
+                .lock()
+                .map_or(true, |guard| guard.is_empty());
+            if ui
+                .add_enabled(!empty, egui::Button::new(tr(Msg::CopyToClipboard)))
+                .clicked()
+                && let Ok(guard) = self.log_buffer.lock()
+            {
+                // The whole log, not only the lines the filter shows
+                ui.ctx().copy_text(guard.to_vec().join("\n"));
+            }
+            if ui
+                .add_enabled(!empty, egui::Button::new(tr(Msg::ExportLog)))
+                .clicked()
+            {
+                self.on_export_log_click();
+            }
+            if ui
+                .add_enabled(!empty, egui::Button::new(tr(Msg::ClearLog)))
+                .clicked()
+                && let Ok(mut guard) = self.log_buffer.lock()
+            {
+                guard.clear();
+            }
+        });
+
         // Update cached log text from buffer
         let logs = match self.log_buffer.lock() {
-            Ok(guard) => guard.clone(),
+            Ok(guard) => guard.entries(self.log_level_filter),
             Err(_) => Vec::new(),
         };
 
@@ -469,9 +1732,18 @@ impl<'a> AppGUI<'a> {
             .striped(true)
             .body(|body| {
                 body.rows(row_height, num_rows, |mut row| {
-                    let logline = &logs[row.index()];
+                    let (level, logline) = &logs[row.index()];
                     row.col(|ui| {
-                        ui.label(logline);
+                        // The theme colors read well on light and dark backgrounds
+                        let color = match level {
+                            log::Level::Error => Some(ui.visuals().error_fg_color),
+                            log::Level::Warn => Some(ui.visuals().warn_fg_color),
+                            _ => None,
+                        };
+                        match color {
+                            Some(color) => ui.label(egui::RichText::new(logline).color(color)),
+                            None => ui.label(logline),
+                        };
                     });
                 });
             });
@@ -488,9 +1760,42 @@ impl<'a> AppGUI<'a> {
                 }
 
                 // About section
-                ui.heading("About");
+                ui.heading(tr(Msg::AboutHeading));
                 ui.label(format!("Irate Goose v{}", VERSION));
-                ui.hyperlink_to("Home page", REPOSITORY);
+                ui.hyperlink_to(tr(Msg::HomePage), REPOSITORY);
+                ui.label(
+                    tr(Msg::SoundServerLabel).replace("{}", &sound_server_text(&self.sound_server)),
+                );
+                let log_file = {
+                    let settings = self.settings.borrow();
+                    settings
+                        .log_to_file
+                        .then(|| settings.log_file_path().ok())
+                        .flatten()
+                };
+                match log_file {
+                    Some(path) => {
+                        ui.label(tr(Msg::LogFileLabel).replace("{}", &path.display().to_string()));
+                    }
+                    None => {
+                        ui.label(tr(Msg::LogFileOff));
+                    }
+                }
+
+                ui.separator();
+
+                // Problem report section
+                ui.heading(tr(Msg::ReportProblemHeading));
+                ui.label(tr(Msg::ReportProblemExplanation));
+                ui.horizontal(|ui| {
+                    if ui.button(tr(Msg::CopyReport)).clicked() {
+                        ui.ctx().copy_text(self.problem_report());
+                        info!("Problem report copied to clipboard");
+                    }
+                    if ui.button(tr(Msg::SaveReport)).clicked() {
+                        self.on_save_report_click();
+                    }
+                });
 
                 ui.separator();
 
@@ -499,21 +1804,160 @@ impl<'a> AppGUI<'a> {
             });
     }
 
+    /// Assembles the problem report from the current application state.
+    fn problem_report(&self) -> String {
+        let logs = match self.log_buffer.lock() {
+            Ok(guard) => guard.to_vec(),
+            Err(_) => Vec::new(),
+        };
+        build_problem_report(
+            self.config_manager,
+            &self.settings.borrow(),
+            self.config_installed,
+            &logs,
+        )
+    }
+
+    /// Asks for a file name and saves the current settings there.
+    fn on_export_settings_click(&mut self) {
+        let Some(path) = FileDialog::new()
+            .set_title(tr(Msg::ExportSettingsDialogTitle))
+            .set_file_name("irate_goose_settings.toml")
+            .add_filter("TOML", &["toml"])
+            .save_file()
+        else {
+            return;
+        };
+        let result = self.settings.borrow().save_to_str().and_then(|text| {
+            std::fs::write(&path, text)
+                .with_context(|| format!("Failed to write {}", path.display()))
+        });
+        match result {
+            Ok(()) => info!("Settings exported to {}", path.display()),
+            Err(e) => self.show_modal(
+                tr(Msg::SettingsExportErrorHeader),
+                &tr(Msg::SettingsExportErrorMessage).replace("{}", &format!("{e:#}")),
+            ),
+        }
+    }
+
+    /// Asks for a settings file and replaces the current settings with it.
+    /// A file that can't be read or parsed leaves the settings unchanged.
+    fn on_import_settings_click(&mut self, ctx: &egui::Context) {
+        let Some(path) = FileDialog::new()
+            .set_title(tr(Msg::ImportSettingsDialogTitle))
+            .add_filter("TOML", &["toml"])
+            .pick_file()
+        else {
+            return;
+        };
+        let result = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))
+            .and_then(|text| AppSettings::load_from_str(&text));
+        match result {
+            Ok(imported) => {
+                self.apply_settings(ctx, imported);
+                info!("Settings imported from {}", path.display());
+            }
+            Err(e) => self.show_modal(
+                tr(Msg::SettingsImportErrorHeader),
+                &tr(Msg::SettingsImportErrorMessage).replace("{}", &format!("{e:#}")),
+            ),
+        }
+    }
+
+    /// Asks for a file name and saves the problem report there.
+    fn on_save_report_click(&mut self) {
+        let Some(path) = FileDialog::new()
+            .set_file_name("irate_goose_report.txt")
+            .save_file()
+        else {
+            return;
+        };
+        match std::fs::write(&path, self.problem_report()) {
+            Ok(_) => info!("Problem report saved to {}", path.display()),
+            Err(e) => self.show_modal(
+                tr(Msg::ReportSaveErrorHeader),
+                &tr(Msg::ReportSaveErrorMessage).replace("{}", &e.to_string()),
+            ),
+        }
+    }
+
+    /// Asks for a file name and saves the whole log there.
+    fn on_export_log_click(&mut self) {
+        let Some(path) = FileDialog::new()
+            .set_title(tr(Msg::ExportLogDialogTitle))
+            .set_file_name("irate_goose_log.txt")
+            .add_filter("Text", &["txt"])
+            .save_file()
+        else {
+            return;
+        };
+        let text = match self.log_buffer.lock() {
+            Ok(guard) => guard.to_vec().join("\n"),
+            Err(_) => String::new(),
+        };
+        match std::fs::write(&path, text) {
+            Ok(_) => info!("Log exported to {}", path.display()),
+            Err(e) => self.show_modal(
+                tr(Msg::ExportErrorHeader),
+                &tr(Msg::LogExportErrorMessage).replace("{}", &e.to_string()),
+            ),
+        }
+    }
+
+    /// Waiting time after a selection change before auto-apply writes the
+    /// config, so quickly browsing the list doesn't restart services each time.
+    const AUTO_APPLY_DELAY: Duration = Duration::from_millis(1500);
+
+    /// Writes the config for a changed selection when auto-apply is on,
+    /// debounced by `AUTO_APPLY_DELAY`.
+    fn auto_apply_selection(&mut self, ctx: &egui::Context) {
+        if !self.settings.borrow().auto_apply {
+            self.auto_apply_pending = None;
+            self.auto_apply_seen = self.selected_checksum;
+            return;
+        }
+        if self.selected_checksum != self.auto_apply_seen {
+            self.auto_apply_seen = self.selected_checksum;
+            self.auto_apply_pending = self.selected_checksum.map(|c| (c, Instant::now()));
+        }
+        let Some((checksum, since)) = self.auto_apply_pending else {
+            return;
+        };
+        let elapsed = since.elapsed();
+        if elapsed < Self::AUTO_APPLY_DELAY {
+            ctx.request_repaint_after(Self::AUTO_APPLY_DELAY - elapsed);
+            return;
+        }
+        self.auto_apply_pending = None;
+        if self.config_installed != Some(checksum) && self.selected_checksum == Some(checksum) {
+            info!("Auto-applying the selected file");
+            self.on_write_config_click();
+        }
+    }
+
     /// Handles the "Apply" button click for virtual device name.
     fn on_apply_device_name_click(&mut self, trimmed_text: &str) {
         debug_assert!(!trimmed_text.is_empty());
+        let previous_id = self.config_manager.device_id();
 
         // Update settings
         {
             let mut settings = self.settings.borrow_mut();
             settings.virtual_device_name = trimmed_text.to_string();
         }
+        self.update_active_profile();
 
         // Save settings
         self.write_settings();
 
         // Show success message
         info!("Device name updated to '{}'", trimmed_text);
+        // An identifier that follows the name makes this another device
+        if self.config_manager.device_id() != previous_id {
+            self.on_device_switched();
+        }
     }
 
     /// Handles the "Default" button click for virtual device name.
@@ -523,33 +1967,83 @@ impl<'a> AppGUI<'a> {
         self.on_apply_device_name_click(DEFAULT_VIRTUAL_DEVICE_NAME);
     }
 
+    /// Switches to the device with the given identifier, None for the one
+    /// derived from the device name.
+    pub(crate) fn on_apply_device_id_click(&mut self, device_id: Option<String>) {
+        self.settings.borrow_mut().device_id = device_id;
+        self.write_settings();
+        self.on_device_switched();
+    }
+
+    /// Checks the config again after the identifier of the configured
+    /// device changed, since the buttons now manage another device.
+    fn on_device_switched(&mut self) {
+        self.device_id_text = self.config_manager.device_id();
+        self.config_installed = Self::check_config_exists(self.config_manager);
+        self.backup_available = self.config_manager.has_backup();
+        self.device_health = None;
+        // The undo belongs to the config of the previous identifier
+        self.undo = None;
+        info!(
+            "Device identifier updated, config file is {}",
+            self.config_manager.config_path().display()
+        );
+    }
+
     /// Write current settings to disk.
     pub(crate) fn write_settings(&mut self) {
         let save_result = self.settings.borrow().save();
         if let Err(e) = save_result {
-            self.show_modal("Settings Error", &format!("Failed to save settings: {}", e));
+            self.show_modal(
+                tr(Msg::SettingsErrorHeader),
+                &tr(Msg::SettingsErrorMessage).replace("{}", &e.to_string()),
+            );
         }
     }
 }
 
-/// Resolve a `ThemePreference` to a concrete theme.
+/// Resolve an `AppTheme` to a concrete light or dark theme.
 ///
-/// For `System` the preference is obtained via D-Bus from the Freedesktop portal.
-/// Falls back to dark when detection fails.
-fn resolve_theme(preference: egui::ThemePreference) -> egui::ThemePreference {
+/// For `System` the preference is obtained via D-Bus from the Freedesktop portal,
+/// or from the KDE or GNOME settings without the portal. Falls back to dark
+/// when detection fails. High contrast is a dark theme.
+fn resolve_theme(preference: AppTheme) -> egui::Theme {
     match preference {
-        egui::ThemePreference::Dark => egui::ThemePreference::Dark,
-        egui::ThemePreference::Light => egui::ThemePreference::Light,
-        egui::ThemePreference::System => match detect_system_theme() {
-            Some(DetectedTheme::Dark) => egui::ThemePreference::Dark,
-            Some(DetectedTheme::Light) => egui::ThemePreference::Light,
-            None => egui::ThemePreference::Dark,
+        AppTheme::Dark | AppTheme::HighContrast => egui::Theme::Dark,
+        AppTheme::Light => egui::Theme::Light,
+        AppTheme::System => match detect_system_theme() {
+            Some(DetectedTheme::Dark) => egui::Theme::Dark,
+            Some(DetectedTheme::Light) => egui::Theme::Light,
+            None => egui::Theme::Dark,
         },
     }
 }
 
+/// Applies a theme preference to the egui context.
+///
+/// The dark visuals are replaced with high contrast ones when that theme is
+/// selected, and restored to the defaults otherwise.
+fn apply_theme(ctx: &egui::Context, preference: AppTheme) {
+    let dark_visuals = if preference == AppTheme::HighContrast {
+        high_contrast_visuals()
+    } else {
+        egui::Visuals::dark()
+    };
+    ctx.set_visuals_of(egui::Theme::Dark, dark_visuals);
+    ctx.set_visuals_of(egui::Theme::Light, egui::Visuals::light());
+    ctx.set_theme(resolve_theme(preference));
+}
+
 impl<'a> eframe::App for AppGUI<'a> {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.abandon_scan();
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_scan(ctx);
+        self.update_watcher(ctx);
+        self.auto_apply_selection(ctx);
+
         egui::TopBottomPanel::bottom("status_panel").show(ctx, |ui| {
             // Add status bar at the bottom
             ui.horizontal(|ui| {
@@ -560,6 +2054,54 @@ impl<'a> eframe::App for AppGUI<'a> {
                             .strong(),
                     );
                 }
+                if let Some(health) = self.device_health {
+                    let (text, color) = match health {
+                        DeviceHealth::Healthy => {
+                            (tr(Msg::DeviceHealthy), ui.visuals().weak_text_color())
+                        }
+                        DeviceHealth::FileMissing => {
+                            (tr(Msg::DeviceFileMissing), ui.visuals().error_fg_color)
+                        }
+                        DeviceHealth::FileDamaged => {
+                            (tr(Msg::DeviceFileDamaged), ui.visuals().error_fg_color)
+                        }
+                        DeviceHealth::NodeMissing => {
+                            (tr(Msg::DeviceNodeMissing), ui.visuals().warn_fg_color)
+                        }
+                    };
+                    ui.label(egui::RichText::new(text).color(color));
+                    ui.separator();
+                }
+                if let Some(running) = &self.scan {
+                    let progress = running.scan.progress();
+                    let fraction = if progress.total == 0 {
+                        0.0
+                    } else {
+                        progress.done as f32 / progress.total as f32
+                    };
+                    let text = tr(Msg::ScanningProgress)
+                        .replace("{done}", &progress.done.to_string())
+                        .replace("{total}", &progress.total.to_string());
+                    ui.add(
+                        egui::ProgressBar::new(fraction)
+                            .desired_width(Self::SCAN_PROGRESS_WIDTH)
+                            .text(text),
+                    );
+                    if ui.small_button(tr(Msg::Cancel)).clicked() {
+                        self.on_cancel_scan_click();
+                    }
+                    ui.separator();
+                }
+                // The filter cache is rebuilt while the file table is drawn
+                if !self.all_wav_index.is_empty()
+                    && let Some(filtered) = &self.filtered_wav_index
+                {
+                    let text = tr(Msg::ShownFilesCount)
+                        .replace("{shown}", &filtered.len().to_string())
+                        .replace("{total}", &self.all_wav_index.len().to_string());
+                    ui.label(text);
+                    ui.separator();
+                }
                 // Get the last line from the log buffer
                 let last_log = self
                     .log_buffer
@@ -571,60 +2113,114 @@ impl<'a> eframe::App for AppGUI<'a> {
             });
         });
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Create Virtual Device");
+            ui.heading(tr(Msg::MainHeading));
 
             // Determine if a file is selected
             let is_file_selected = self.selected_checksum.is_some();
+            let is_flac_selected = self.selected_is_flac();
 
             // Add the "Write Config" and the "Delete Config" buttons
             ui.horizontal(|ui| {
                 ui.style_mut().spacing.button_padding = (8.0, 6.0).into();
-                // The "Write config" button should be disabled if no file is selected
+                // The "Write config" button should be disabled if no usable file is selected
                 let button_text = match self.config_installed {
-                    Some(_) => "💾 Update device",
-                    None => "💾 Create device",
+                    Some(_) => tr(Msg::UpdateDevice),
+                    None => tr(Msg::CreateDevice),
                 };
                 let write_button = ui.add_enabled(
-                    is_file_selected,
-                    egui::Button::new(
-                        egui::RichText::new(button_text).heading()
-                    )
+                    is_file_selected && !is_flac_selected,
+                    egui::Button::new(egui::RichText::new(button_text).heading()),
                 );
                 if write_button.clicked() {
                     self.on_write_config_click();
                 }
                 if !write_button.enabled() && write_button.hovered() {
-                    write_button.on_hover_text("Select a IR file to proceed.");
+                    write_button.on_hover_text(if is_flac_selected {
+                        tr(Msg::FlacNeedsConversion)
+                    } else {
+                        tr(Msg::SelectFileToProceed)
+                    });
                 }
 
                 ui.style_mut().spacing.button_padding = (6.0, 4.0).into();
                 // The "Delete config" button should be disabled if config is not installed
                 let delete_button = ui.add_enabled(
                     self.config_installed.is_some(),
-                    egui::Button::new("❌ Remove device"),
+                    egui::Button::new(tr(Msg::RemoveDevice)),
                 );
                 if delete_button.clicked() {
-                    self.on_delete_config_click();
+                    self.on_remove_device_click();
+                }
+
+                let restore_button = ui
+                    .add_enabled(
+                        self.backup_available,
+                        egui::Button::new(tr(Msg::RestorePreviousConfig)),
+                    )
+                    .on_hover_text(tr(Msg::RestorePreviousConfigHint));
+                if restore_button.clicked() {
+                    self.on_restore_config_click();
+                }
+
+                let undo_hint = self.undo_hint();
+                let undo_button = ui
+                    .add_enabled(self.undo.is_some(), egui::Button::new(tr(Msg::Undo)))
+                    .on_hover_text(undo_hint)
+                    .on_disabled_hover_text(undo_hint);
+                if undo_button.clicked() {
+                    self.on_undo_click();
+                }
+
+                if !self.settings.borrow().restart_services
+                    && ui
+                        .button(tr(Msg::ApplyNow))
+                        .on_hover_text(tr(Msg::ApplyNowHint))
+                        .clicked()
+                {
+                    self.on_apply_now_click();
+                }
+
+                let preview_button =
+                    ui.add_enabled(is_file_selected, egui::Button::new(tr(Msg::PreviewConfig)));
+                if preview_button.clicked() {
+                    self.on_preview_config_click();
+                }
+
+                if self.settings.borrow().auto_apply {
+                    ui.label(
+                        egui::RichText::new(tr(Msg::AutoApplyActive))
+                            .color(ui.visuals().warn_fg_color)
+                            .strong(),
+                    )
+                    .on_hover_text(tr(Msg::AutoApplyActiveHint));
                 }
             });
 
             // Display current config status
             match self.config_installed {
                 Some(0) => {
-                    ui.label(egui::RichText::new("Warning: The configured IR file is damaged.")
-                        .color(egui::Color32::RED));
+                    ui.label(
+                        egui::RichText::new(tr(Msg::ConfiguredFileDamaged))
+                            .color(ui.visuals().error_fg_color),
+                    );
                 }
                 Some(checksum) => {
                     if let Some(wave) = self.find_wav_by_checksum(checksum) {
-                        ui.label(format!("Current IR file: {}", wave.relative_path.display()));
+                        ui.label(format!(
+                            "{} {}",
+                            tr(Msg::CurrentIrFile),
+                            wave.relative_path.display()
+                        ));
                     } else {
-                        ui.label(egui::RichText::new("Warning: The configured IR file is not found in the current IR directory.")
-                            .color(egui::Color32::RED))
-                            .on_hover_text("If you create a new virtual device, the content of the IR file currently used will be lost.");
+                        ui.label(
+                            egui::RichText::new(tr(Msg::ConfiguredFileNotFound))
+                                .color(ui.visuals().error_fg_color),
+                        )
+                        .on_hover_text(tr(Msg::ConfiguredFileNotFoundHint));
                     }
                 }
                 None => {
-                    ui.label("No config installed");
+                    ui.label(tr(Msg::NoConfigInstalled));
                 }
             }
 
@@ -637,52 +2233,69 @@ impl<'a> eframe::App for AppGUI<'a> {
                 let min_button_width = 80.0; // Minimum width, buttons will expand if needed
 
                 // Files tab
-                if ui.add(
-                    egui::Button::selectable(
-                        self.selected_tab == Tab::Files,
-                        egui::RichText::new("♪ Files").heading(),
+                if ui
+                    .add(
+                        egui::Button::selectable(
+                            self.selected_tab == Tab::Files,
+                            egui::RichText::new(tr(Msg::TabFiles)).heading(),
+                        )
+                        .min_size(egui::vec2(min_button_width, ui.spacing().interact_size.y)),
                     )
-                    .min_size(egui::vec2(min_button_width, ui.spacing().interact_size.y))
-                ).clicked() {
+                    .clicked()
+                {
                     self.selected_tab = Tab::Files;
                 }
 
                 // Options tab
-                if ui.add(
-                    egui::Button::selectable(
-                        self.selected_tab == Tab::Options,
-                        egui::RichText::new("⚙ Options").heading(),
+                if ui
+                    .add(
+                        egui::Button::selectable(
+                            self.selected_tab == Tab::Options,
+                            egui::RichText::new(tr(Msg::TabOptions)).heading(),
+                        )
+                        .min_size(egui::vec2(min_button_width, ui.spacing().interact_size.y)),
                     )
-                    .min_size(egui::vec2(min_button_width, ui.spacing().interact_size.y))
-                ).clicked() {
+                    .clicked()
+                {
                     self.selected_tab = Tab::Options;
                 }
 
                 // Log tab
-                if ui.add(
-                    egui::Button::selectable(
-                        self.selected_tab == Tab::Log,
-                        egui::RichText::new("🖹 Log").heading(),
+                if ui
+                    .add(
+                        egui::Button::selectable(
+                            self.selected_tab == Tab::Log,
+                            egui::RichText::new(tr(Msg::TabLog)).heading(),
+                        )
+                        .min_size(egui::vec2(min_button_width, ui.spacing().interact_size.y)),
                     )
-                    .min_size(egui::vec2(min_button_width, ui.spacing().interact_size.y))
-                ).clicked() {
+                    .clicked()
+                {
                     self.selected_tab = Tab::Log;
                 }
 
                 // Help tab
-                if ui.add(
-                    egui::Button::selectable(
-                        self.selected_tab == Tab::Help,
-                        egui::RichText::new("❓ Help").heading(),
+                if ui
+                    .add(
+                        egui::Button::selectable(
+                            self.selected_tab == Tab::Help,
+                            egui::RichText::new(tr(Msg::TabHelp)).heading(),
+                        )
+                        .min_size(egui::vec2(min_button_width, ui.spacing().interact_size.y)),
                     )
-                    .min_size(egui::vec2(min_button_width, ui.spacing().interact_size.y))
-                ).clicked() {
+                    .clicked()
+                {
                     self.selected_tab = Tab::Help;
                 }
             });
 
             ui.separator();
 
+            if self.selected_tab != self.settings.borrow().selected_tab {
+                self.settings.borrow_mut().selected_tab = self.selected_tab;
+                self.write_settings();
+            }
+
             // Tab content
             match self.selected_tab {
                 Tab::Files => {
@@ -699,27 +2312,18 @@ impl<'a> eframe::App for AppGUI<'a> {
                 }
             }
 
-            // Render modal if open
-            if self.modal_open {
-                let modal = egui::Modal::new(egui::Id::new("message_modal")).show(ctx, |ui| {
-                    ui.set_width(300.0);
-
-                    // Header
-                    ui.heading(&self.modal_header);
-
-                    // Message body
-                    ui.label(&self.modal_message);
-
-                    ui.separator();
+            self.render_config_preview(ctx);
 
-                    // Continue button
-                    if ui.button("Continue").clicked() {
-                        ui.close();
+            // Render modal if open
+            if let Some(modal) = &mut self.modal {
+                let (should_close, confirmed) = modal.show(ctx);
+                // Closed before the action runs, which may open another one
+                if should_close && let Some(closed) = self.modal.take() {
+                    match confirmed {
+                        Some(action) => self.on_modal_action(ctx, action, closed.dont_ask),
+                        // A profile switch waiting for the write is cancelled
+                        None => self.finish_profile_switch(),
                     }
-                });
-
-                if modal.should_close() {
-                    self.modal_open = false;
                 }
             }
         });
@@ -1,5 +1,8 @@
+mod devices;
 mod files;
 mod main_gui;
+mod profiles;
+pub(crate) mod strings;
 pub mod theme;
 
 pub use main_gui::AppGUI;
@@ -0,0 +1,194 @@
+use eframe::egui;
+use log::{info, warn};
+
+use super::AppGUI;
+use super::strings::{Msg, tr};
+use crate::settings::Profile;
+
+/// Settings from before a profile switch, restored if the config of the
+/// profile is not written.
+pub(crate) struct ProfileSwitch {
+    active_profile: Option<usize>,
+    device_name: String,
+}
+
+impl<'a> AppGUI<'a> {
+    /// Renders the profile selector and the controls to add, rename and
+    /// delete profiles.
+    pub(crate) fn render_profiles(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr(Msg::ProfilesHeading));
+        ui.label(tr(Msg::ProfilesExplanation));
+
+        let (names, active) = {
+            let settings = self.settings.borrow();
+            let names: Vec<String> = settings.profiles.iter().map(|p| p.name.clone()).collect();
+            (names, settings.active_profile())
+        };
+        ui.horizontal(|ui| {
+            let selected_text = active
+                .and_then(|i| names.get(i).cloned())
+                .unwrap_or_else(|| tr(Msg::NoProfile).to_string());
+            let mut picked = None;
+            egui::ComboBox::from_id_salt("profiles")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (i, name) in names.iter().enumerate() {
+                        if ui.selectable_label(active == Some(i), name).clicked() {
+                            picked = Some(i);
+                        }
+                    }
+                });
+            if let Some(index) = picked
+                && active != Some(index)
+            {
+                self.switch_profile(index);
+            }
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.profile_name_text)
+                    .hint_text(tr(Msg::ProfileNameHint)),
+            );
+            let name = self.profile_name_text.trim().to_string();
+            let name_ok = !name.is_empty() && !names.contains(&name);
+            if ui
+                .add_enabled(name_ok, egui::Button::new(tr(Msg::AddProfile)))
+                .clicked()
+            {
+                self.on_add_profile_click(name.clone());
+            }
+            if ui
+                .add_enabled(
+                    name_ok && active.is_some(),
+                    egui::Button::new(tr(Msg::RenameProfile)),
+                )
+                .clicked()
+            {
+                self.on_rename_profile_click(name);
+            }
+            if ui
+                .add_enabled(active.is_some(), egui::Button::new(tr(Msg::DeleteProfile)))
+                .clicked()
+            {
+                self.on_delete_profile_click();
+            }
+        });
+    }
+
+    /// Creates a profile from the current device name and IR file and
+    /// makes it active.
+    fn on_add_profile_click(&mut self, name: String) {
+        let checksum = self
+            .selected_checksum
+            .or(self.config_installed)
+            .unwrap_or(0);
+        {
+            let mut settings = self.settings.borrow_mut();
+            let profile = Profile {
+                name: name.clone(),
+                device_name: settings.virtual_device_name.clone(),
+                checksum,
+            };
+            settings.profiles.push(profile);
+            settings.active_profile = Some(settings.profiles.len() - 1);
+        }
+        self.profile_name_text.clear();
+        self.write_settings();
+        info!("Profile '{}' added", name);
+    }
+
+    /// Renames the active profile.
+    fn on_rename_profile_click(&mut self, name: String) {
+        if let Some(profile) = self.settings.borrow_mut().active_profile_mut() {
+            info!("Profile '{}' renamed to '{}'", profile.name, name);
+            profile.name = name;
+        }
+        self.profile_name_text.clear();
+        self.write_settings();
+    }
+
+    /// Deletes the active profile. The device keeps its current name and file.
+    fn on_delete_profile_click(&mut self) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            if let Some(index) = settings.active_profile() {
+                let profile = settings.profiles.remove(index);
+                info!("Profile '{}' deleted", profile.name);
+            }
+            settings.active_profile = None;
+        }
+        self.write_settings();
+    }
+
+    /// Activates a profile: takes over its device name and writes the
+    /// config with its IR file. The profile stays active only if the
+    /// config is written.
+    fn switch_profile(&mut self, index: usize) {
+        let Some(profile) = self.settings.borrow().profiles.get(index).cloned() else {
+            return;
+        };
+        if self.find_wav_by_checksum(profile.checksum).is_none() {
+            warn!(
+                "The IR file of profile '{}' is not in the scanned directory",
+                profile.name
+            );
+            return;
+        }
+        // The config is written with the device name from the settings,
+        // which are saved once it is written
+        {
+            let mut settings = self.settings.borrow_mut();
+            self.profile_switch = Some(ProfileSwitch {
+                active_profile: settings.active_profile,
+                device_name: settings.virtual_device_name.clone(),
+            });
+            settings.active_profile = Some(index);
+            settings.virtual_device_name = profile.device_name.clone();
+        }
+        self.device_name_text = profile.device_name.clone();
+        self.device_id_text = self.config_manager.device_id();
+        info!("Switching to profile '{}'", profile.name);
+
+        self.selected_checksum = Some(profile.checksum);
+        if let Some(row) = self
+            .filtered_wav_index
+            .as_ref()
+            .and_then(|filtered| filtered.index_of_checksum(profile.checksum))
+        {
+            self.scroll_to_row = Some(row);
+        }
+        self.on_write_config_click();
+        if !self.write_awaits_confirmation() {
+            self.finish_profile_switch();
+        }
+    }
+
+    /// Restores the settings from before a profile switch whose config was
+    /// not written. Does nothing once the config is written.
+    pub(crate) fn finish_profile_switch(&mut self) {
+        let Some(previous) = self.profile_switch.take() else {
+            return;
+        };
+        warn!("Config not written, the profile was not switched");
+        {
+            let mut settings = self.settings.borrow_mut();
+            settings.active_profile = previous.active_profile;
+            settings.virtual_device_name = previous.device_name.clone();
+        }
+        self.device_name_text = previous.device_name;
+        self.device_id_text = self.config_manager.device_id();
+    }
+
+    /// Stores the current device name and installed IR file in the active
+    /// profile, so it keeps up with changes made while it is active.
+    pub(crate) fn update_active_profile(&mut self) {
+        let installed = self.config_installed;
+        let mut settings = self.settings.borrow_mut();
+        let device_name = settings.virtual_device_name.clone();
+        if let Some(profile) = settings.active_profile_mut() {
+            profile.device_name = device_name;
+            if let Some(checksum) = installed {
+                profile.checksum = checksum;
+            }
+        }
+    }
+}
@@ -0,0 +1,1124 @@
+//! Central registry of user-facing GUI strings.
+//!
+//! Every string shown in the interface has a `Msg` identifier. The text is
+//! looked up with `tr` in the table of the current language, so a missing or
+//! misspelled identifier is a compile error. Translations may lag behind:
+//! strings missing from a translation table fall back to English.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::config_manager::{PreflightError, SoundServer};
+use crate::settings::Language;
+
+/// Identifiers of user-facing GUI strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Msg {
+    // === Main window ===
+    MainHeading,
+    CreateDevice,
+    UpdateDevice,
+    RemoveDevice,
+    SelectFileToProceed,
+    FlacNeedsConversion,
+    ConfiguredFileDamaged,
+    CurrentIrFile,
+    ConfiguredFileNotFound,
+    ConfiguredFileNotFoundHint,
+    NoConfigInstalled,
+    AutoApplyActive,
+    AutoApplyActiveHint,
+    DeviceHealthy,
+    DeviceFileMissing,
+    DeviceFileDamaged,
+    DeviceNodeMissing,
+    TabFiles,
+    TabOptions,
+    TabLog,
+    TabHelp,
+    Continue,
+
+    // === Files tab ===
+    LocatedIrFiles,
+    SampleRateLabel,
+    SampleRateAll,
+    SampleRateOther,
+    SearchHint,
+    ClearSearch,
+    SearchDescriptions,
+    SearchRegex,
+    SearchRegexHint,
+    DescribedOnly,
+    FavoritesOnly,
+    FamilyLabel,
+    AllFamilies,
+    ColumnFiles,
+    ColumnModified,
+    ColumnChannels,
+    ColumnPoints,
+    ColumnDescription,
+    DamagedTag,
+    AddFavorite,
+    RemoveFavorite,
+    NoFilesIntro,
+    NoFilesFound,
+    NoFilesHelpHint,
+    GitHubLink,
+    NoFilesMatchFilter,
+    SourcePrefix,
+    CreditsPrefix,
+    NoDescription,
+    ShowMore,
+    ShowLess,
+    ChecksumLabel,
+    FormatLabel,
+    FormatValue,
+    CopyChecksum,
+    CopyAbsolutePath,
+    CopyRelativePath,
+    OpenContainingFolder,
+    FileNameCollision,
+    SampleRateMismatch,
+    SampleRateMismatchHelp,
+    SampleRateMismatchHeader,
+    SampleRateMismatchMessage,
+    NotesLabel,
+    NotesHint,
+    ClearNote,
+    CompareHeading,
+    ClearCompare,
+    MarkedFiles,
+    ExportMarked,
+    ClearMarks,
+    ExportDialogTitle,
+    ExportList,
+    ExportListDialogTitle,
+    CompareChannels,
+    CompareDuration,
+    ComparePoints,
+    DurationMs,
+
+    // === Options tab ===
+    IrDirectoryHeading,
+    IrDirectoryExplanation,
+    DirectoryLabel,
+    DirectoryHint,
+    RecentDirectories,
+    ExtraDirectoriesLabel,
+    AddExtraDirectory,
+    RemoveExtraDirectory,
+    SelectExtraDirectoryDialogTitle,
+    SelectDirectory,
+    Rescan,
+    ScanningProgress,
+    ShownFilesCount,
+    SelectDirectoryDialogTitle,
+    SelectFile,
+    SelectFileDialogTitle,
+    ProfilesHeading,
+    ProfilesExplanation,
+    NoProfile,
+    ProfileNameHint,
+    AddProfile,
+    RenameProfile,
+    DeleteProfile,
+    DeviceNameHeading,
+    DeviceNameExplanation,
+    CurrentlyConfigured,
+    DeviceNameLabel,
+    DeviceNameHint,
+    DeviceIdHeading,
+    DeviceIdExplanation,
+    DeviceIdLabel,
+    DeviceIdHint,
+    Apply,
+    Default,
+    InstalledDevicesHeading,
+    NoInstalledDevices,
+    DefaultDeviceId,
+    UnknownIrFile,
+    ManageDevice,
+    RemoveInstalledDevice,
+    OutputDeviceHeading,
+    OutputDeviceExplanation,
+    OutputDeviceLabel,
+    AutoOutputDevice,
+    UnknownDevice,
+    InvalidDevice,
+    ReloadDevices,
+    HrirStorageHeading,
+    HrirStorageExplanation,
+    HrirStorageDefault,
+    CopyIrFile,
+    CopyIrFileHint,
+    SelectHrirDirectoryDialogTitle,
+    HrirStorageRefusedHeader,
+    HrirStorageRefusedMessage,
+    DescriptionsFileHeading,
+    DescriptionsFileExplanation,
+    DescriptionsFileNone,
+    SelectDescriptionsFileDialogTitle,
+    ThemeHeading,
+    ThemeExplanation,
+    ThemeLight,
+    ThemeDark,
+    ThemeHighContrast,
+    ThemeSystem,
+    LanguageHeading,
+    LanguageExplanation,
+    DensityHeading,
+    DensityExplanation,
+    DensityCompact,
+    DensityComfortable,
+    AutoApplyHeading,
+    AutoApplyExplanation,
+    RestartServicesHeading,
+    RestartServicesExplanation,
+    RestartUnitsExplanation,
+    AddUnitLabel,
+    AddUnitHint,
+    AddUnit,
+    ReloadPipewireHeader,
+    ReloadPipewireMessage,
+    ApplyNow,
+    ApplyNowHint,
+    RestartErrorHeader,
+    RestartErrorMessage,
+    OutputGainHeading,
+    OutputGainExplanation,
+    OutputGainLabel,
+    ChannelLayoutHeading,
+    ChannelLayoutExplanation,
+    ScanDepthHeading,
+    ScanDepthExplanation,
+    ScanDepthLimit,
+    FollowSymlinks,
+    TableColumnsHeading,
+    ShowSizeColumn,
+    ColumnSize,
+    SizeLabel,
+    SizeBytes,
+    SizeKib,
+    SizeMib,
+    ShowPointsColumn,
+    LogFileHeading,
+    LogToFile,
+    LogFileLabel,
+    LogFileOff,
+    ConfirmationsHeading,
+    ConfirmDeviceRemoval,
+    FavoritesHeading,
+    FavoritesFirst,
+    DuplicatesHeading,
+    HideDuplicates,
+    DuplicateTag,
+    PlayTest,
+    PlayTestHint,
+    PlayTestUnsupported,
+    WatchDirectoryHeading,
+    WatchDirectoryExplanation,
+    ScanCacheHeading,
+    ScanCacheExplanation,
+    ClearScanCache,
+    ExportSettings,
+    ImportSettings,
+    ExportSettingsDialogTitle,
+    ImportSettingsDialogTitle,
+    SettingsExportErrorHeader,
+    SettingsExportErrorMessage,
+    SettingsImportErrorHeader,
+    SettingsImportErrorMessage,
+    ResetSettings,
+    ShowTestModal,
+
+    // === Help tab ===
+    AboutHeading,
+    HomePage,
+    ReportProblemHeading,
+    ReportProblemExplanation,
+    CopyReport,
+    PreviewConfig,
+    Undo,
+    UndoCreateHint,
+    UndoUpdateHint,
+    UndoRemoveHint,
+    NothingToUndo,
+    RestorePreviousConfig,
+    RestorePreviousConfigHint,
+    ConfigPreviewHeader,
+    CopyToClipboard,
+    SaveReport,
+
+    // === Modal dialogs ===
+    PickAnotherDirectory,
+    Cancel,
+    ResetSettingsHeader,
+    ResetSettingsMessage,
+    ResetSettingsConfirm,
+    RemoveDeviceHeader,
+    RemoveDeviceMessage,
+    RemoveDeviceConfirm,
+    DontAskAgain,
+    ChannelMismatchHeader,
+    ChannelMismatchMessage,
+    WriteAnyway,
+    PipewireMissingHeader,
+    PipewireMissingMessage,
+    NoSoundServer,
+    SoundServerLabel,
+    LogLevelLabel,
+    LogLevelAll,
+    LogLevelWarn,
+    LogLevelError,
+    ClearLog,
+    ExportLog,
+    ExportLogDialogTitle,
+    LogExportErrorMessage,
+    TestModalHeader,
+    TestModalMessage,
+    SettingsErrorHeader,
+    SettingsErrorMessage,
+    ReportSaveErrorHeader,
+    ReportSaveErrorMessage,
+    ExportErrorHeader,
+    ExportErrorMessage,
+    DirectoryNotFoundHeader,
+    DirectoryNotFoundMessage,
+    NotADirectoryHeader,
+    NotADirectoryMessage,
+    NotAWavFileHeader,
+    NotAWavFileMessage,
+    DirectoryNotAccessibleHeader,
+    DirectoryNotAccessibleMessage,
+    RescanErrorHeader,
+    RescanErrorMessage,
+    ArchivesFoundHeader,
+    ArchivesFoundMessage,
+
+    // === Terminal interface ===
+    TuiFilesFound,
+    TuiFilterLabel,
+    TuiKeysHint,
+    TuiDeviceWritten,
+    TuiDeviceRemoved,
+    TuiError,
+    TuiConfirmWrite,
+    TuiConfirmRemove,
+}
+
+/// Language used by `tr`, stored as the `Language` discriminant.
+static CURRENT_LANGUAGE: AtomicU8 = AtomicU8::new(Language::English as u8);
+
+/// Sets the language of all GUI strings. Takes effect on the next frame.
+pub(crate) fn set_language(language: Language) {
+    CURRENT_LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+/// Returns the text of a GUI string in the current language.
+pub(crate) fn tr(msg: Msg) -> &'static str {
+    let language = CURRENT_LANGUAGE.load(Ordering::Relaxed);
+    let translated = if language == Language::Russian as u8 {
+        russian(msg)
+    } else {
+        None
+    };
+    translated.unwrap_or_else(|| english(msg))
+}
+
+/// Description of the detected sound server, for the user.
+pub(crate) fn sound_server_text(server: &SoundServer) -> String {
+    match server {
+        SoundServer::PipeWire { version: Some(v) } => format!("PipeWire {v}"),
+        SoundServer::PipeWire { version: None } => "PipeWire".to_string(),
+        SoundServer::Other(name) => name.clone(),
+        SoundServer::NotFound => tr(Msg::NoSoundServer).to_string(),
+    }
+}
+
+/// Header and message telling the user why `file` can't be written.
+pub(crate) fn preflight_error_text(error: &PreflightError, file: &str) -> (&'static str, String) {
+    match error {
+        PreflightError::NotWav => (
+            tr(Msg::NotAWavFileHeader),
+            tr(Msg::FlacNeedsConversion).to_string(),
+        ),
+        PreflightError::NoPipeWire(server) => (
+            tr(Msg::PipewireMissingHeader),
+            tr(Msg::PipewireMissingMessage).replace("{}", &sound_server_text(server)),
+        ),
+        PreflightError::ChannelMismatch {
+            channels,
+            expected,
+            layout,
+        } => (
+            tr(Msg::ChannelMismatchHeader),
+            tr(Msg::ChannelMismatchMessage)
+                .replace("{file}", file)
+                .replace("{channels}", &channels.to_string())
+                .replace("{layout}", layout.name())
+                .replace("{expected}", &expected.to_string()),
+        ),
+    }
+}
+
+/// English text of every GUI string.
+fn english(msg: Msg) -> &'static str {
+    match msg {
+        // === Main window ===
+        Msg::MainHeading => "Create Virtual Device",
+        Msg::CreateDevice => "💾 Create device",
+        Msg::UpdateDevice => "💾 Update device",
+        Msg::RemoveDevice => "❌ Remove device",
+        Msg::SelectFileToProceed => "Select a IR file to proceed.",
+        Msg::FlacNeedsConversion => {
+            "The PipeWire convolver only loads WAV files. Convert this FLAC file to WAV to use it for the device."
+        }
+        Msg::ConfiguredFileDamaged => "Warning: The configured IR file is damaged.",
+        Msg::CurrentIrFile => "Current IR file:",
+        Msg::ConfiguredFileNotFound => {
+            "Warning: The configured IR file is not found in the current IR directory."
+        }
+        Msg::ConfiguredFileNotFoundHint => {
+            "If you create a new virtual device, the content of the IR file currently used will be lost."
+        }
+        Msg::NoConfigInstalled => "No config installed",
+        Msg::AutoApplyActive => "⚡ Auto-apply on",
+        Msg::AutoApplyActiveHint => {
+            "Selecting a file updates the device automatically. Turn off in Options."
+        }
+        Msg::DeviceHealthy => "✔ Device OK",
+        Msg::DeviceFileMissing => "⚠ Device IR file is missing",
+        Msg::DeviceFileDamaged => "⚠ Device IR file is damaged",
+        Msg::DeviceNodeMissing => "⚠ Device is not active in PipeWire, try updating it",
+        Msg::TabFiles => "♪ Files",
+        Msg::TabOptions => "⚙ Options",
+        Msg::TabLog => "🖹 Log",
+        Msg::TabHelp => "❓ Help",
+        Msg::Continue => "Continue",
+
+        // === Files tab ===
+        Msg::LocatedIrFiles => "Located IR Files",
+        Msg::SampleRateLabel => "Sample rate:",
+        Msg::SampleRateAll => "All",
+        Msg::SampleRateOther => "Other",
+        Msg::SearchHint => "Search IR files...",
+        Msg::ClearSearch => "Clear",
+        Msg::SearchDescriptions => "Search descriptions too",
+        Msg::SearchRegex => "Regex",
+        Msg::SearchRegexHint => {
+            "Treat the search text as a case-insensitive regular expression, e.g. sadie_0[12]\\d"
+        }
+        Msg::DescribedOnly => "Described only",
+        Msg::FavoritesOnly => "Favorites only",
+        Msg::FamilyLabel => "HRTF family:",
+        Msg::AllFamilies => "All families",
+        Msg::ColumnFiles => "Files",
+        Msg::ColumnModified => "Modified",
+        Msg::ColumnChannels => "Ch.",
+        Msg::ColumnPoints => "Points",
+        Msg::ColumnDescription => "Description",
+        Msg::DamagedTag => "(Damaged)",
+        Msg::AddFavorite => "Add to favorites",
+        Msg::RemoveFavorite => "Remove from favorites",
+        Msg::NoFilesIntro => {
+            "Irate Goose needs IR (Impulse Response) files to create a virtual surround sound effect."
+        }
+        Msg::NoFilesFound => "No IR files were found in the selected directory.",
+        Msg::NoFilesHelpHint => "Look at the Help tab for ways to obtain IR files.",
+        Msg::GitHubLink => " Irate Goose GitHub",
+        Msg::NoFilesMatchFilter => {
+            "No .wav files matching this filter were found in the directory."
+        }
+        Msg::SourcePrefix => "Source:",
+        Msg::CreditsPrefix => "By:",
+        Msg::NoDescription => "No description for the selected files.",
+        Msg::ShowMore => "Show more",
+        Msg::ShowLess => "Show less",
+        Msg::ChecksumLabel => "Checksum:",
+        Msg::FormatLabel => "Format:",
+        Msg::FormatValue => "{rate} Hz, {bits}-bit",
+        Msg::CopyChecksum => "📋 Copy",
+        Msg::CopyAbsolutePath => "Copy absolute path",
+        Msg::CopyRelativePath => "Copy relative path",
+        Msg::OpenContainingFolder => "Open containing folder",
+        Msg::FileNameCollision => {
+            "⚠ Another scanned file is also named {}. The device file name is ambiguous."
+        }
+        Msg::SampleRateMismatch => {
+            "ℹ This file is {file} Hz, but PipeWire runs at {graph} Hz. The IR will be resampled."
+        }
+        Msg::SampleRateMismatchHelp => "What does this mean?",
+        Msg::SampleRateMismatchHeader => "Sample Rate Differs",
+        Msg::SampleRateMismatchMessage => {
+            "The device was written with a {file} Hz file, but PipeWire runs at {graph} Hz. The IR will be resampled, which may cost some quality. If there is a {graph} Hz version of this file, use that one."
+        }
+        Msg::NotesLabel => "Notes:",
+        Msg::NotesHint => "Your notes about this file, e.g. \"good for FPS\"",
+        Msg::ClearNote => "✖ Clear",
+        Msg::CompareHeading => "Comparison",
+        Msg::ClearCompare => "Clear comparison",
+        Msg::MarkedFiles => "Marked files: {}",
+        Msg::ExportMarked => "📄 Export marked to CSV...",
+        Msg::ClearMarks => "Clear marks",
+        Msg::ExportDialogTitle => "Export Marked Files",
+        Msg::ExportList => "Export list...",
+        Msg::ExportListDialogTitle => "Export File List",
+        Msg::CompareChannels => "Channels:",
+        Msg::CompareDuration => "Duration:",
+        Msg::ComparePoints => "Points:",
+        Msg::DurationMs => "{} ms",
+
+        // === Options tab ===
+        Msg::IrDirectoryHeading => "IR files Directory",
+        Msg::IrDirectoryExplanation => "Set the directory containing IR files for surround sound:",
+        Msg::DirectoryLabel => "Directory:",
+        Msg::DirectoryHint => "Path to IR files",
+        Msg::RecentDirectories => "Recent",
+        Msg::ExtraDirectoriesLabel => {
+            "Additional directories, scanned together with the one above:"
+        }
+        Msg::AddExtraDirectory => "➕ Add directory",
+        Msg::RemoveExtraDirectory => "❌ Remove",
+        Msg::SelectExtraDirectoryDialogTitle => "Select Additional IR Directory",
+        Msg::SelectDirectory => "Select",
+        Msg::Rescan => "Rescan",
+        Msg::ScanningProgress => "Scanning… {done}/{total}",
+        Msg::ShownFilesCount => "Showing {shown} of {total}",
+        Msg::SelectDirectoryDialogTitle => "Select IR Files Directory",
+        Msg::SelectFile => "Select file",
+        Msg::SelectFileDialogTitle => "Select IR File",
+        Msg::ProfilesHeading => "Profiles",
+        Msg::ProfilesExplanation => {
+            "A profile remembers a device name and an IR file. Picking a profile applies it."
+        }
+        Msg::NoProfile => "No profile",
+        Msg::ProfileNameHint => "Profile name",
+        Msg::AddProfile => "Add",
+        Msg::RenameProfile => "Rename",
+        Msg::DeleteProfile => "Delete",
+        Msg::DeviceNameHeading => "Virtual Device Name",
+        Msg::DeviceNameExplanation => {
+            "Set the name of the virtual audio device that will appear in your system audio settings:"
+        }
+        Msg::CurrentlyConfigured => "Currently configured:",
+        Msg::DeviceNameLabel => "Device name:",
+        Msg::DeviceNameHint => "Virtual device name",
+        Msg::DeviceIdHeading => "Device Identifier",
+        Msg::DeviceIdExplanation => {
+            "Each device name gets its own identifier, so devices with different names coexist (e.g. one per headphone). Set one by hand to keep it when the name changes; Default follows the name again:"
+        }
+        Msg::DeviceIdLabel => "Identifier:",
+        Msg::DeviceIdHint => "e.g. headphones-2",
+        Msg::Apply => "Apply",
+        Msg::Default => "Default",
+        Msg::InstalledDevicesHeading => "Installed Devices",
+        Msg::NoInstalledDevices => "No virtual device is installed.",
+        Msg::DefaultDeviceId => "(default)",
+        Msg::UnknownIrFile => "(file not in the list)",
+        Msg::ManageDevice => "Manage",
+        Msg::RemoveInstalledDevice => "❌ Remove",
+        Msg::OutputDeviceHeading => "Output Device",
+        Msg::OutputDeviceExplanation => {
+            "Select the audio sink where the virtual surround device will output sound:"
+        }
+        Msg::OutputDeviceLabel => "Output device:",
+        Msg::AutoOutputDevice => "Auto\nLet Pipewire decide",
+        Msg::UnknownDevice => "UNKNOWN DEVICE",
+        Msg::InvalidDevice => "Invalid",
+        Msg::ReloadDevices => "Reload",
+        Msg::HrirStorageHeading => "IR File Storage",
+        Msg::HrirStorageExplanation => {
+            "Folder where the selected IR file is copied for the virtual device. Applies the next time the device is created or updated:"
+        }
+        Msg::HrirStorageDefault => "Next to the PipeWire config",
+        Msg::CopyIrFile => "Copy IR file",
+        Msg::CopyIrFileHint => {
+            "When off, the device uses the IR file where it is. Moving or deleting that file breaks the device"
+        }
+        Msg::SelectHrirDirectoryDialogTitle => "Select IR File Storage Directory",
+        Msg::HrirStorageRefusedHeader => "Storage Location Not Allowed",
+        Msg::HrirStorageRefusedMessage => {
+            "The IR files in {} are inside this location. Choose a storage location outside your IR file library."
+        }
+        Msg::DescriptionsFileHeading => "Descriptions File",
+        Msg::DescriptionsFileExplanation => {
+            "CSV file with your own HRTF descriptions, in the columns of the built-in database (HRIR;HRTF;Configuration;Description;Source;Credits;Points). Its entries replace or extend the built-in ones:"
+        }
+        Msg::DescriptionsFileNone => "None, built-in descriptions only",
+        Msg::SelectDescriptionsFileDialogTitle => "Select Descriptions File",
+        Msg::ThemeHeading => "UI Theme",
+        Msg::ThemeExplanation => "Select the application visual theme:",
+        Msg::ThemeLight => "🌞 Light",
+        Msg::ThemeDark => "🌙 Dark",
+        Msg::ThemeHighContrast => "◑ High contrast",
+        Msg::ThemeSystem => "🌟 System",
+        Msg::LanguageHeading => "Language",
+        Msg::LanguageExplanation => "Select the language of the interface:",
+        Msg::DensityHeading => "Table density",
+        Msg::DensityExplanation => "Select the row height of the file list:",
+        Msg::DensityCompact => "Compact",
+        Msg::DensityComfortable => "Comfortable",
+        Msg::AutoApplyHeading => "Auto-apply",
+        Msg::AutoApplyExplanation => {
+            "Auto-apply on select: update the device shortly after another file is selected"
+        }
+        Msg::RestartServicesHeading => "Service Restart",
+        Msg::RestartServicesExplanation => {
+            "Restart PipeWire services automatically after every device change (briefly interrupts all audio)"
+        }
+        Msg::RestartUnitsExplanation => {
+            "Systemd user services restarted to apply changes (missing ones are skipped):"
+        }
+        Msg::AddUnitLabel => "Other service:",
+        Msg::AddUnitHint => "unit name",
+        Msg::AddUnit => "Add",
+        Msg::ReloadPipewireHeader => "Reload PipeWire",
+        Msg::ReloadPipewireMessage => {
+            "The config was written, but PipeWire was not restarted. Click \"Apply now\" or restart the PipeWire services yourself to load the device."
+        }
+        Msg::ApplyNow => "⟳ Apply now",
+        Msg::ApplyNowHint => "Restart the PipeWire services to load the written config",
+        Msg::RestartErrorHeader => "Restart Failed",
+        Msg::RestartErrorMessage => "Could not restart the PipeWire services: {}",
+        Msg::OutputGainHeading => "Output Gain",
+        Msg::OutputGainExplanation => {
+            "Makes quiet or loud IR files match in level. Takes effect when the device is written next:"
+        }
+        Msg::OutputGainLabel => "Gain:",
+        Msg::ChannelLayoutHeading => "Channel Layout",
+        Msg::ChannelLayoutExplanation => {
+            "Speaker layout of the virtual device. Takes effect when the device is written next:"
+        }
+        Msg::TableColumnsHeading => "Table Columns",
+        Msg::ShowSizeColumn => "Show the size of each file",
+        Msg::ColumnSize => "Size",
+        Msg::SizeLabel => "Size:",
+        Msg::SizeBytes => "{} B",
+        Msg::SizeKib => "{} KiB",
+        Msg::SizeMib => "{} MiB",
+        Msg::ShowPointsColumn => "Show the number of measurement points of each HRTF",
+        Msg::LogFileHeading => "Log File",
+        Msg::LogToFile => "Write logs to file, to attach to bug reports",
+        Msg::LogFileLabel => "Log file: {}",
+        Msg::LogFileOff => "Log file: not written (turn it on in Options)",
+        Msg::ConfirmationsHeading => "Confirmations",
+        Msg::ConfirmDeviceRemoval => "Ask before removing the virtual device",
+        Msg::FavoritesHeading => "Favorites",
+        Msg::FavoritesFirst => "List favorite files above all others",
+        Msg::DuplicatesHeading => "Duplicates",
+        Msg::HideDuplicates => {
+            "Hide duplicates: list only the shortest path of byte-identical files"
+        }
+        Msg::DuplicateTag => "  (dup)",
+        Msg::PlayTest => "▶ Play test",
+        Msg::PlayTestHint => {
+            "Plays clicks around the 7.1 speakers through this file, on the default output. Use headphones and don't select the virtual device as output."
+        }
+        Msg::PlayTestUnsupported => "Test playback needs an undamaged 14-channel HeSuVi file",
+        Msg::ScanDepthHeading => "Scan Depth",
+        Msg::ScanDepthExplanation => {
+            "How many levels of subdirectories are scanned. 0 scans only the IR directory itself. Applies on the next rescan."
+        }
+        Msg::ScanDepthLimit => "Limit depth:",
+        Msg::FollowSymlinks => "Follow symbolic links to files and directories",
+        Msg::WatchDirectoryHeading => "Directory Watch",
+        Msg::WatchDirectoryExplanation => {
+            "Watch directory for changes: rescan automatically when WAV files are added, removed or modified"
+        }
+        Msg::ScanCacheHeading => "Scan Cache",
+        Msg::ScanCacheExplanation => {
+            "Scan results are cached, so unchanged files are not read again on rescan. Clear the cache if the file info looks wrong."
+        }
+        Msg::ClearScanCache => "Clear cache",
+        Msg::ExportSettings => "Export settings…",
+        Msg::ImportSettings => "Import settings…",
+        Msg::ExportSettingsDialogTitle => "Export settings",
+        Msg::ImportSettingsDialogTitle => "Import settings",
+        Msg::SettingsExportErrorHeader => "Export Failed",
+        Msg::SettingsExportErrorMessage => "Failed to export settings: {}",
+        Msg::SettingsImportErrorHeader => "Import Failed",
+        Msg::SettingsImportErrorMessage => {
+            "The file was not imported, the settings are unchanged: {}"
+        }
+        Msg::ResetSettings => "Reset all settings to defaults",
+        Msg::ShowTestModal => "Show modal test message",
+
+        // === Help tab ===
+        Msg::AboutHeading => "About",
+        Msg::HomePage => "Home page",
+        Msg::ReportProblemHeading => "Report a Problem",
+        Msg::ReportProblemExplanation => {
+            "Collects version info, paths, config status and the log. Attach it to your bug report:"
+        }
+        Msg::CopyReport => "📋 Copy report",
+        Msg::PreviewConfig => "Preview config",
+        Msg::Undo => "⟲ Undo",
+        Msg::UndoCreateHint => "Remove the device that was just created",
+        Msg::UndoUpdateHint => "Put back the device as it was before the last update",
+        Msg::UndoRemoveHint => "Bring back the device that was just removed",
+        Msg::NothingToUndo => "Nothing to undo",
+        Msg::RestorePreviousConfig => "↺ Restore previous",
+        Msg::RestorePreviousConfigHint => {
+            "Put back the config that was installed before the last update, with its IR file"
+        }
+        Msg::ConfigPreviewHeader => "Config Preview",
+        Msg::CopyToClipboard => "📋 Copy",
+        Msg::SaveReport => "💾 Save report...",
+
+        // === Modal dialogs ===
+        Msg::PickAnotherDirectory => "Pick another directory",
+        Msg::Cancel => "Cancel",
+        Msg::ResetSettingsHeader => "Reset Settings",
+        Msg::ResetSettingsMessage => {
+            "All settings, including the IR directory and favorites, will be replaced with defaults. The installed device is not changed."
+        }
+        Msg::ResetSettingsConfirm => "Reset",
+        Msg::RemoveDeviceHeader => "Remove Device",
+        Msg::RemoveDeviceMessage => {
+            "Remove the virtual device \"{}\"? PipeWire will be restarted, which interrupts all playing audio."
+        }
+        Msg::RemoveDeviceConfirm => "Remove",
+        Msg::DontAskAgain => "Don't ask again",
+        Msg::ChannelMismatchHeader => "Unexpected Channel Count",
+        Msg::ChannelMismatchMessage => {
+            "{file} has {channels} channels, but the {layout} device expects a {expected}-channel HeSuVi file. The device will probably stay silent."
+        }
+        Msg::WriteAnyway => "Write anyway",
+        Msg::PipewireMissingHeader => "PipeWire Not Running",
+        Msg::PipewireMissingMessage => {
+            "The virtual device needs the PipeWire sound server, but it is not running (found: {}). Switch the system to PipeWire and try again."
+        }
+        Msg::NoSoundServer => "no sound server",
+        Msg::SoundServerLabel => "Sound server: {}",
+        Msg::LogLevelLabel => "Show:",
+        Msg::LogLevelAll => "All",
+        Msg::LogLevelWarn => "Warnings+",
+        Msg::LogLevelError => "Errors",
+        Msg::ClearLog => "🗑 Clear",
+        Msg::ExportLog => "💾 Export log…",
+        Msg::ExportLogDialogTitle => "Export Log",
+        Msg::LogExportErrorMessage => "Failed to export the log: {}",
+        Msg::TestModalHeader => "Test Modal",
+        Msg::TestModalMessage => {
+            "This is a test message to demonstrate the modal dialog functionality. Click 'Continue' to close this dialog."
+        }
+        Msg::SettingsErrorHeader => "Settings Error",
+        Msg::SettingsErrorMessage => "Failed to save settings: {}",
+        Msg::ReportSaveErrorHeader => "Report Error",
+        Msg::ReportSaveErrorMessage => "Failed to save the problem report: {}",
+        Msg::ExportErrorHeader => "Export Error",
+        Msg::ExportErrorMessage => "Failed to export the marked files: {}",
+        Msg::DirectoryNotFoundHeader => "Directory Not Found",
+        Msg::DirectoryNotFoundMessage => "The specified directory does not exist.",
+        Msg::NotADirectoryHeader => "Not a Directory",
+        Msg::NotADirectoryMessage => "The specified path is not a directory.",
+        Msg::NotAWavFileHeader => "Not a WAV File",
+        Msg::NotAWavFileMessage => "The specified file is not a WAV file.",
+        Msg::DirectoryNotAccessibleHeader => "Directory Not Accessible",
+        Msg::DirectoryNotAccessibleMessage => {
+            "Directory is not accessible — was a drive unmounted?\n\n\
+            The previously loaded file list is kept.\n\nReason: {}"
+        }
+        Msg::RescanErrorHeader => "Rescan Error",
+        Msg::RescanErrorMessage => "Failed to rescan directory: {}",
+        Msg::ArchivesFoundHeader => "Archives Found",
+        Msg::ArchivesFoundMessage => {
+            "No IR files were found in the directory, but .tar.zstd archives were detected.\n\n\
+            You need to unpack the archive files before Irate Goose can use them.\n\
+            Navigate to the project page for instructions on how to obtain and install IR files."
+        }
+
+        // === Terminal interface ===
+        Msg::TuiFilesFound => "Found {} IR files",
+        Msg::TuiFilterLabel => "Filter:",
+        Msg::TuiKeysHint => {
+            "↑/↓ select  / filter  Enter create/update device  d remove device  r rescan  q quit"
+        }
+        Msg::TuiDeviceWritten => "Device updated with {}",
+        Msg::TuiDeviceRemoved => "Device removed",
+        Msg::TuiError => "Error: {}",
+        Msg::TuiConfirmWrite => "Press y to write anyway, any other key to cancel.",
+        Msg::TuiConfirmRemove => "Press y to remove it, any other key to cancel.",
+    }
+}
+
+/// Russian text of GUI strings. Returns `None` for untranslated strings.
+fn russian(msg: Msg) -> Option<&'static str> {
+    let text = match msg {
+        // === Main window ===
+        Msg::MainHeading => "Создание виртуального устройства",
+        Msg::CreateDevice => "💾 Создать устройство",
+        Msg::UpdateDevice => "💾 Обновить устройство",
+        Msg::RemoveDevice => "❌ Удалить устройство",
+        Msg::SelectFileToProceed => "Выберите IR-файл, чтобы продолжить.",
+        Msg::FlacNeedsConversion => {
+            "Свёртка PipeWire загружает только файлы WAV. Преобразуйте этот файл FLAC в WAV, чтобы использовать его для устройства."
+        }
+        Msg::ConfiguredFileDamaged => "Внимание: настроенный IR-файл повреждён.",
+        Msg::CurrentIrFile => "Текущий IR-файл:",
+        Msg::ConfiguredFileNotFound => {
+            "Внимание: настроенный IR-файл не найден в текущей папке IR-файлов."
+        }
+        Msg::ConfiguredFileNotFoundHint => {
+            "Если создать новое виртуальное устройство, содержимое используемого сейчас IR-файла будет потеряно."
+        }
+        Msg::NoConfigInstalled => "Конфигурация не установлена",
+        Msg::AutoApplyActive => "⚡ Автоприменение",
+        Msg::AutoApplyActiveHint => {
+            "Выбор файла автоматически обновляет устройство. Отключается в настройках."
+        }
+        Msg::DeviceHealthy => "✔ Устройство в порядке",
+        Msg::DeviceFileMissing => "⚠ IR-файл устройства отсутствует",
+        Msg::DeviceFileDamaged => "⚠ IR-файл устройства повреждён",
+        Msg::DeviceNodeMissing => "⚠ Устройство не активно в PipeWire, попробуйте обновить его",
+        Msg::TabFiles => "♪ Файлы",
+        Msg::TabOptions => "⚙ Настройки",
+        Msg::TabLog => "🖹 Журнал",
+        Msg::TabHelp => "❓ Справка",
+        Msg::Continue => "Продолжить",
+
+        // === Files tab ===
+        Msg::LocatedIrFiles => "Найденные IR-файлы",
+        Msg::SampleRateLabel => "Частота дискретизации:",
+        Msg::SampleRateAll => "Все",
+        Msg::SampleRateOther => "Другая",
+        Msg::SearchHint => "Поиск IR-файлов...",
+        Msg::ClearSearch => "Очистить",
+        Msg::SearchDescriptions => "Искать и в описаниях",
+        Msg::SearchRegex => "Regex",
+        Msg::SearchRegexHint => {
+            "Считать текст поиска регулярным выражением без учёта регистра, например sadie_0[12]\\d"
+        }
+        Msg::DescribedOnly => "Только с описанием",
+        Msg::FavoritesOnly => "Только избранные",
+        Msg::FamilyLabel => "Семейство HRTF:",
+        Msg::AllFamilies => "Все семейства",
+        Msg::ColumnFiles => "Файлы",
+        Msg::ColumnModified => "Изменён",
+        Msg::ColumnChannels => "Кан.",
+        Msg::ColumnPoints => "Точки",
+        Msg::ColumnDescription => "Описание",
+        Msg::DamagedTag => "(Повреждён)",
+        Msg::AddFavorite => "Добавить в избранное",
+        Msg::RemoveFavorite => "Убрать из избранного",
+        Msg::NoFilesIntro => {
+            "Для создания эффекта виртуального объёмного звука Irate Goose нужны IR-файлы (импульсные характеристики)."
+        }
+        Msg::NoFilesFound => "В выбранной папке не найдено IR-файлов.",
+        Msg::NoFilesHelpHint => "На вкладке «Справка» описано, где взять IR-файлы.",
+        Msg::GitHubLink => " Irate Goose на GitHub",
+        Msg::NoFilesMatchFilter => "В папке нет .wav-файлов, подходящих под этот фильтр.",
+        Msg::SourcePrefix => "Источник:",
+        Msg::CreditsPrefix => "Авторы:",
+        Msg::NoDescription => "Нет описания для выбранного файла.",
+        Msg::ShowMore => "Показать полностью",
+        Msg::ShowLess => "Свернуть",
+        Msg::ChecksumLabel => "Контрольная сумма:",
+        Msg::FormatLabel => "Формат:",
+        Msg::FormatValue => "{rate} Гц, {bits} бит",
+        Msg::CopyChecksum => "📋 Копировать",
+        Msg::CopyAbsolutePath => "Копировать полный путь",
+        Msg::CopyRelativePath => "Копировать относительный путь",
+        Msg::OpenContainingFolder => "Открыть папку с файлом",
+        Msg::FileNameCollision => {
+            "⚠ Другой найденный файл тоже называется {}. Имя файла устройства неоднозначно."
+        }
+        Msg::SampleRateMismatch => {
+            "ℹ Частота файла {file} Гц, а PipeWire работает на {graph} Гц. IR будет передискретизирован."
+        }
+        Msg::SampleRateMismatchHelp => "Что это значит?",
+        Msg::SampleRateMismatchHeader => "Частота отличается",
+        Msg::SampleRateMismatchMessage => {
+            "Устройство создано с файлом {file} Гц, а PipeWire работает на {graph} Гц. IR будет передискретизирован, что может немного ухудшить качество. Если есть версия этого файла на {graph} Гц, лучше выбрать её."
+        }
+        Msg::NotesLabel => "Заметки:",
+        Msg::NotesHint => "Ваши заметки об этом файле, например «хорош для шутеров»",
+        Msg::ClearNote => "✖ Очистить",
+        Msg::CompareHeading => "Сравнение",
+        Msg::ClearCompare => "Закончить сравнение",
+        Msg::MarkedFiles => "Отмечено файлов: {}",
+        Msg::ExportMarked => "📄 Экспорт отмеченных в CSV...",
+        Msg::ClearMarks => "Снять отметки",
+        Msg::ExportDialogTitle => "Экспорт отмеченных файлов",
+        Msg::ExportList => "Экспорт списка...",
+        Msg::ExportListDialogTitle => "Экспорт списка файлов",
+        Msg::CompareChannels => "Каналы:",
+        Msg::CompareDuration => "Длительность:",
+        Msg::ComparePoints => "Точки:",
+        Msg::DurationMs => "{} мс",
+
+        // === Options tab ===
+        Msg::IrDirectoryHeading => "Папка IR-файлов",
+        Msg::IrDirectoryExplanation => "Укажите папку с IR-файлами для объёмного звука:",
+        Msg::DirectoryLabel => "Папка:",
+        Msg::DirectoryHint => "Путь к IR-файлам",
+        Msg::RecentDirectories => "Недавние",
+        Msg::ExtraDirectoriesLabel => "Дополнительные папки, сканируются вместе с папкой выше:",
+        Msg::AddExtraDirectory => "➕ Добавить папку",
+        Msg::RemoveExtraDirectory => "❌ Убрать",
+        Msg::SelectExtraDirectoryDialogTitle => "Выбор дополнительной папки IR",
+        Msg::SelectDirectory => "Выбрать",
+        Msg::Rescan => "Пересканировать",
+        Msg::ScanningProgress => "Сканирование… {done}/{total}",
+        Msg::ShownFilesCount => "Показано {shown} из {total}",
+        Msg::SelectDirectoryDialogTitle => "Выбор папки IR-файлов",
+        Msg::SelectFile => "Выбрать файл",
+        Msg::SelectFileDialogTitle => "Выбор IR-файла",
+        Msg::ProfilesHeading => "Профили",
+        Msg::ProfilesExplanation => {
+            "Профиль запоминает имя устройства и IR-файл. Выбор профиля сразу применяет его."
+        }
+        Msg::NoProfile => "Без профиля",
+        Msg::ProfileNameHint => "Имя профиля",
+        Msg::AddProfile => "Добавить",
+        Msg::RenameProfile => "Переименовать",
+        Msg::DeleteProfile => "Удалить",
+        Msg::DeviceNameHeading => "Имя виртуального устройства",
+        Msg::DeviceNameExplanation => {
+            "Укажите имя виртуального аудиоустройства, которое появится в системных настройках звука:"
+        }
+        Msg::CurrentlyConfigured => "Сейчас настроено:",
+        Msg::DeviceNameLabel => "Имя устройства:",
+        Msg::DeviceNameHint => "Имя виртуального устройства",
+        Msg::DeviceIdHeading => "Идентификатор устройства",
+        Msg::DeviceIdExplanation => {
+            "Каждое имя устройства получает свой идентификатор, поэтому устройства с разными именами существуют одновременно (например, по одному на наушники). Задайте его вручную, чтобы он не менялся вместе с именем; «По умолчанию» снова связывает его с именем:"
+        }
+        Msg::DeviceIdLabel => "Идентификатор:",
+        Msg::DeviceIdHint => "например, headphones-2",
+        Msg::Apply => "Применить",
+        Msg::Default => "По умолчанию",
+        Msg::InstalledDevicesHeading => "Установленные устройства",
+        Msg::NoInstalledDevices => "Виртуальные устройства не установлены.",
+        Msg::DefaultDeviceId => "(по умолчанию)",
+        Msg::UnknownIrFile => "(файла нет в списке)",
+        Msg::ManageDevice => "Управлять",
+        Msg::RemoveInstalledDevice => "❌ Удалить",
+        Msg::OutputDeviceHeading => "Устройство вывода",
+        Msg::OutputDeviceExplanation => {
+            "Выберите аудиовыход, на который виртуальное устройство будет выводить звук:"
+        }
+        Msg::OutputDeviceLabel => "Устройство вывода:",
+        Msg::AutoOutputDevice => "Авто\nВыбор за PipeWire",
+        Msg::UnknownDevice => "НЕИЗВЕСТНОЕ УСТРОЙСТВО",
+        Msg::InvalidDevice => "Недопустимо",
+        Msg::ReloadDevices => "Обновить",
+        Msg::HrirStorageHeading => "Хранение IR-файла",
+        Msg::HrirStorageExplanation => {
+            "Папка, в которую копируется выбранный IR-файл для виртуального устройства. Применяется при следующем создании или обновлении устройства:"
+        }
+        Msg::HrirStorageDefault => "Рядом с конфигурацией PipeWire",
+        Msg::CopyIrFile => "Копировать IR-файл",
+        Msg::CopyIrFileHint => {
+            "Если выключено, устройство использует IR-файл на его месте. Перемещение или удаление этого файла ломает устройство"
+        }
+        Msg::SelectHrirDirectoryDialogTitle => "Выбор папки для хранения IR-файла",
+        Msg::HrirStorageRefusedHeader => "Это место хранения недопустимо",
+        Msg::HrirStorageRefusedMessage => {
+            "IR-файлы из {} находятся внутри этой папки. Выберите место хранения вне библиотеки IR-файлов."
+        }
+        Msg::DescriptionsFileHeading => "Файл описаний",
+        Msg::DescriptionsFileExplanation => {
+            "CSV-файл с вашими описаниями HRTF, в столбцах встроенной базы (HRIR;HRTF;Configuration;Description;Source;Credits;Points). Его записи заменяют или дополняют встроенные:"
+        }
+        Msg::DescriptionsFileNone => "Нет, только встроенные описания",
+        Msg::SelectDescriptionsFileDialogTitle => "Выбор файла описаний",
+        Msg::ThemeHeading => "Тема интерфейса",
+        Msg::ThemeExplanation => "Выберите оформление приложения:",
+        Msg::ThemeLight => "🌞 Светлая",
+        Msg::ThemeDark => "🌙 Тёмная",
+        Msg::ThemeHighContrast => "◑ Высокая контрастность",
+        Msg::ThemeSystem => "🌟 Системная",
+        Msg::LanguageHeading => "Язык",
+        Msg::LanguageExplanation => "Выберите язык интерфейса:",
+        Msg::DensityHeading => "Плотность таблицы",
+        Msg::DensityExplanation => "Выберите высоту строк списка файлов:",
+        Msg::DensityCompact => "Компактная",
+        Msg::DensityComfortable => "Обычная",
+        Msg::AutoApplyHeading => "Автоприменение",
+        Msg::AutoApplyExplanation => {
+            "Применять при выборе: обновлять устройство вскоре после выбора другого файла"
+        }
+        Msg::RestartServicesHeading => "Перезапуск служб",
+        Msg::RestartServicesExplanation => {
+            "Автоматически перезапускать службы PipeWire после каждого изменения устройства (ненадолго прерывает весь звук)"
+        }
+        Msg::RestartUnitsExplanation => {
+            "Пользовательские службы systemd, перезапускаемые для применения изменений (отсутствующие пропускаются):"
+        }
+        Msg::AddUnitLabel => "Другая служба:",
+        Msg::AddUnitHint => "имя юнита",
+        Msg::AddUnit => "Добавить",
+        Msg::ReloadPipewireHeader => "Перезагрузите PipeWire",
+        Msg::ReloadPipewireMessage => {
+            "Конфигурация записана, но PipeWire не перезапущен. Нажмите \"Применить сейчас\" или перезапустите службы PipeWire сами, чтобы загрузить устройство."
+        }
+        Msg::ApplyNow => "⟳ Применить сейчас",
+        Msg::ApplyNowHint => {
+            "Перезапустить службы PipeWire, чтобы загрузить записанную конфигурацию"
+        }
+        Msg::RestartErrorHeader => "Ошибка перезапуска",
+        Msg::RestartErrorMessage => "Не удалось перезапустить службы PipeWire: {}",
+        Msg::OutputGainHeading => "Выходное усиление",
+        Msg::OutputGainExplanation => {
+            "Выравнивает громкость тихих и громких файлов IR. Применяется при следующей записи устройства:"
+        }
+        Msg::OutputGainLabel => "Усиление:",
+        Msg::ChannelLayoutHeading => "Раскладка каналов",
+        Msg::ChannelLayoutExplanation => {
+            "Раскладка колонок виртуального устройства. Применяется при следующей записи устройства:"
+        }
+        Msg::TableColumnsHeading => "Столбцы таблицы",
+        Msg::ShowSizeColumn => "Показывать размер каждого файла",
+        Msg::ColumnSize => "Размер",
+        Msg::SizeLabel => "Размер:",
+        Msg::SizeBytes => "{} Б",
+        Msg::SizeKib => "{} КиБ",
+        Msg::SizeMib => "{} МиБ",
+        Msg::ShowPointsColumn => "Показывать число точек измерения каждой HRTF",
+        Msg::LogFileHeading => "Файл журнала",
+        Msg::LogToFile => "Записывать журнал в файл, чтобы приложить к отчёту об ошибке",
+        Msg::LogFileLabel => "Файл журнала: {}",
+        Msg::LogFileOff => "Файл журнала: не пишется (включите в Настройках)",
+        Msg::ConfirmationsHeading => "Подтверждения",
+        Msg::ConfirmDeviceRemoval => "Спрашивать перед удалением виртуального устройства",
+        Msg::FavoritesHeading => "Избранное",
+        Msg::FavoritesFirst => "Показывать избранные файлы выше остальных",
+        Msg::DuplicatesHeading => "Дубликаты",
+        Msg::HideDuplicates => {
+            "Скрывать дубликаты: показывать только самый короткий путь среди одинаковых файлов"
+        }
+        Msg::DuplicateTag => "  (дубль)",
+        Msg::PlayTest => "▶ Прослушать",
+        Msg::PlayTestHint => {
+            "Проигрывает щелчки по кругу колонок 7.1 через этот файл на выход по умолчанию. Используйте наушники и не выбирайте виртуальное устройство выходом."
+        }
+        Msg::PlayTestUnsupported => {
+            "Для прослушивания нужен неповреждённый 14-канальный файл HeSuVi"
+        }
+        Msg::ScanDepthHeading => "Глубина сканирования",
+        Msg::ScanDepthExplanation => {
+            "Сколько уровней подкаталогов сканируется. 0 — только сам каталог IR. Действует со следующего сканирования."
+        }
+        Msg::ScanDepthLimit => "Ограничить глубину:",
+        Msg::FollowSymlinks => "Переходить по символическим ссылкам на файлы и каталоги",
+        Msg::WatchDirectoryHeading => "Отслеживание каталога",
+        Msg::WatchDirectoryExplanation => {
+            "Следить за изменениями в каталоге: пересканировать автоматически, когда WAV-файлы добавлены, удалены или изменены"
+        }
+        Msg::ScanCacheHeading => "Кэш сканирования",
+        Msg::ScanCacheExplanation => {
+            "Результаты сканирования кэшируются, и неизменённые файлы при пересканировании не читаются повторно. Очистите кэш, если сведения о файлах выглядят неверно."
+        }
+        Msg::ClearScanCache => "Очистить кэш",
+        Msg::ExportSettings => "Экспорт настроек…",
+        Msg::ImportSettings => "Импорт настроек…",
+        Msg::ExportSettingsDialogTitle => "Экспорт настроек",
+        Msg::ImportSettingsDialogTitle => "Импорт настроек",
+        Msg::SettingsExportErrorHeader => "Ошибка экспорта",
+        Msg::SettingsExportErrorMessage => "Не удалось экспортировать настройки: {}",
+        Msg::SettingsImportErrorHeader => "Ошибка импорта",
+        Msg::SettingsImportErrorMessage => "Файл не импортирован, настройки не изменены: {}",
+        Msg::ResetSettings => "Сбросить все настройки",
+
+        // === Help tab ===
+        Msg::AboutHeading => "О программе",
+        Msg::HomePage => "Домашняя страница",
+        Msg::ReportProblemHeading => "Сообщить о проблеме",
+        Msg::ReportProblemExplanation => {
+            "Собирает версии, пути, состояние конфигурации и журнал. Приложите это к сообщению об ошибке:"
+        }
+        Msg::CopyReport => "📋 Копировать отчёт",
+        Msg::PreviewConfig => "Просмотр конфигурации",
+        Msg::Undo => "⟲ Отменить",
+        Msg::UndoCreateHint => "Удалить только что созданное устройство",
+        Msg::UndoUpdateHint => "Вернуть устройство к состоянию до последнего обновления",
+        Msg::UndoRemoveHint => "Вернуть только что удалённое устройство",
+        Msg::NothingToUndo => "Нечего отменять",
+        Msg::RestorePreviousConfig => "↺ Вернуть прежнюю",
+        Msg::RestorePreviousConfigHint => {
+            "Вернуть конфигурацию, установленную до последнего обновления, вместе с её IR-файлом"
+        }
+        Msg::ConfigPreviewHeader => "Просмотр конфигурации",
+        Msg::CopyToClipboard => "📋 Копировать",
+        Msg::SaveReport => "💾 Сохранить отчёт...",
+
+        // === Modal dialogs ===
+        Msg::PickAnotherDirectory => "Выбрать другую папку",
+        Msg::Cancel => "Отмена",
+        Msg::ResetSettingsHeader => "Сброс настроек",
+        Msg::ResetSettingsMessage => {
+            "Все настройки, включая папку IR-файлов и избранное, будут заменены значениями по умолчанию. Установленное устройство не изменится."
+        }
+        Msg::ResetSettingsConfirm => "Сбросить",
+        Msg::RemoveDeviceHeader => "Удаление устройства",
+        Msg::RemoveDeviceMessage => {
+            "Удалить виртуальное устройство \"{}\"? PipeWire будет перезапущен, и всё воспроизводимое аудио прервётся."
+        }
+        Msg::RemoveDeviceConfirm => "Удалить",
+        Msg::DontAskAgain => "Больше не спрашивать",
+        Msg::ChannelMismatchHeader => "Неожиданное число каналов",
+        Msg::ChannelMismatchMessage => {
+            "В {file} каналов: {channels}, а устройству {layout} нужен {expected}-канальный файл HeSuVi. Скорее всего, устройство будет молчать."
+        }
+        Msg::WriteAnyway => "Всё равно записать",
+        Msg::PipewireMissingHeader => "PipeWire не запущен",
+        Msg::PipewireMissingMessage => {
+            "Виртуальному устройству нужен звуковой сервер PipeWire, но он не запущен (найдено: {}). Переключите систему на PipeWire и попробуйте снова."
+        }
+        Msg::NoSoundServer => "звуковой сервер не найден",
+        Msg::SoundServerLabel => "Звуковой сервер: {}",
+        Msg::LogLevelLabel => "Показать:",
+        Msg::LogLevelAll => "Все",
+        Msg::LogLevelWarn => "Предупреждения+",
+        Msg::LogLevelError => "Ошибки",
+        Msg::ClearLog => "🗑 Очистить",
+        Msg::ExportLog => "💾 Экспорт журнала…",
+        Msg::ExportLogDialogTitle => "Экспорт журнала",
+        Msg::LogExportErrorMessage => "Не удалось экспортировать журнал: {}",
+        Msg::SettingsErrorHeader => "Ошибка настроек",
+        Msg::SettingsErrorMessage => "Не удалось сохранить настройки: {}",
+        Msg::ReportSaveErrorHeader => "Ошибка отчёта",
+        Msg::ReportSaveErrorMessage => "Не удалось сохранить отчёт о проблеме: {}",
+        Msg::ExportErrorHeader => "Ошибка экспорта",
+        Msg::ExportErrorMessage => "Не удалось экспортировать отмеченные файлы: {}",
+        Msg::DirectoryNotFoundHeader => "Папка не найдена",
+        Msg::DirectoryNotFoundMessage => "Указанная папка не существует.",
+        Msg::NotADirectoryHeader => "Это не папка",
+        Msg::NotADirectoryMessage => "Указанный путь не является папкой.",
+        Msg::NotAWavFileHeader => "Это не WAV-файл",
+        Msg::NotAWavFileMessage => "Указанный файл не является WAV-файлом.",
+        Msg::DirectoryNotAccessibleHeader => "Папка недоступна",
+        Msg::DirectoryNotAccessibleMessage => {
+            "Папка недоступна — возможно, диск был отключён?\n\n\
+            Ранее загруженный список файлов сохранён.\n\nПричина: {}"
+        }
+        Msg::RescanErrorHeader => "Ошибка сканирования",
+        Msg::RescanErrorMessage => "Не удалось просканировать папку: {}",
+        Msg::ArchivesFoundHeader => "Найдены архивы",
+        Msg::ArchivesFoundMessage => {
+            "В папке не найдено IR-файлов, но обнаружены архивы .tar.zstd.\n\n\
+            Их нужно распаковать, прежде чем Irate Goose сможет ими пользоваться.\n\
+            Инструкции по получению и установке IR-файлов есть на странице проекта."
+        }
+
+        // === Terminal interface ===
+        Msg::TuiFilesFound => "Найдено IR-файлов: {}",
+        Msg::TuiFilterLabel => "Фильтр:",
+        Msg::TuiKeysHint => {
+            "↑/↓ выбор  / фильтр  Enter создать/обновить устройство  d удалить устройство  r пересканировать  q выход"
+        }
+        Msg::TuiDeviceWritten => "Устройство обновлено: {}",
+        Msg::TuiDeviceRemoved => "Устройство удалено",
+        Msg::TuiError => "Ошибка: {}",
+        Msg::TuiConfirmWrite => {
+            "Нажмите y, чтобы всё равно записать, или любую другую клавишу для отмены."
+        }
+        Msg::TuiConfirmRemove => {
+            "Нажмите y, чтобы удалить его, или любую другую клавишу для отмены."
+        }
+
+        // Developer-only strings are not translated
+        _ => return None,
+    };
+    Some(text)
+}
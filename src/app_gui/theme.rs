@@ -1,5 +1,7 @@
 use dbus::arg::RefArg;
 use dbus::blocking::{BlockingSender, Connection};
+use eframe::egui::{Color32, Stroke, Visuals};
+use std::process::Command;
 
 /// Represents a concrete system color scheme preference detected via D-Bus.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -8,6 +10,14 @@ pub enum DetectedTheme {
     Light,
 }
 
+/// Detects the system color scheme preference, from the Freedesktop portal
+/// or else from the KDE Plasma or GNOME settings.
+pub fn detect_system_theme() -> Option<DetectedTheme> {
+    detect_portal_theme()
+        .or_else(detect_kde_theme)
+        .or_else(detect_gnome_theme)
+}
+
 /// Detects the system color scheme preference via the Freedesktop portal D-Bus API.
 ///
 /// Queries `org.freedesktop.portal.Settings.Read` for the `color-scheme` key
@@ -17,7 +27,7 @@ pub enum DetectedTheme {
 /// - `Some(DetectedTheme::Dark)`  — dark mode preferred
 /// - `Some(DetectedTheme::Light)` — light mode preferred
 /// - `None`                       — could not determine (no portal, no preference, or error)
-pub fn detect_system_theme() -> Option<DetectedTheme> {
+fn detect_portal_theme() -> Option<DetectedTheme> {
     let conn = Connection::new_session().ok()?;
 
     let mut msg = dbus::Message::new_method_call(
@@ -44,3 +54,121 @@ pub fn detect_system_theme() -> Option<DetectedTheme> {
         _ => None,
     }
 }
+
+/// Detects the color scheme of a KDE Plasma session from `kdeglobals`.
+///
+/// The window background color decides; the name of the color scheme is
+/// used when the color is not set. Returns `None` outside of KDE.
+fn detect_kde_theme() -> Option<DetectedTheme> {
+    let kde_session = std::env::var_os("KDE_SESSION_VERSION").is_some()
+        || std::env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktop| desktop.contains("KDE"));
+    if !kde_session {
+        return None;
+    }
+    if let Some(theme) = read_kde_setting("Colors:Window", "BackgroundNormal")
+        .as_deref()
+        .and_then(theme_of_color)
+    {
+        return Some(theme);
+    }
+    let scheme = read_kde_setting("General", "ColorScheme")?;
+    if scheme.to_lowercase().contains("dark") {
+        Some(DetectedTheme::Dark)
+    } else {
+        Some(DetectedTheme::Light)
+    }
+}
+
+/// Detects the GNOME color scheme with `gsettings`, for desktops that have
+/// no settings portal running.
+fn detect_gnome_theme() -> Option<DetectedTheme> {
+    let output = Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // The value is printed quoted, like 'prefer-dark'
+    match String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .trim_matches('\'')
+    {
+        "prefer-dark" => Some(DetectedTheme::Dark),
+        "prefer-light" => Some(DetectedTheme::Light),
+        _ => None,
+    }
+}
+
+/// Reads a key of `kdeglobals` with `kreadconfig6`, or `kreadconfig5` on
+/// Plasma 5. Returns `None` if neither is installed or the key is not set.
+fn read_kde_setting(group: &str, key: &str) -> Option<String> {
+    ["kreadconfig6", "kreadconfig5"].iter().find_map(|tool| {
+        let output = Command::new(tool)
+            .args(["--file", "kdeglobals", "--group", group, "--key", key])
+            .output()
+            .ok()?;
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !value.is_empty()).then_some(value)
+    })
+}
+
+/// Tells a dark background from a light one by its luminance. The color
+/// is written by KDE as `r,g,b`.
+fn theme_of_color(color: &str) -> Option<DetectedTheme> {
+    let channels: Vec<f32> = color
+        .split(',')
+        .map(|c| c.trim().parse::<u8>().map(f32::from))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let [r, g, b] = channels[..] else {
+        return None;
+    };
+    if 0.299 * r + 0.587 * g + 0.114 * b < 128.0 {
+        Some(DetectedTheme::Dark)
+    } else {
+        Some(DetectedTheme::Light)
+    }
+}
+
+/// Builds the visuals of the high contrast theme: white text and strokes on
+/// a black background, thick separators and a vivid selection color.
+pub fn high_contrast_visuals() -> Visuals {
+    let mut visuals = Visuals::dark();
+
+    visuals.override_text_color = Some(Color32::WHITE);
+    visuals.weak_text_color = Some(Color32::from_gray(200));
+    visuals.panel_fill = Color32::BLACK;
+    visuals.window_fill = Color32::BLACK;
+    visuals.extreme_bg_color = Color32::BLACK;
+    visuals.faint_bg_color = Color32::from_gray(40);
+    visuals.window_stroke = Stroke::new(2.0, Color32::WHITE);
+    visuals.hyperlink_color = Color32::from_rgb(0, 255, 255);
+    visuals.warn_fg_color = Color32::from_rgb(255, 230, 0);
+    visuals.error_fg_color = Color32::from_rgb(255, 110, 110);
+
+    visuals.selection.bg_fill = Color32::from_rgb(0, 80, 220);
+    visuals.selection.stroke = Stroke::new(2.0, Color32::WHITE);
+
+    // Separators and frames use the non-interactive background stroke
+    let widgets = &mut visuals.widgets;
+    widgets.noninteractive.bg_stroke = Stroke::new(2.0, Color32::WHITE);
+    widgets.noninteractive.fg_stroke = Stroke::new(2.0, Color32::WHITE);
+    for state in [
+        &mut widgets.inactive,
+        &mut widgets.hovered,
+        &mut widgets.active,
+        &mut widgets.open,
+    ] {
+        state.fg_stroke = Stroke::new(2.0, Color32::WHITE);
+        state.bg_stroke = Stroke::new(1.5, Color32::WHITE);
+    }
+    widgets.inactive.bg_fill = Color32::from_gray(20);
+    widgets.inactive.weak_bg_fill = Color32::from_gray(20);
+    widgets.hovered.bg_fill = Color32::from_gray(60);
+    widgets.hovered.weak_bg_fill = Color32::from_gray(60);
+    widgets.active.bg_fill = Color32::from_gray(90);
+    widgets.active.weak_bg_fill = Color32::from_gray(90);
+
+    visuals
+}
@@ -0,0 +1,64 @@
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+/// Command line arguments of IrateGoose.
+#[derive(Debug, Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+pub struct CliArgs {
+    /// IR files directory to scan, or a single WAV file to select
+    pub path: Option<PathBuf>,
+
+    /// Keep all settings and PipeWire config under this directory instead of
+    /// the user config directory
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config_dir: Option<PathBuf>,
+
+    /// Run a keyboard-only terminal interface instead of the GUI
+    #[arg(long)]
+    pub tui: bool,
+
+    /// Run a command and exit instead of opening the GUI
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Commands that run without a user interface.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Create or update the virtual device with a WAV file
+    Write {
+        /// WAV file to use. A relative path that doesn't exist in the current
+        /// directory is looked up in the configured IR directory
+        #[arg(long, value_name = "PATH")]
+        file: PathBuf,
+
+        /// Check the file and print the config that would be written,
+        /// without writing it
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Write the file even if its channel count doesn't match the
+        /// layout of the device
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Scan an IR directory and print the files found, one per line as
+    /// `relative path<TAB>sample rate<TAB>channels`
+    Scan {
+        /// Directory to scan instead of the configured one
+        dir: Option<PathBuf>,
+
+        /// Print a JSON array with the checksum and HRTF metadata of each file
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List the audio sinks the device can play to, one per line as
+    /// `node.name<TAB>node.description`
+    ListSinks {
+        /// Print a JSON array with all properties of each sink instead
+        #[arg(long)]
+        json: bool,
+    },
+}
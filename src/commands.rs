@@ -0,0 +1,137 @@
+//! Commands that run from the command line without opening a window,
+//! for scripts and login hooks.
+
+use anyhow::{Context, Result, anyhow, bail};
+use log::info;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::app_context::AppContext;
+use crate::cli::Command;
+use crate::config_manager::{ConfigManager, PreflightError};
+use crate::export::write_files_json;
+use crate::file_manager::{FileManager, WaveSampleRate};
+use crate::settings::AppSettings;
+
+/// Runs a command, printing its result to stdout.
+pub fn run(context: &mut AppContext, command: &Command) -> Result<()> {
+    match command {
+        Command::Scan { dir, json } => scan(context, dir.as_deref(), *json),
+        Command::Write {
+            file,
+            dry_run,
+            force,
+        } => write(context, file, *dry_run, *force),
+        Command::ListSinks { json } => list_sinks(context, *json),
+    }
+}
+
+/// Creates or updates the virtual device with the given WAV file, after
+/// the same checks as the GUI. `force` writes a file of the wrong channel
+/// count anyway; `dry_run` prints the config instead of writing it.
+fn write(context: &AppContext, file: &Path, dry_run: bool, force: bool) -> Result<()> {
+    let path = resolve_file(&context.settings.borrow(), file)?;
+    // The config refers to the file, so it needs an absolute path
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", path.display()))?;
+    let (checksum, channels) = FileManager::file_checksum_and_channels(&path)
+        .ok_or_else(|| anyhow!("{} is not a valid WAV file", path.display()))?;
+    let config_manager = &context.config_manager;
+    config_manager
+        .preflight(&path, channels, force)
+        .map_err(|e| match e {
+            PreflightError::ChannelMismatch { .. } => {
+                anyhow!("{e}. Pass --force to write it anyway")
+            }
+            e => anyhow::Error::new(e),
+        })
+        .with_context(|| format!("Can't use {}", path.display()))?;
+    let config_path = config_manager.config_path();
+    if dry_run {
+        let text = config_manager.preview_config(&path)?;
+        println!("Would write {}:", config_path.display());
+        print!("{text}");
+        return Ok(());
+    }
+    config_manager.write_config(&path, checksum)?;
+    info!("Config written using {}", path.display());
+    println!("Wrote {} using {}", config_path.display(), path.display());
+    Ok(())
+}
+
+/// Finds the file given on the command line: as given, or relative to one
+/// of the configured IR directories.
+fn resolve_file(settings: &AppSettings, file: &Path) -> Result<PathBuf> {
+    if file.exists() {
+        return Ok(file.to_path_buf());
+    }
+    if file.is_relative()
+        && let Some(path) = settings
+            .wav_directories()
+            .iter()
+            .map(|dir| dir.join(file))
+            .find(|path| path.exists())
+    {
+        return Ok(path);
+    }
+    bail!("File {} does not exist", file.display())
+}
+
+/// Scans a directory, the configured ones by default, and prints the files.
+/// A directory given here is scanned alone and not saved to the settings.
+fn scan(context: &mut AppContext, dir: Option<&Path>, json: bool) -> Result<()> {
+    if let Some(dir) = dir {
+        if !dir.is_dir() {
+            bail!("{} is not a directory", dir.display());
+        }
+        let mut settings = context.settings.borrow_mut();
+        settings.set_wav_directory(Some(dir.to_path_buf()));
+        settings.extra_wav_directories.clear();
+    }
+    if context.settings.borrow().get_wav_directory().is_none() {
+        bail!("No IR directory is configured, pass one to scan");
+    }
+    let files = context.file_manager.rescan_configured_directory()?;
+    if json {
+        return write_files_json(std::io::stdout().lock(), files.iter());
+    }
+    for wave in files.iter() {
+        let sample_rate = match wave.sample_rate {
+            WaveSampleRate::Damaged => "damaged".to_string(),
+            rate => rate.hz().map(|hz| hz.to_string()).unwrap_or_default(),
+        };
+        println!(
+            "{}\t{}\t{}",
+            wave.relative_path.display(),
+            sample_rate,
+            wave.channels
+        );
+    }
+    Ok(())
+}
+
+/// Prints the audio sinks, sorted by node name so the output is stable.
+fn list_sinks(context: &AppContext, json: bool) -> Result<()> {
+    let devices = context.config_manager.list_audio_devices()?;
+    // Sorted maps keep the property order stable too
+    let mut sinks: Vec<BTreeMap<String, String>> = ConfigManager::filter_audio_sinks(&devices)
+        .into_iter()
+        .map(|sink| sink.into_iter().collect())
+        .collect();
+    sinks.sort_by(|a, b| a.get("node.name").cmp(&b.get("node.name")));
+    if json {
+        let text = serde_json::to_string_pretty(&sinks).context("Failed to serialize sinks")?;
+        println!("{text}");
+        return Ok(());
+    }
+    for sink in &sinks {
+        let property = |key: &str| sink.get(key).map(String::as_str).unwrap_or("");
+        println!(
+            "{}\t{}",
+            property("node.name"),
+            property("node.description")
+        );
+    }
+    Ok(())
+}
@@ -2,52 +2,178 @@ use anyhow::{Context, Result, anyhow, bail};
 use log::{info, warn};
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::rc::Rc;
+use xxhash_rust::xxh3::xxh3_64;
 
-use crate::settings::AppSettings;
-use xxhash_rust::xxh3::xxh3_128;
+use crate::file_manager::{FileManager, read_file_start, stream_checksum};
+use crate::settings::{AppSettings, ChannelLayout, DEFAULT_VIRTUAL_DEVICE_NAME};
+
+/// Result of the self-check of an installed virtual device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceHealth {
+    /// Config, IR file and PipeWire node are all in place
+    Healthy,
+    /// The IR file referenced by the config does not exist
+    FileMissing,
+    /// The IR file referenced by the config is not a valid WAV
+    FileDamaged,
+    /// The config exists, but PipeWire did not create the virtual node
+    NodeMissing,
+}
+
+/// Sound server found running for the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoundServer {
+    /// PipeWire, with the version its core reports
+    PipeWire { version: Option<String> },
+    /// Another server, by the name `pactl` reports (e.g. plain PulseAudio)
+    Other(String),
+    /// No sound server answered
+    NotFound,
+}
+
+impl SoundServer {
+    /// Whether the server can load the virtual device configs.
+    pub fn is_pipewire(&self) -> bool {
+        matches!(self, SoundServer::PipeWire { .. })
+    }
+}
+
+/// Reason a file can't be written as the device config, found by
+/// `ConfigManager::preflight`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreflightError {
+    /// The PipeWire convolver only loads WAV files, e.g. not FLAC
+    NotWav,
+    /// PipeWire is not running, so nothing would load the config
+    NoPipeWire(SoundServer),
+    /// The file has another channel count than the layout of the device
+    /// needs. The file can still be written, leaving the device silent.
+    ChannelMismatch {
+        channels: u16,
+        expected: u16,
+        layout: ChannelLayout,
+    },
+}
+
+impl std::fmt::Display for PreflightError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreflightError::NotWav => {
+                write!(
+                    f,
+                    "The file is not a WAV file. Convert it to WAV to use it for the device"
+                )
+            }
+            PreflightError::NoPipeWire(server) => {
+                write!(f, "PipeWire is not running (found: {server:?})")
+            }
+            PreflightError::ChannelMismatch {
+                channels,
+                expected,
+                layout,
+            } => write!(
+                f,
+                "The file has {channels} channels, but the {} device expects a {expected}-channel HeSuVi file",
+                layout.name()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PreflightError {}
+
+/// A virtual device with a config in the PipeWire config directory,
+/// whichever device identifier it was created with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledDevice {
+    /// Device identifier, empty for the default device
+    pub device_id: String,
+    /// Name the device is shown under, from its config
+    pub name: String,
+    /// Checksum of its IR file; 0 if the file is missing or damaged
+    pub checksum: u128,
+    pub config_path: PathBuf,
+}
+
+/// Bundled filter-chain config of a channel layout. Supporting another
+/// layout takes a `ChannelLayout` variant, a template and a row in
+/// `LAYOUT_TEMPLATES`.
+struct LayoutTemplate {
+    layout: ChannelLayout,
+    /// Config text with the `{IRFILETEMPLATE}`, `{DEVICENAMETEMPLATE}`,
+    /// `{VIRTUALNODENAME}`, `{OUTPUTDEVICE}` and `{GAINTEMPLATE}` placeholders
+    template: &'static str,
+    /// Suffix for virtual surround node names (appended after "effect_input." / "effect_output.")
+    node_suffix: &'static str,
+    /// Channel count of the IR files the template reads
+    ir_channels: u16,
+}
+
+/// Templates of all supported layouts.
+const LAYOUT_TEMPLATES: &[LayoutTemplate] = &[
+    LayoutTemplate {
+        layout: ChannelLayout::Surround51,
+        template: include_str!("../templates/virtual_device_5.1.conf.template"),
+        node_suffix: "virtual-surround-5.1-irategoose",
+        ir_channels: 14,
+    },
+    LayoutTemplate {
+        layout: ChannelLayout::Surround71,
+        template: include_str!("../templates/virtual_device_7.1.conf.template"),
+        node_suffix: "virtual-surround-7.1-irategoose",
+        ir_channels: 14,
+    },
+];
+
+impl LayoutTemplate {
+    /// Template of `layout`.
+    fn of(layout: ChannelLayout) -> &'static LayoutTemplate {
+        LAYOUT_TEMPLATES
+            .iter()
+            .find(|template| template.layout == layout)
+            .expect("every channel layout has a template")
+    }
+}
 
 /// Manages PipeWire configuration files, NOT application configuration.
 /// This class handles creation, deletion, and application of PipeWire config files
 /// that define virtual audio sinks for surround sound processing.
 pub struct ConfigManager {
-    /// Full absolute path to the config file
-    config_path: PathBuf,
+    /// Config root directory (~/.config or --config-dir)
+    config_dir: PathBuf,
     settings: Rc<RefCell<AppSettings>>,
 }
 
 impl ConfigManager {
-    /// The config file template
-    const CONFIG_TEMPLATE: &'static str = include_str!("../templates/virtual_device.conf.template");
+    /// Suffix of the backup of the installed config and its hrir directory
+    const BACKUP_SUFFIX: &str = ".bak";
 
-    /// Suffix for virtual surround node names (appended after "effect_input." / "effect_output.")
-    const VIRTUAL_NODE_SUFFIX: &str = "virtual-surround-7.1-irategoose";
+    /// Suffix of the backup made before that one
+    const OLDER_BACKUP_SUFFIX: &str = ".bak.1";
 
     /// Creates a new ConfigManager instance
     pub fn new(settings: Rc<RefCell<AppSettings>>) -> Result<ConfigManager> {
-        // Determine the full path to the current user's ~/.config directory
-        let config_dir = dirs::config_dir().ok_or(anyhow!("Could not determine home directory"))?;
-
-        // Determine config suffix based on dev_mode from settings
-        // Uses /tmp/surround.conf in dev mode for testing
-        // Uses the real PipeWire config path in production mode
-        let dev_mode = settings.borrow().dev_mode;
-        let config_suffix = if dev_mode {
-            "/tmp/surround.conf"
-        } else {
-            "pipewire/pipewire.conf.d/sink-virtual-surround-7.1-irategoose.conf"
+        // Determine the full path to the config root (~/.config or --config-dir,
+        // the host's ~/.config under Flatpak)
+        let config_dir = settings.borrow().pipewire_config_root()?;
+        let manager = Self {
+            config_dir,
+            settings,
         };
 
-        // Append the config suffix to get the full absolute path
-        let config_path = config_dir.join(config_suffix);
-
-        // Migrate config file from old name to new name
+        // Migrate config file from old name to new name. Only the default
+        // device existed before the name became configurable.
+        if !manager.device_id().is_empty() {
+            return Ok(manager);
+        }
         let old_suffix = "pipewire/pipewire.conf.d/sink-virtual-surround-7.1-hesuvi.conf";
-        let old_path = config_dir.join(old_suffix);
-        let new_path = &config_path; // already uses new suffix
+        let old_path = manager.config_dir.join(old_suffix);
+        let new_path = &manager.config_path();
 
         if old_path.exists() && !new_path.exists() {
             match std::fs::rename(&old_path, new_path) {
@@ -74,80 +200,383 @@ impl ConfigManager {
             );
         }
 
-        Ok(Self {
-            config_path,
-            settings,
-        })
+        Ok(manager)
     }
 
-    /// Writes the updated configuration to the config path
-    pub fn write_config(&self, wavefile_path: &Path) -> Result<()> {
-        // Determine the hrir directory (sibling of config file)
-        let hrir_dir = self
-            .config_path
-            .parent()
-            .ok_or_else(|| anyhow!("Config path has no parent directory"))?
-            .join("hrir");
+    /// Full absolute path to the PipeWire config file of the configured device.
+    /// A device installed with the other channel layout is found as well.
+    pub fn config_path(&self) -> PathBuf {
+        self.config_path_in(self.installed_layout())
+    }
 
-        // Remove all existing files in the hrir directory
-        let _ = fs::remove_dir_all(&hrir_dir);
+    /// Layout of the installed device: the configured one, unless only a
+    /// config of the other layout exists.
+    fn installed_layout(&self) -> ChannelLayout {
+        let configured = self.layout();
+        if self.config_path_in(configured).exists() {
+            return configured;
+        }
+        LAYOUT_TEMPLATES
+            .iter()
+            .map(|template| template.layout)
+            .find(|&layout| self.config_path_in(layout).exists())
+            .unwrap_or(configured)
+    }
+
+    /// Channel count of the IR files the configured layout needs.
+    pub fn expected_ir_channels(&self) -> u16 {
+        LayoutTemplate::of(self.layout()).ir_channels
+    }
+
+    /// Layout new configs are written with.
+    fn layout(&self) -> ChannelLayout {
+        self.settings.borrow().channel_layout
+    }
 
-        // Ensure the hrir directory exists
-        fs::create_dir_all(&hrir_dir)
-            .with_context(|| format!("Failed to create hrir directory {}", hrir_dir.display()))?;
+    /// Full absolute path to the config file the device is written to, with
+    /// the configured layout.
+    fn configured_config_path(&self) -> PathBuf {
+        self.config_path_in(self.layout())
+    }
 
-        // Copy the selected WAV file into the hrir directory, preserving its filename
-        let target_path = self.copy_wav_to_hrir(wavefile_path, &hrir_dir)?;
+    /// Full absolute path to the PipeWire config file of the device with
+    /// `layout`.
+    /// Uses /tmp/surround.conf in dev mode for testing, or surround.conf
+    /// inside the config root if it was overridden with --config-dir.
+    /// Uses the real PipeWire config path in production mode.
+    fn config_path_in(&self, layout: ChannelLayout) -> PathBuf {
+        let settings = self.settings.borrow();
+        let file_name = if settings.dev_mode {
+            format!("surround{}.conf", self.name_suffix())
+        } else {
+            format!("sink-{}.conf", self.node_suffix(layout))
+        };
+        match (settings.dev_mode, settings.config_dir.is_some()) {
+            (true, false) => PathBuf::from("/tmp").join(file_name),
+            (true, true) => self.config_dir.join(file_name),
+            (false, _) => self
+                .config_dir
+                .join("pipewire/pipewire.conf.d")
+                .join(file_name),
+        }
+    }
 
+    /// Suffix of the node names of the device with `layout`.
+    fn node_suffix(&self, layout: ChannelLayout) -> String {
+        format!(
+            "{}{}",
+            LayoutTemplate::of(layout).node_suffix,
+            self.name_suffix()
+        )
+    }
+
+    /// Part appended to all node, file and directory names of the device.
+    /// Empty for the default device; other devices append their identifier.
+    fn name_suffix(&self) -> String {
+        match self.device_id().as_str() {
+            "" => String::new(),
+            id => format!("-{id}"),
+        }
+    }
+
+    /// Identifier of the configured device: the one set in the settings,
+    /// or else the one derived from the device name.
+    pub fn device_id(&self) -> String {
+        let settings = self.settings.borrow();
+        match &settings.device_id {
+            Some(id) => id.clone(),
+            None => Self::derive_device_id(&settings.virtual_device_name),
+        }
+    }
+
+    /// Device identifier that follows the device name: empty for the
+    /// default name, else the sanitized name. A name without any ASCII
+    /// letters or digits gets a hash of it instead, so that e.g. two
+    /// Cyrillic names don't end up as the same device.
+    pub fn derive_device_id(name: &str) -> String {
+        let name = name.trim();
+        if name == DEFAULT_VIRTUAL_DEVICE_NAME {
+            return String::new();
+        }
+        match Self::sanitize_device_id(name) {
+            id if id.is_empty() => format!("device-{:08x}", xxh3_64(name.as_bytes()) as u32),
+            id => id,
+        }
+    }
+
+    /// Turns user input into a device identifier usable in node and file
+    /// names: lowercase ASCII letters, digits and dashes.
+    pub fn sanitize_device_id(text: &str) -> String {
+        text.trim()
+            .chars()
+            .map(|c| c.to_ascii_lowercase())
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect::<String>()
+            .trim_matches('-')
+            .to_string()
+    }
+
+    /// Checks that a file with `channels` channels can be written as the
+    /// device config, the same for every interface: it must be a WAV file,
+    /// PipeWire must be running (not checked in dev mode), and its channels
+    /// must match the layout unless `allow_channel_mismatch` is set.
+    pub fn preflight(
+        &self,
+        wavefile_path: &Path,
+        channels: u16,
+        allow_channel_mismatch: bool,
+    ) -> Result<(), PreflightError> {
+        if !FileManager::is_wav_file(wavefile_path) {
+            return Err(PreflightError::NotWav);
+        }
+        if !self.settings.borrow().dev_mode {
+            let server = Self::detect_sound_server();
+            if !server.is_pipewire() {
+                return Err(PreflightError::NoPipeWire(server));
+            }
+        }
+        let expected = self.expected_ir_channels();
+        if channels != expected && !allow_channel_mismatch {
+            return Err(PreflightError::ChannelMismatch {
+                channels,
+                expected,
+                layout: self.layout(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Writes the updated configuration to the config path.
+    /// `checksum` is the known checksum of the WAV file, used to verify the
+    /// copy in the hrir directory; 0 skips the verification.
+    /// An installed config that can't be backed up is left in place, so
+    /// Undo and Restore never bring back an older backup instead.
+    pub fn write_config(&self, wavefile_path: &Path, checksum: u128) -> Result<()> {
+        // The PipeWire convolver only loads WAV files
+        if !FileManager::is_wav_file(wavefile_path) {
+            bail!(
+                "{} is not a WAV file. Convert it to WAV to use it for the device",
+                wavefile_path.display()
+            );
+        }
+        self.backup_config()
+            .context("Failed to back up the installed config")?;
+        // A device of the other layout has a config under another name
+        let installed_path = self.config_path();
+        let replaced_layout =
+            (installed_path != self.configured_config_path()).then_some(installed_path);
+
+        self.write_device_files(wavefile_path, checksum)?;
+        if let Some(path) = replaced_layout {
+            let _ = fs::remove_file(path);
+        }
+
+        // Restart services to apply the new config
+        if let Err(e) = self.apply_config() {
+            // If service restart fails, the config may be unreliable; delete it.
+            let _ = fs::remove_file(self.config_path());
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Copies the WAV file into the hrir directory and writes the config
+    /// file referencing it. With `copy_ir_file` off, the config refers to the
+    /// original file instead. Does not apply the config.
+    fn write_device_files(&self, wavefile_path: &Path, checksum: u128) -> Result<()> {
+        let config_path = self.configured_config_path();
+        let hrir_dir = self.hrir_dir()?;
+
+        let target_path = if self.settings.borrow().copy_ir_file {
+            // Check the location before touching the currently installed file
+            self.ensure_outside_storage(wavefile_path)?;
+            Self::ensure_writable(hrir_dir.parent().unwrap_or(&hrir_dir))?;
+
+            // Remove the copy an earlier write left, other files stay
+            self.remove_previous_copy(wavefile_path);
+
+            // Ensure the hrir directory exists
+            fs::create_dir_all(&hrir_dir).with_context(|| {
+                format!("Failed to create hrir directory {}", hrir_dir.display())
+            })?;
+
+            // Copy the selected WAV file into the hrir directory, preserving its filename
+            self.copy_wav_to_hrir(wavefile_path, &hrir_dir, checksum)?
+        } else {
+            let original = Self::original_ir_path(wavefile_path)?;
+            // A copy from an earlier write would only waste space now, unless
+            // it is the file the config is going to use
+            self.remove_previous_copy(&original);
+            original
+        };
+
+        // Create text for config file using the IR file's absolute path
+        let config_text = self.render_config_text(&target_path);
+
+        // Ensure the parent directory of the config file exists
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+
+        // Write the config file
+        if let Err(e) = fs::write(&config_path, config_text) {
+            // If writing fails, delete any partially written config file.
+            let _ = fs::remove_file(&config_path);
+            return Err(e)
+                .with_context(|| format!("Failed to write config to {}", config_path.display()));
+        }
+        Ok(())
+    }
+
+    /// Text of the config file of the device, with `ir_path` as the IR file
+    /// the convolver loads.
+    pub fn render_config_text(&self, ir_path: &Path) -> String {
         // Determine output device replacement
         let output_device_replacement = match &self.settings.borrow().output_device {
             None => "# Automatic output selection".to_string(),
             Some(device) => format!("target.object = \"{}\"", device),
         };
-
-        // Create text for config file using the copied file's absolute path
-        let config_text = Self::CONFIG_TEMPLATE
-            .replace("{IRFILETEMPLATE}", target_path.to_string_lossy().as_ref())
+        let layout = self.layout();
+        LayoutTemplate::of(layout)
+            .template
+            .replace("{IRFILETEMPLATE}", ir_path.to_string_lossy().as_ref())
             .replace(
                 "{DEVICENAMETEMPLATE}",
                 &self.settings.borrow().virtual_device_name,
             )
-            .replace("{VIRTUALNODENAME}", Self::VIRTUAL_NODE_SUFFIX)
-            .replace("{OUTPUTDEVICE}", &output_device_replacement);
+            .replace("{VIRTUALNODENAME}", &self.node_suffix(layout))
+            .replace("{OUTPUTDEVICE}", &output_device_replacement)
+            .replace(
+                "{GAINTEMPLATE}",
+                &format!("{:.6}", self.settings.borrow().output_gain_factor()),
+            )
+    }
 
-        // Ensure the parent directory of the config file exists
-        if let Some(parent) = self.config_path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
-        }
+    /// Config `write_config` would install for the WAV file, for review.
+    /// Nothing is written and no service is restarted.
+    pub fn preview_config(&self, wavefile_path: &Path) -> Result<String> {
+        let filename = wavefile_path
+            .file_name()
+            .ok_or_else(|| anyhow!("Source path has no filename"))?;
+        let target_path = if self.settings.borrow().copy_ir_file {
+            // The config refers to the copy in the hrir directory
+            self.hrir_dir()?.join(filename)
+        } else {
+            Self::original_ir_path(wavefile_path)?
+        };
+        Ok(self.render_config_text(&target_path))
+    }
 
-        // Write the config file
-        if let Err(e) = fs::write(&self.config_path, config_text) {
-            // If writing fails, delete any partially written config file.
-            let _ = fs::remove_file(&self.config_path);
-            return Err(e).with_context(|| {
-                format!("Failed to write config to {}", self.config_path.display())
-            });
+    /// Absolute path of the WAV file, for a config that refers to it in
+    /// place. PipeWire doesn't resolve relative paths against our directory.
+    fn original_ir_path(wavefile_path: &Path) -> Result<PathBuf> {
+        std::path::absolute(wavefile_path)
+            .with_context(|| format!("Failed to resolve {}", wavefile_path.display()))
+    }
+
+    /// Copies the installed config and its IR file to `.bak` before they are
+    /// replaced, keeping manual edits. The previous backup moves to `.bak.1`,
+    /// older ones are dropped.
+    fn backup_config(&self) -> Result<()> {
+        let config_path = self.config_path();
+        if !config_path.exists() {
+            return Ok(());
         }
+        let hrir_dir = self.hrir_dir()?;
+        let config_backup = Self::with_suffix(&config_path, Self::BACKUP_SUFFIX);
+        let hrir_backup = Self::with_suffix(&hrir_dir, Self::BACKUP_SUFFIX);
 
-        // Restart services to apply the new config
-        if let Err(e) = self.apply_config() {
-            // If service restart fails, the config may be unreliable; delete it.
-            let _ = fs::remove_file(&self.config_path);
-            return Err(e);
+        // Drop the older backup: the IR file copied there, then its directory
+        // once nothing else is left in it
+        let config_older = Self::with_suffix(&config_path, Self::OLDER_BACKUP_SUFFIX);
+        let hrir_older = Self::with_suffix(&hrir_dir, Self::OLDER_BACKUP_SUFFIX);
+        if let Some(name) = Self::copied_ir_name(&config_older, &hrir_dir) {
+            let _ = fs::remove_file(hrir_older.join(name));
+        }
+        let _ = fs::remove_dir(&hrir_older);
+        let _ = fs::remove_file(&config_older);
+        for (backup, older) in [(&config_backup, &config_older), (&hrir_backup, &hrir_older)] {
+            if backup.exists() {
+                fs::rename(backup, older)
+                    .with_context(|| format!("Failed to move {}", backup.display()))?;
+            }
         }
 
+        // The installed files are copied, not moved, so the device keeps
+        // working if the new one can't be written
+        fs::copy(&config_path, &config_backup)
+            .with_context(|| format!("Failed to copy {}", config_path.display()))?;
+        if let Some(name) = Self::copied_ir_name(&config_path, &hrir_dir) {
+            fs::create_dir_all(&hrir_backup)
+                .with_context(|| format!("Failed to create {}", hrir_backup.display()))?;
+            let copy = hrir_dir.join(&name);
+            fs::copy(&copy, hrir_backup.join(&name))
+                .with_context(|| format!("Failed to copy {}", copy.display()))?;
+        }
+        info!("Previous config backed up to {}", config_backup.display());
         Ok(())
     }
 
+    /// Reads the installed config file, None if there is none.
+    pub fn read_config_text(&self) -> Option<String> {
+        fs::read_to_string(self.config_path()).ok()
+    }
+
+    /// Installs a config file read earlier with `read_config_text` and
+    /// applies it. Its IR file must still be in the hrir directory.
+    pub fn restore_config_text(&self, text: &str) -> Result<()> {
+        let config_path = self.config_path();
+        fs::write(&config_path, text)
+            .with_context(|| format!("Failed to write config to {}", config_path.display()))?;
+        self.apply_config()
+    }
+
+    /// Checks whether there is a backup of a previous config to restore.
+    pub fn has_backup(&self) -> bool {
+        Self::with_suffix(&self.config_path(), Self::BACKUP_SUFFIX).exists()
+    }
+
+    /// Puts the backup of the previous config and its IR file back in place
+    /// and applies it. The replaced config becomes the backup, so restoring
+    /// again undoes the restore.
+    pub fn restore_backup(&self) -> Result<()> {
+        if !self.has_backup() {
+            bail!("There is no previous config to restore");
+        }
+        for path in [self.config_path(), self.hrir_dir()?] {
+            let backup = Self::with_suffix(&path, Self::BACKUP_SUFFIX);
+            let temp = Self::with_suffix(&path, ".restore");
+            let rename = |from: &Path, to: &Path| {
+                fs::rename(from, to).with_context(|| format!("Failed to move {}", from.display()))
+            };
+            if path.exists() {
+                rename(&path, &temp)?;
+            }
+            if backup.exists() {
+                rename(&backup, &path)?;
+            }
+            if temp.exists() {
+                rename(&temp, &backup)?;
+            }
+        }
+        self.apply_config()
+    }
+
+    /// Appends a suffix to the file name of a path.
+    fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(suffix);
+        PathBuf::from(name)
+    }
+
     /// Deletes the config file completely
     pub fn delete_config(&self) -> Result<()> {
-        if self.config_path.exists() {
-            fs::remove_file(&self.config_path).with_context(|| {
-                format!(
-                    "Failed to delete config file {}",
-                    self.config_path.display()
-                )
+        let config_path = self.config_path();
+        if config_path.exists() {
+            fs::remove_file(&config_path).with_context(|| {
+                format!("Failed to delete config file {}", config_path.display())
             })?;
         }
         // Restart services to apply the removal
@@ -160,41 +589,264 @@ impl ConfigManager {
     /// Returns Ok(None) if config file does not exist.
     /// Returns Err(String) if config exists but cannot be read or parsed.
     pub fn config_exists(&self) -> Result<Option<u128>, String> {
-        if !self.config_path.exists() {
+        let config_path = self.config_path();
+        if !config_path.exists() {
             return Ok(None);
         }
 
         // Read the config file
-        let content = fs::read_to_string(&self.config_path)
+        let content = fs::read_to_string(&config_path)
             .map_err(|e| format!("Failed to read config file: {}", e))?;
 
         // Extract filename from config
         let file_path = Self::extract_filename_from_config(&content)
             .map_err(|e| format!("Failed to parse config: {}", e))?;
 
-        // Compute checksum of the referenced WAV file
-        let checksum = match fs::read(&file_path) {
-            Ok(data) => {
-                // Basic WAV header check (optional)
-                if data.len() >= 28 && &data[0..4] == b"RIFF" && &data[8..12] == b"WAVE" {
-                    xxh3_128(&data)
-                } else {
-                    0 // Damaged or not a WAV
+        Ok(Some(Self::ir_checksum(&file_path)))
+    }
+
+    /// Checksum of the WAV file a config references; 0 if it is missing or
+    /// damaged.
+    fn ir_checksum(file_path: &Path) -> u128 {
+        match read_file_start(file_path, Self::WAV_HEADER_SIZE) {
+            // Basic WAV header check (optional)
+            Ok(header) if Self::has_wav_header(&header) => stream_checksum(file_path).unwrap_or(0),
+            Ok(_) => 0,  // Damaged or not a WAV
+            Err(_) => 0, // File missing or unreadable
+        }
+    }
+
+    /// Lists the devices of all identifiers and layouts installed next to
+    /// the config of the configured device, sorted by identifier.
+    /// Configs that can't be read are skipped with a warning.
+    pub fn installed_devices(&self) -> Result<Vec<InstalledDevice>> {
+        let config_path = self.config_path();
+        let Some(dir) = config_path.parent() else {
+            return Ok(Vec::new());
+        };
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut devices = Vec::new();
+        for entry in
+            fs::read_dir(dir).with_context(|| format!("Failed to list {}", dir.display()))?
+        {
+            let path = entry?.path();
+            let Some(device_id) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| self.device_id_of(name))
+            else {
+                continue;
+            };
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Failed to read config {}: {}", path.display(), e);
+                    continue;
                 }
+            };
+            let checksum = Self::extract_filename_from_config(&content)
+                .map(|file_path| Self::ir_checksum(&file_path))
+                .unwrap_or(0);
+            devices.push(InstalledDevice {
+                device_id,
+                name: Self::extract_device_name(&content).unwrap_or_default(),
+                checksum,
+                config_path: path,
+            });
+        }
+        devices.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+        Ok(devices)
+    }
+
+    /// Device identifier of a config file name written by this app, None
+    /// for other files and backups.
+    fn device_id_of(&self, file_name: &str) -> Option<String> {
+        let stem = file_name.strip_suffix(".conf")?;
+        let rest = if self.settings.borrow().dev_mode {
+            stem.strip_prefix("surround")?
+        } else {
+            let stem = stem.strip_prefix("sink-")?;
+            LAYOUT_TEMPLATES
+                .iter()
+                .find_map(|template| stem.strip_prefix(template.node_suffix))?
+        };
+        match rest.strip_prefix('-') {
+            Some(id) if !id.is_empty() => Some(id.to_string()),
+            None if rest.is_empty() => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    /// Reads the device name from the `node.description` of a config.
+    fn extract_device_name(content: &str) -> Option<String> {
+        let re = regex::Regex::new(r#"node\.description\s*=\s*"([^"]*)""#).ok()?;
+        Some(re.captures(content)?.get(1)?.as_str().to_string())
+    }
+
+    /// Removes the config of an installed device of any identifier, like
+    /// `delete_config` does for the configured one.
+    pub fn delete_installed_device(&self, device: &InstalledDevice) -> Result<()> {
+        if device.config_path.exists() {
+            fs::remove_file(&device.config_path).with_context(|| {
+                format!(
+                    "Failed to delete config file {}",
+                    device.config_path.display()
+                )
+            })?;
+        }
+        // Restart services to apply the removal
+        self.apply_config()
+    }
+
+    /// Checks that the installed device is usable: the config references an
+    /// existing, valid WAV file, and PipeWire has the virtual node among
+    /// `devices` (as returned by `list_audio_devices`).
+    /// Returns Ok(None) if no config is installed. The node is not checked in
+    /// dev mode, since services are not restarted there.
+    pub fn check_device_health(
+        &self,
+        devices: &[HashMap<String, String>],
+    ) -> Result<Option<DeviceHealth>> {
+        let config_path = self.config_path();
+        if !config_path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&config_path)
+            .with_context(|| format!("Failed to read config file {}", config_path.display()))?;
+        let file_path = Self::extract_filename_from_config(&content).map_err(|e| anyhow!(e))?;
+
+        match read_file_start(&file_path, Self::WAV_HEADER_SIZE) {
+            Err(_) => return Ok(Some(DeviceHealth::FileMissing)),
+            Ok(data) if !Self::has_wav_header(&data) => {
+                return Ok(Some(DeviceHealth::FileDamaged));
             }
-            Err(_) => 0, // File missing or unreadable
+            Ok(_) => {}
+        }
+
+        if !self.settings.borrow().dev_mode {
+            let node_suffix = self.node_suffix(self.installed_layout());
+            let node_name = format!("effect_input.{}", node_suffix);
+            if !devices
+                .iter()
+                .any(|obj| obj.get("node.name") == Some(&node_name))
+            {
+                return Ok(Some(DeviceHealth::NodeMissing));
+            }
+        }
+
+        Ok(Some(DeviceHealth::Healthy))
+    }
+
+    /// Number of bytes `has_wav_header` looks at
+    const WAV_HEADER_SIZE: u64 = 28;
+
+    /// Basic check of the RIFF/WAVE header.
+    fn has_wav_header(data: &[u8]) -> bool {
+        data.len() >= 28 && &data[0..4] == b"RIFF" && &data[8..12] == b"WAVE"
+    }
+
+    /// Determines the hrir directory: `irate_goose_hrir` inside the custom
+    /// storage location from settings if set, else `hrir` next to the config
+    /// file. Every device gets its own directory. The custom location may be
+    /// any folder of the user, so the name there is one no other tool uses.
+    fn hrir_dir(&self) -> Result<PathBuf> {
+        let suffix = self.name_suffix();
+        if let Some(storage) = &self.settings.borrow().hrir_directory {
+            return Ok(storage.join(format!("irate_goose_hrir{suffix}")));
+        }
+        Ok(self
+            .configured_config_path()
+            .parent()
+            .ok_or_else(|| anyhow!("Config path has no parent directory"))?
+            .join(format!("hrir{suffix}")))
+    }
+
+    /// Removes the IR file the installed config refers to if an earlier
+    /// write copied it into the hrir directory, then the directory once it
+    /// is empty. Other files in the directory and `keep` are left alone.
+    fn remove_previous_copy(&self, keep: &Path) {
+        let Ok(hrir_dir) = self.hrir_dir() else {
+            return;
         };
+        if let Some(name) = Self::copied_ir_name(&self.config_path(), &hrir_dir) {
+            let previous = hrir_dir.join(name);
+            if Self::resolved(&previous) != Self::resolved(keep) {
+                let _ = fs::remove_file(&previous);
+            }
+        }
+        // Fails while anything else is left in it
+        let _ = fs::remove_dir(&hrir_dir);
+    }
+
+    /// File name of the IR file the config at `config_path` refers to, if a
+    /// write copied it into `hrir_dir`.
+    fn copied_ir_name(config_path: &Path, hrir_dir: &Path) -> Option<OsString> {
+        let content = fs::read_to_string(config_path).ok()?;
+        let file = Self::extract_filename_from_config(&content).ok()?;
+        if file.parent() != Some(hrir_dir) {
+            return None;
+        }
+        file.file_name().map(OsString::from)
+    }
 
-        Ok(Some(checksum))
+    /// Fails if the custom storage location contains the directory of the
+    /// IR file, e.g. a HeSuVi folder chosen as storage: the app manages the
+    /// files it writes there, and must not mix them with the library.
+    fn ensure_outside_storage(&self, wavefile_path: &Path) -> Result<()> {
+        let Some(storage) = self.settings.borrow().hrir_directory.clone() else {
+            return Ok(());
+        };
+        let source_dir = wavefile_path.parent().unwrap_or(wavefile_path);
+        if Self::storage_contains(&storage, source_dir) {
+            bail!(
+                "The IR file storage {} contains {}. Choose a storage location outside the IR file library",
+                storage.display(),
+                wavefile_path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks whether `dir` is the storage location or inside it.
+    pub fn storage_contains(storage: &Path, dir: &Path) -> bool {
+        Self::resolved(dir).starts_with(Self::resolved(storage))
+    }
+
+    /// Absolute path with symlinks resolved where it exists, for comparing
+    /// locations.
+    fn resolved(path: &Path) -> PathBuf {
+        fs::canonicalize(path)
+            .or_else(|_| std::path::absolute(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    /// Creates the directory if needed and checks that files can be written to it.
+    fn ensure_writable(dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+        let probe = dir.join(".irategoose-write-test");
+        fs::write(&probe, b"")
+            .with_context(|| format!("Directory {} is not writable", dir.display()))?;
+        let _ = fs::remove_file(&probe);
+        Ok(())
     }
 
     /// Copies a WAV file into the hrir directory, preserving the filename.
+    /// Unless `checksum` is 0, the copy is re-hashed and compared to it, so a
+    /// truncated copy (e.g. on a full disk) is deleted and reported as error.
     /// Returns the absolute path of the copied file.
-    fn copy_wav_to_hrir(&self, source: &Path, hrir_dir: &Path) -> Result<PathBuf> {
+    fn copy_wav_to_hrir(&self, source: &Path, hrir_dir: &Path, checksum: u128) -> Result<PathBuf> {
         let filename = source
             .file_name()
             .ok_or_else(|| anyhow!("Source path has no filename"))?;
         let target = hrir_dir.join(filename);
+        // Copying a file onto itself would truncate it
+        if Self::resolved(source) == Self::resolved(&target) {
+            return Ok(target);
+        }
         fs::copy(source, &target).with_context(|| {
             format!(
                 "Failed to copy {} to {}",
@@ -202,6 +854,19 @@ impl ConfigManager {
                 target.display()
             )
         })?;
+
+        if checksum != 0 {
+            let copied = stream_checksum(&target)
+                .with_context(|| format!("Failed to read back {}", target.display()))?;
+            if copied != checksum {
+                let _ = fs::remove_file(&target);
+                bail!(
+                    "Copy of {} in {} does not match the source file",
+                    source.display(),
+                    hrir_dir.display()
+                );
+            }
+        }
         Ok(target)
     }
 
@@ -211,35 +876,83 @@ impl ConfigManager {
         // Search for filename = "..." pattern
         // The pattern could be: filename = "/path/to/file.wav"
         // or: filename = "/home/barafu/Scripts/Surround_WAV/HeSuVi/Common/cmss_ent-/cmss_ent-.wav"
-        let re = regex::Regex::new(r#"filename\s*=\s*"([^"]+)"#)
+        let filename_re = regex::Regex::new(r#"filename\s*=\s*"([^"]+)"#)
+            .map_err(|e| format!("Failed to compile regex: {}", e))?;
+        let label_re = regex::Regex::new(r"label\s*=\s*(\w+)")
             .map_err(|e| format!("Failed to compile regex: {}", e))?;
 
-        if let Some(captures) = re.captures(content)
-            && let Some(filename_match) = captures.get(1)
-        {
-            let filename = filename_match.as_str();
-            return Ok(PathBuf::from(filename));
+        // A filename outside a convolver node is only used if no convolver
+        // has one. The label of the node seen last tells which node a
+        // filename belongs to, also when a node spans several lines.
+        let mut label = None;
+        let mut fallback = None;
+        for line in content.lines() {
+            let line = Self::strip_comment(line);
+            if let Some(captures) = label_re.captures(line) {
+                label = captures.get(1).map(|m| m.as_str());
+            }
+            let Some(filename) = filename_re.captures(line).and_then(|c| c.get(1)) else {
+                continue;
+            };
+            if label == Some("convolver") {
+                return Ok(PathBuf::from(filename.as_str()));
+            }
+            fallback.get_or_insert_with(|| PathBuf::from(filename.as_str()));
         }
 
-        Err("No filename found in config".to_string())
+        fallback.ok_or_else(|| "No filename found in config".to_string())
     }
 
-    /// Restarts the PipeWire services to apply configuration changes.
-    /// Does nothing when in dev mode.
+    /// Cuts a `#` comment off a config line. A `#` inside quotes, as in a
+    /// file name, does not start a comment.
+    fn strip_comment(line: &str) -> &str {
+        let mut quoted = false;
+        for (i, c) in line.char_indices() {
+            match c {
+                '"' => quoted = !quoted,
+                '#' if !quoted => return &line[..i],
+                _ => {}
+            }
+        }
+        line
+    }
+
+    /// Restarts the PipeWire services to apply configuration changes,
+    /// unless the user turned automatic restarts off.
     fn apply_config(&self) -> Result<()> {
+        if !self.settings.borrow().restart_services {
+            info!("Automatic service restart is off, PipeWire was not reloaded");
+            return Ok(());
+        }
+        self.restart_services()
+    }
+
+    /// Restarts the PipeWire services now, whether or not automatic
+    /// restarts are on.
+    /// Only the configured units that exist on this system are restarted.
+    /// Does nothing when in dev mode.
+    pub fn restart_services(&self) -> Result<()> {
         // In dev mode, skip restarting services
         if self.settings.borrow().dev_mode {
             return Ok(());
         }
 
+        let units: Vec<String> = self
+            .settings
+            .borrow()
+            .restart_units
+            .iter()
+            .filter(|unit| Self::is_valid_unit_name(unit) && Self::unit_exists(unit))
+            .cloned()
+            .collect();
+        if units.is_empty() {
+            info!("No service units to restart");
+            return Ok(());
+        }
+
         let output = Command::new("systemctl")
-            .args([
-                "--user",
-                "restart",
-                "wireplumber",
-                "pipewire",
-                "pipewire-pulse",
-            ])
+            .args(["--user", "restart", "--"])
+            .args(&units)
             .output()
             .with_context(|| "Failed to execute systemctl command")?;
 
@@ -254,6 +967,39 @@ impl ConfigManager {
         }
     }
 
+    /// Checks whether `unit` can be passed to systemctl as a unit name: not
+    /// empty, without whitespace, and not starting with `-` like an option.
+    pub fn is_valid_unit_name(unit: &str) -> bool {
+        !unit.is_empty() && !unit.starts_with('-') && !unit.contains(char::is_whitespace)
+    }
+
+    /// Checks whether a systemd user unit is known to the service manager.
+    /// If the query itself fails, the unit is assumed to exist, so that
+    /// the restart is still attempted.
+    fn unit_exists(unit: &str) -> bool {
+        let output = Command::new("systemctl")
+            .args([
+                "--user",
+                "show",
+                "--property=LoadState",
+                "--value",
+                "--",
+                unit,
+            ])
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {
+                let state = String::from_utf8_lossy(&output.stdout);
+                let exists = state.trim() != "not-found";
+                if !exists {
+                    info!("Service unit {unit} not found, skipping restart");
+                }
+                exists
+            }
+            _ => true,
+        }
+    }
+
     /// Runs `pw-cli list-objects` and parses its output into a vector of property maps.
     ///
     /// Each object is represented as a `HashMap<String, String>` where keys are property names
@@ -280,6 +1026,87 @@ impl ConfigManager {
         Self::parse_pwcli_output(&stdout)
     }
 
+    /// Finds out which sound server runs: PipeWire if `pw-cli` can reach
+    /// its core, otherwise whatever `pactl` is connected to.
+    pub fn detect_sound_server() -> SoundServer {
+        if let Ok(output) = Command::new("pw-cli").args(["info", "0"]).output()
+            && output.status.success()
+        {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            return SoundServer::PipeWire {
+                version: Self::parse_core_version(&stdout),
+            };
+        }
+        if let Ok(output) = Command::new("pactl").arg("info").output()
+            && output.status.success()
+            && let Some(name) = Self::parse_server_name(&String::from_utf8_lossy(&output.stdout))
+        {
+            // pipewire-pulse answers pactl too, if pw-cli is just missing
+            if let Some(version) = name.strip_prefix("PulseAudio (on PipeWire ") {
+                return SoundServer::PipeWire {
+                    version: version.strip_suffix(')').map(str::to_string),
+                };
+            }
+            return SoundServer::Other(name);
+        }
+        SoundServer::NotFound
+    }
+
+    /// Extracts the version from the output of `pw-cli info 0`.
+    fn parse_core_version(output: &str) -> Option<String> {
+        output.lines().find_map(|line| {
+            let value = line.trim().strip_prefix("version:")?;
+            Some(value.trim().trim_matches('"').to_string())
+        })
+    }
+
+    /// Extracts the server name from the output of `pactl info`.
+    fn parse_server_name(output: &str) -> Option<String> {
+        output.lines().find_map(|line| {
+            let value = line.trim().strip_prefix("Server Name:")?;
+            Some(value.trim().to_string())
+        })
+    }
+
+    /// Reads the sample rate the PipeWire graph runs at from the `settings`
+    /// metadata, preferring a forced rate over the default one.
+    /// Returns Ok(None) in dev mode or if PipeWire reports no rate.
+    pub fn graph_sample_rate(&self) -> Result<Option<u32>> {
+        if self.settings.borrow().dev_mode {
+            return Ok(None);
+        }
+        let output = Command::new("pw-metadata")
+            .args(["-n", "settings", "0"])
+            .output()
+            .with_context(|| "Failed to execute pw-metadata command")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!(
+                "pw-metadata failed with status {}: {}",
+                output.status,
+                stderr
+            );
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(Self::parse_clock_rate(&stdout))
+    }
+
+    /// Extracts the graph rate from `pw-metadata -n settings` output, where
+    /// lines look like `update: id:0 key:'clock.rate' value:'48000' type:''`.
+    /// A nonzero `clock.force-rate` wins over `clock.rate`.
+    fn parse_clock_rate(output: &str) -> Option<u32> {
+        let value_of = |key: &str| {
+            let pattern = format!("key:'{key}' value:'");
+            output.lines().find_map(|line| {
+                let rest = &line[line.find(&pattern)? + pattern.len()..];
+                rest[..rest.find('\'')?].parse::<u32>().ok()
+            })
+        };
+        value_of("clock.force-rate")
+            .filter(|&rate| rate != 0)
+            .or_else(|| value_of("clock.rate"))
+    }
+
     /// Parses the stdout of `pw-cli list-objects` into a vector of property maps.
     ///
     /// The expected format is:
@@ -311,7 +1138,7 @@ impl ConfigManager {
                 // Parse id and type
                 // Example: "id 0, type PipeWire:Interface:Core/4"
                 let parts: Vec<&str> = line.splitn(2, ',').collect();
-                if parts.len() >= 1 {
+                if !parts.is_empty() {
                     let id_part = parts[0].trim();
                     if let Some(id) = id_part.strip_prefix("id ") {
                         obj.insert("id".to_string(), id.trim().to_string());
@@ -352,24 +1179,32 @@ impl ConfigManager {
         Ok(objects)
     }
 
+    /// Media class of PipeWire nodes that are audio sinks.
+    const AUDIO_DEVICE_CLASS: &str = "Audio/Sink";
+
     /// Filters a list of audio device objects, returning only those that are audio sinks.
     ///
     /// An audio sink is defined as having a property `media.class` equal to AUDIO_DEVICE_TYPE. Skips
-    /// IrateGoose virtual device.
+    /// IrateGoose virtual devices, including the ones with other identifiers.
     /// The returned vector contains clones of the matching entries.
-
-    const AUDIO_DEVICE_CLASS: &str = "Audio/Sink";
-    pub fn filter_audio_sinks(
-        devices: &Vec<HashMap<String, String>>,
-    ) -> Vec<HashMap<String, String>> {
-        let irategoose_node = format!("effect_input.{}", Self::VIRTUAL_NODE_SUFFIX);
+    pub fn filter_audio_sinks(devices: &[HashMap<String, String>]) -> Vec<HashMap<String, String>> {
+        let irategoose_nodes: Vec<String> = LAYOUT_TEMPLATES
+            .iter()
+            .map(|template| format!("effect_input.{}", template.node_suffix))
+            .collect();
+        let is_irategoose_node = |name: &String| {
+            irategoose_nodes.iter().any(|node| {
+                name.strip_prefix(node.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+            })
+        };
         devices
             .iter()
             .filter(|obj| match obj.get("media.class") {
                 Some(v) => v == ConfigManager::AUDIO_DEVICE_CLASS,
                 None => false,
             })
-            .filter(|obj| obj.get("node.name").as_deref() != Some(&irategoose_node))
+            .filter(|obj| !obj.get("node.name").is_some_and(is_irategoose_node))
             .cloned()
             .collect()
     }
@@ -430,6 +1265,128 @@ mod tests {
         assert_eq!(obj.get("quoted"), Some(&"value with spaces".to_string()));
     }
 
+    #[test]
+    fn test_parse_clock_rate() {
+        let input = "Found \"settings\" metadata 32\n\
+            update: id:0 key:'log.level' value:'2' type:''\n\
+            update: id:0 key:'clock.rate' value:'48000' type:''\n\
+            update: id:0 key:'clock.force-rate' value:'0' type:''\n";
+        assert_eq!(ConfigManager::parse_clock_rate(input), Some(48000));
+
+        let forced = input.replace("force-rate' value:'0'", "force-rate' value:'44100'");
+        assert_eq!(ConfigManager::parse_clock_rate(&forced), Some(44100));
+
+        assert_eq!(ConfigManager::parse_clock_rate("Found nothing"), None);
+    }
+
+    #[test]
+    fn test_extract_filename_skips_comments() {
+        let config = r#"
+            # filename = "/old/commented.wav"
+            nodes = [
+                { type = builtin label = copy name = copyFL }
+                #{ type = builtin label = convolver name = convFL_L config = { filename = "/old/decoy.wav" channel = 0 } }
+                { type = builtin label = convolver name = convFL_L config = { filename = "/ir/real #1.wav" channel = 0 } } # note
+            ]
+        "#;
+        assert_eq!(
+            ConfigManager::extract_filename_from_config(config),
+            Ok(PathBuf::from("/ir/real #1.wav"))
+        );
+
+        // A convolver node spread over several lines, after another node
+        // with a file name
+        let config = r#"
+            { type = lv2 name = other config = { filename = "/other/plugin.ttl" } }
+            {
+                type = builtin
+                label = convolver
+                config = {
+                    filename = "/ir/multiline.wav"
+                }
+            }
+        "#;
+        assert_eq!(
+            ConfigManager::extract_filename_from_config(config),
+            Ok(PathBuf::from("/ir/multiline.wav"))
+        );
+        assert!(ConfigManager::extract_filename_from_config("# filename = \"/x.wav\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_sound_server() {
+        let pw_info = "\tid: 0\n\ttype: PipeWire:Interface:Core/4\n\tversion: \"1.0.5\"\n\tname: \"pipewire-0\"\n";
+        assert_eq!(
+            ConfigManager::parse_core_version(pw_info),
+            Some("1.0.5".to_string())
+        );
+        let pactl_info = "Server String: /run/user/1000/pulse/native\nServer Name: pulseaudio\nServer Version: 16.1\n";
+        assert_eq!(
+            ConfigManager::parse_server_name(pactl_info),
+            Some("pulseaudio".to_string())
+        );
+        assert_eq!(ConfigManager::parse_server_name("nothing"), None);
+    }
+
+    #[test]
+    fn test_device_id_follows_the_device_name() {
+        assert_eq!(ConfigManager::derive_device_id("Virtual Surround Sink"), "");
+        assert_eq!(
+            ConfigManager::derive_device_id(" Desk Headphones! "),
+            "desk-headphones"
+        );
+        let headphones = ConfigManager::derive_device_id("Наушники");
+        assert!(headphones.starts_with("device-"));
+        assert_ne!(headphones, ConfigManager::derive_device_id("Колонки"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = AppSettings::default();
+        settings.config_dir = Some(dir.path().to_path_buf());
+        settings.virtual_device_name = "Desk".to_string();
+        let settings = Rc::new(RefCell::new(settings));
+        let manager = ConfigManager::new(Rc::clone(&settings)).unwrap();
+        assert_eq!(manager.device_id(), "desk");
+        // A set identifier wins over the name
+        settings.borrow_mut().device_id = Some(String::new());
+        assert_eq!(manager.device_id(), "");
+    }
+
+    #[test]
+    fn test_preflight_refuses_unusable_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = AppSettings::default();
+        settings.config_dir = Some(dir.path().to_path_buf());
+        // Dev mode doesn't need PipeWire to run
+        settings.dev_mode = true;
+        let manager = ConfigManager::new(Rc::new(RefCell::new(settings))).unwrap();
+        let wav = Path::new("/ir/sadie.wav");
+
+        assert_eq!(manager.preflight(wav, 14, false), Ok(()));
+        assert_eq!(
+            manager.preflight(Path::new("/ir/sadie.flac"), 14, false),
+            Err(PreflightError::NotWav)
+        );
+        assert_eq!(
+            manager.preflight(wav, 2, false),
+            Err(PreflightError::ChannelMismatch {
+                channels: 2,
+                expected: 14,
+                layout: ChannelLayout::Surround71,
+            })
+        );
+        assert_eq!(manager.preflight(wav, 2, true), Ok(()));
+    }
+
+    #[test]
+    fn test_unit_names_are_not_options() {
+        assert!(ConfigManager::is_valid_unit_name("pipewire-pulse"));
+        assert!(ConfigManager::is_valid_unit_name("easyeffects.service"));
+        assert!(!ConfigManager::is_valid_unit_name("--now"));
+        assert!(!ConfigManager::is_valid_unit_name("-x"));
+        assert!(!ConfigManager::is_valid_unit_name("two units"));
+        assert!(!ConfigManager::is_valid_unit_name(""));
+    }
+
     #[test]
     fn test_filter_audio_sinks() {
         let mut dev1 = HashMap::new();
@@ -441,10 +1398,234 @@ mod tests {
         let mut dev3 = HashMap::new();
         dev3.insert("id".to_string(), "38".to_string());
         // no media.class
+        let mut dev4 = HashMap::new();
+        dev4.insert("id".to_string(), "39".to_string());
+        dev4.insert("media.class".to_string(), "Audio/Sink".to_string());
+        dev4.insert(
+            "node.name".to_string(),
+            "effect_input.virtual-surround-7.1-irategoose-headphones-2".to_string(),
+        );
+
+        let mut dev5 = HashMap::new();
+        dev5.insert("id".to_string(), "40".to_string());
+        dev5.insert("media.class".to_string(), "Audio/Sink".to_string());
+        dev5.insert(
+            "node.name".to_string(),
+            "effect_input.virtual-surround-5.1-irategoose".to_string(),
+        );
 
-        let devices = vec![dev1, dev2, dev3];
+        let devices = vec![dev1, dev2, dev3, dev4, dev5];
         let filtered = ConfigManager::filter_audio_sinks(&devices);
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].get("id"), Some(&"36".to_string()));
     }
+
+    #[test]
+    fn test_every_layout_has_a_template() {
+        for layout in ChannelLayout::ALL {
+            let template = LayoutTemplate::of(layout).template;
+            for placeholder in [
+                "{IRFILETEMPLATE}",
+                "{DEVICENAMETEMPLATE}",
+                "{VIRTUALNODENAME}",
+                "{OUTPUTDEVICE}",
+                "{GAINTEMPLATE}",
+            ] {
+                assert!(template.contains(placeholder), "{layout:?}: {placeholder}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_installed_devices_are_listed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = AppSettings::default();
+        settings.config_dir = Some(dir.path().to_path_buf());
+        let manager = ConfigManager::new(Rc::new(RefCell::new(settings))).unwrap();
+
+        let conf_dir = dir.path().join("pipewire/pipewire.conf.d");
+        fs::create_dir_all(&conf_dir).unwrap();
+        let config =
+            |name: &str| format!("node.description = \"{name}\"\nfilename = \"/none.wav\"");
+        for (file, name) in [
+            ("sink-virtual-surround-7.1-irategoose.conf", "Default"),
+            ("sink-virtual-surround-5.1-irategoose-desk.conf", "Desk"),
+            // An identifier is not cut short at a dash
+            (
+                "sink-virtual-surround-7.1-irategoose-desk-left.conf",
+                "Desk Left",
+            ),
+            ("sink-virtual-surround-7.1-irategoose.conf.bak", "Backup"),
+            ("sink-other.conf", "Other"),
+        ] {
+            fs::write(conf_dir.join(file), config(name)).unwrap();
+        }
+
+        let devices = manager.installed_devices().unwrap();
+        let listed: Vec<_> = devices
+            .iter()
+            .map(|d| (d.device_id.as_str(), d.name.as_str(), d.checksum))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                ("", "Default", 0),
+                ("desk", "Desk", 0),
+                ("desk-left", "Desk Left", 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_refers_to_original_file_without_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = AppSettings::default();
+        settings.config_dir = Some(dir.path().to_path_buf());
+        settings.copy_ir_file = false;
+        let manager = ConfigManager::new(Rc::new(RefCell::new(settings))).unwrap();
+
+        let wav = dir.path().join("library/ir.wav");
+        fs::create_dir_all(wav.parent().unwrap()).unwrap();
+        fs::write(&wav, b"RIFF").unwrap();
+        manager.write_device_files(&wav, 0).unwrap();
+
+        let config = fs::read_to_string(manager.configured_config_path()).unwrap();
+        assert_eq!(
+            ConfigManager::extract_filename_from_config(&config).unwrap(),
+            wav
+        );
+        assert!(!manager.hrir_dir().unwrap().exists());
+    }
+
+    #[test]
+    fn test_earlier_copy_can_be_used_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = AppSettings::default();
+        settings.config_dir = Some(dir.path().to_path_buf());
+        let settings = Rc::new(RefCell::new(settings));
+        let manager = ConfigManager::new(Rc::clone(&settings)).unwrap();
+
+        let wav = dir.path().join("library/ir.wav");
+        fs::create_dir_all(wav.parent().unwrap()).unwrap();
+        fs::write(&wav, b"RIFF").unwrap();
+        manager.write_device_files(&wav, 0).unwrap();
+        let copy = manager.hrir_dir().unwrap().join("ir.wav");
+        assert!(copy.exists());
+
+        // The source now lives under the hrir directory
+        settings.borrow_mut().copy_ir_file = false;
+        manager.write_device_files(&copy, 0).unwrap();
+        let config = fs::read_to_string(manager.config_path()).unwrap();
+        assert_eq!(
+            ConfigManager::extract_filename_from_config(&config).unwrap(),
+            copy
+        );
+        assert!(copy.exists());
+    }
+
+    #[test]
+    fn test_backups_keep_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = AppSettings::default();
+        settings.config_dir = Some(dir.path().to_path_buf());
+        let manager = ConfigManager::new(Rc::new(RefCell::new(settings))).unwrap();
+        let library = dir.path().join("library");
+        fs::create_dir_all(&library).unwrap();
+        let write = |name: &str| {
+            fs::write(library.join(name), b"RIFF").unwrap();
+            manager.backup_config().unwrap();
+            manager.write_device_files(&library.join(name), 0).unwrap();
+        };
+
+        write("first.wav");
+        write("second.wav");
+        write("third.wav");
+        let hrir_dir = manager.hrir_dir().unwrap();
+        let backup = ConfigManager::with_suffix(&hrir_dir, ConfigManager::BACKUP_SUFFIX);
+        let older = ConfigManager::with_suffix(&hrir_dir, ConfigManager::OLDER_BACKUP_SUFFIX);
+        assert!(backup.join("second.wav").exists());
+        assert!(older.join("first.wav").exists());
+
+        // Dropping the older backup leaves files the app didn't copy there
+        fs::write(older.join("own.txt"), b"").unwrap();
+        let _ = manager.backup_config();
+        assert!(!older.join("first.wav").exists());
+        assert!(older.join("own.txt").exists());
+    }
+
+    #[test]
+    fn test_write_keeps_config_that_cant_be_backed_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = AppSettings::default();
+        settings.config_dir = Some(dir.path().to_path_buf());
+        settings.dev_mode = true;
+        let manager = ConfigManager::new(Rc::new(RefCell::new(settings))).unwrap();
+        let config_path = manager.config_path();
+        fs::write(&config_path, "installed").unwrap();
+        // The previous backup can't move aside to make room for a new one
+        let backup = ConfigManager::with_suffix(&config_path, ConfigManager::BACKUP_SUFFIX);
+        fs::write(&backup, "backup").unwrap();
+        let older = ConfigManager::with_suffix(&config_path, ConfigManager::OLDER_BACKUP_SUFFIX);
+        fs::create_dir_all(older.join("own")).unwrap();
+
+        let wav = dir.path().join("ir.wav");
+        fs::write(&wav, b"RIFF").unwrap();
+        assert!(manager.write_config(&wav, 0).is_err());
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), "installed");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "backup");
+    }
+
+    #[test]
+    fn test_custom_storage_keeps_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = dir.path().join("HeSuVi");
+        let library_file = storage.join("hrir/44/own.wav");
+        fs::create_dir_all(library_file.parent().unwrap()).unwrap();
+        fs::write(&library_file, b"RIFF").unwrap();
+        let mut settings = AppSettings::default();
+        settings.config_dir = Some(dir.path().to_path_buf());
+        settings.hrir_directory = Some(storage.clone());
+        let manager = ConfigManager::new(Rc::new(RefCell::new(settings))).unwrap();
+
+        let library = dir.path().join("library");
+        fs::create_dir_all(&library).unwrap();
+        for name in ["first.wav", "second.wav"] {
+            fs::write(library.join(name), b"RIFF").unwrap();
+            manager.write_device_files(&library.join(name), 0).unwrap();
+        }
+        let installed = storage.join("irate_goose_hrir");
+        let config = fs::read_to_string(manager.config_path()).unwrap();
+        assert_eq!(
+            ConfigManager::extract_filename_from_config(&config).unwrap(),
+            installed.join("second.wav")
+        );
+        assert!(!installed.join("first.wav").exists());
+        assert!(library_file.exists());
+
+        // A file inside the storage location is refused
+        assert!(manager.write_device_files(&library_file, 0).is_err());
+        assert!(library_file.exists());
+    }
+
+    #[test]
+    fn test_config_of_other_layout_is_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = AppSettings::default();
+        settings.config_dir = Some(dir.path().to_path_buf());
+        let settings = Rc::new(RefCell::new(settings));
+        let manager = ConfigManager::new(Rc::clone(&settings)).unwrap();
+
+        let path_7_1 = manager.config_path();
+        assert!(path_7_1.ends_with("sink-virtual-surround-7.1-irategoose.conf"));
+        fs::create_dir_all(path_7_1.parent().unwrap()).unwrap();
+        fs::write(&path_7_1, "").unwrap();
+
+        settings.borrow_mut().channel_layout = ChannelLayout::Surround51;
+        assert_eq!(manager.config_path(), path_7_1);
+        assert!(
+            manager
+                .configured_config_path()
+                .ends_with("sink-virtual-surround-5.1-irategoose.conf")
+        );
+    }
 }
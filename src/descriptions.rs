@@ -1,8 +1,9 @@
-use anyhow::{Result, anyhow};
-use csv::ReaderBuilder;
-use log::warn;
+use anyhow::{Context, Result, anyhow};
+use csv::{ReaderBuilder, StringRecord};
+use log::{info, warn};
 use std::collections::BTreeMap;
 use std::io::Read;
+use std::path::Path;
 use std::rc::Rc;
 
 /// Represents the configuration type for HRTF measurements
@@ -22,6 +23,14 @@ impl Configuration {
             _ => None,  // Invalid values are also treated as None
         }
     }
+
+    /// Name of the configuration, as written in the database
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Configuration::Headphones => "Headphones",
+            Configuration::Speakers => "Speakers",
+        }
+    }
 }
 
 /// Represents a single entry from the HRTF descriptions CSV (excluding the HRIR filename)
@@ -36,10 +45,19 @@ pub struct HRTFMetadata {
     pub points: Option<u32>,
 }
 
+/// Sample rates that may end a file name, optionally followed by "hz"
+const RATE_SUFFIXES: [&str; 12] = [
+    "176400", "192000", "44100", "48000", "88200", "96000", "176.4k", "44.1k", "88.2k", "192k",
+    "48k", "96k",
+];
+
 /// Provides descriptions and credits for WAV files from the embedded database
 pub struct Descriptions {
     /// Maps HRIR filename (without extension) to its description entry
     entries: BTreeMap<String, Rc<HRTFMetadata>>,
+    /// Maps normalized HRIR filenames to the HRIR filename, None if several
+    /// entries normalize to the same name
+    normalized: BTreeMap<String, Option<String>>,
 }
 
 impl Descriptions {
@@ -62,70 +80,169 @@ impl Descriptions {
         let mut entries = BTreeMap::new();
 
         for result in rdr.records() {
-            let record = result?;
-
-            // Expected columns: HRIR;HRTF;Configuration;Description;Source;Credits;Points
-            if record.len() != 7 {
-                return Err(anyhow!(
-                    "Invalid CSV record length: expected 7 columns, got {}",
-                    record.len()
-                ));
-            }
-
-            let hrir = record[0].to_string();
+            let (hrir, entry) = parse_record(&result?)?;
 
             // HRIR should be unique
             if entries.contains_key(&hrir) {
                 warn!("Non-unique HRIR value '{}', skipping second entry", hrir);
                 continue;
             }
+            entries.insert(hrir, Rc::new(entry));
+        }
 
-            // Parse configuration field
-            let config_str = record[2].trim();
-            let configuration = Configuration::from_str(config_str);
-            if !config_str.is_empty() && configuration.is_none() {
-                warn!(
-                    "Invalid configuration value '{}' for HRIR '{}', treating as None",
-                    config_str, hrir
-                );
-            }
+        Ok(Self::from_entries(entries))
+    }
 
-            // Parse points field
-            let points_str = record[6].trim();
-            let points = if points_str.is_empty() {
-                None
-            } else {
-                match points_str.parse::<u32>() {
-                    Ok(value) => Some(value),
-                    Err(e) => {
-                        warn!(
-                            "Failed to parse points '{}' as u32 for HRIR '{}': {}, treating as None",
-                            points_str, hrir, e
-                        );
-                        None
-                    }
+    /// Creates the embedded database with the entries of an external CSV
+    /// file of the same columns on top: they replace embedded entries of the
+    /// same HRIR and add new ones. Broken rows are skipped with a warning.
+    pub fn with_overrides(path: &Path) -> Result<Self> {
+        let mut descriptions = Self::new()?;
+        let mut rdr = ReaderBuilder::new()
+            .delimiter(b';')
+            .has_headers(true)
+            .from_path(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut entries = std::mem::take(&mut descriptions.entries);
+        let mut merged = 0;
+        for (row, result) in rdr.records().enumerate() {
+            // Row 1 is the header
+            match result
+                .map_err(anyhow::Error::from)
+                .and_then(|r| parse_record(&r))
+            {
+                Ok((hrir, entry)) => {
+                    entries.insert(hrir, Rc::new(entry));
+                    merged += 1;
                 }
-            };
-
-            let entry = HRTFMetadata {
-                hrtf: record[1].to_string(),
-                configuration,
-                description: record[3].to_string(),
-                source: record[4].to_string(),
-                credits: record[5].to_string(),
-                points,
-            };
+                Err(e) => warn!("Skipping row {} of {}: {}", row + 2, path.display(), e),
+            }
+        }
+        info!("Loaded {} descriptions from {}", merged, path.display());
+        Ok(Self::from_entries(entries))
+    }
 
-            entries.insert(hrir, Rc::new(entry));
+    /// Loads the descriptions for the app: the embedded database, with the
+    /// entries of `overrides` on top if set. Never fails, a database that
+    /// can't be loaded is reported and left out.
+    pub fn load(overrides: Option<&Path>) -> Self {
+        if let Some(path) = overrides {
+            match Self::with_overrides(path) {
+                Ok(descriptions) => return descriptions,
+                Err(e) => warn!("Can not load descriptions from {}: {:#}", path.display(), e),
+            }
         }
+        // Metadata is optional, so a broken database must not stop the app.
+        Self::new().unwrap_or_else(|e| {
+            warn!("Can not load HRTF descriptions, continuing without metadata. Reason: {e}");
+            Self::empty()
+        })
+    }
 
-        Ok(Self { entries })
+    /// Creates a Descriptions instance without entries. Used when the embedded
+    /// database can't be loaded, so the app can run without metadata.
+    pub fn empty() -> Self {
+        Self::from_entries(BTreeMap::new())
+    }
+
+    /// Wraps the entries and indexes their normalized names.
+    fn from_entries(entries: BTreeMap<String, Rc<HRTFMetadata>>) -> Self {
+        let mut normalized = BTreeMap::new();
+        for hrir in entries.keys() {
+            normalized
+                .entry(normalize_name(hrir))
+                .and_modify(|found: &mut Option<String>| *found = None)
+                .or_insert_with(|| Some(hrir.clone()));
+        }
+        Self {
+            entries,
+            normalized,
+        }
     }
 
     /// Get a shared reference-counted handle to the metadata.
     pub fn get_rc(&self, hrir_filename: &str) -> Option<Rc<HRTFMetadata>> {
         self.entries.get(hrir_filename).cloned()
     }
+
+    /// Looks up the metadata of a file whose name differs from the HRIR
+    /// filename only by case, separators or a sample rate at the end, like
+    /// `sadie-019_48000` for `SADIE_019`. Returns the HRIR filename matched
+    /// too. Only meant as a fallback when `get_rc` finds nothing.
+    pub fn get_rc_fuzzy(&self, file_name: &str) -> Option<(&str, Rc<HRTFMetadata>)> {
+        let hrir = self
+            .normalized
+            .get(&normalize_name(file_name))?
+            .as_deref()?;
+        Some((hrir, self.entries.get(hrir)?.clone()))
+    }
+}
+
+/// Parses a row of the database: the HRIR filename and its entry.
+fn parse_record(record: &StringRecord) -> Result<(String, HRTFMetadata)> {
+    // Expected columns: HRIR;HRTF;Configuration;Description;Source;Credits;Points
+    if record.len() != 7 {
+        return Err(anyhow!(
+            "Invalid CSV record length: expected 7 columns, got {}",
+            record.len()
+        ));
+    }
+
+    let hrir = record[0].to_string();
+
+    // Parse configuration field
+    let config_str = record[2].trim();
+    let configuration = Configuration::from_str(config_str);
+    if !config_str.is_empty() && configuration.is_none() {
+        warn!(
+            "Invalid configuration value '{}' for HRIR '{}', treating as None",
+            config_str, hrir
+        );
+    }
+
+    // Parse points field
+    let points_str = record[6].trim();
+    let points = if points_str.is_empty() {
+        None
+    } else {
+        match points_str.parse::<u32>() {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!(
+                    "Failed to parse points '{}' as u32 for HRIR '{}': {}, treating as None",
+                    points_str, hrir, e
+                );
+                None
+            }
+        }
+    };
+
+    let entry = HRTFMetadata {
+        hrtf: record[1].to_string(),
+        configuration,
+        description: record[3].to_string(),
+        source: record[4].to_string(),
+        credits: record[5].to_string(),
+        points,
+    };
+    Ok((hrir, entry))
+}
+
+/// Reduces a file name to lowercase letters and digits, dropping a sample
+/// rate at the end if a separator comes before it.
+fn normalize_name(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let without_hz = lower.strip_suffix("hz").unwrap_or(&lower);
+    let mut stem = lower.as_str();
+    for rate in RATE_SUFFIXES {
+        if let Some(rest) = without_hz.strip_suffix(rate)
+            && rest.ends_with(['_', '-', ' ', '.'])
+        {
+            stem = rest;
+            break;
+        }
+    }
+    stem.chars().filter(|c| c.is_alphanumeric()).collect()
 }
 
 #[cfg(test)]
@@ -183,6 +300,49 @@ mod tests {
         ); // trimmed
     }
 
+    #[test]
+    fn test_fuzzy_match_of_file_names() {
+        let descriptions = Descriptions::new().expect("Failed to load descriptions database");
+        for name in ["SADIE_019_48000", "sadie-019 44.1kHz", "Sadie_019-96k"] {
+            let (hrir, _) = descriptions
+                .get_rc_fuzzy(name)
+                .unwrap_or_else(|| panic!("{name} should match SADIE_019"));
+            assert_eq!(hrir, "SADIE_019");
+        }
+        // A rate is only cut after a separator, other digits are kept
+        assert_eq!(normalize_name("irc48000"), "irc48000");
+        assert_eq!(
+            normalize_name("RIEC_hrir_subject_048"),
+            "riechrirsubject048"
+        );
+        assert!(descriptions.get_rc_fuzzy("SADIE_0190").is_none());
+    }
+
+    #[test]
+    fn test_overrides_replace_and_extend_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("descriptions.csv");
+        std::fs::write(
+            &path,
+            "HRIR;HRTF;Configuration;Description;Source;Credits;Points\n\
+             SADIE_019;SADIE;Headphones;Replaced;Here;Me;170\n\
+             broken;row\n\
+             My_Own;Mine;Speakers;New entry;;;\n",
+        )
+        .unwrap();
+        let descriptions = Descriptions::with_overrides(&path).unwrap();
+
+        assert_eq!(
+            descriptions.get_rc("SADIE_019").unwrap().description,
+            "Replaced"
+        );
+        assert_eq!(descriptions.get_rc("My_Own").unwrap().hrtf, "Mine");
+        assert!(descriptions.get_rc("broken").is_none());
+        // Untouched embedded entries stay, and new ones are matched loosely too
+        assert!(descriptions.get_rc("SADIE_020").is_some());
+        assert!(descriptions.get_rc_fuzzy("my-own_48000").is_some());
+    }
+
     #[test]
     fn test_sadie_019_entry() {
         // Test that the database contains the SADIE_019 entry with expected values
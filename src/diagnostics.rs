@@ -0,0 +1,81 @@
+use std::fmt::Write;
+use std::fs;
+use std::process::Command;
+
+use crate::config_manager::ConfigManager;
+use crate::file_manager::format_checksum;
+use crate::settings::AppSettings;
+
+/// Assembles a plain text problem report for bug reports: app version,
+/// OS and PipeWire versions, the resolved config and settings paths, the
+/// installed config status and the current log.
+pub fn build_problem_report(
+    config_manager: &ConfigManager,
+    settings: &AppSettings,
+    config_installed: Option<u128>,
+    log_lines: &[String],
+) -> String {
+    let settings_path = settings
+        .settings_path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|e| format!("unknown ({e})"));
+    let config_status = match config_installed {
+        None => "not installed".to_string(),
+        Some(0) => "installed, IR file missing or damaged".to_string(),
+        Some(checksum) => format!("installed, IR file checksum {}", format_checksum(checksum)),
+    };
+
+    let mut report = String::new();
+    // Writing to a String can't fail
+    let _ = writeln!(report, "Irate Goose v{}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "OS: {}", os_description());
+    let _ = writeln!(report, "PipeWire: {}", pipewire_version());
+    let _ = writeln!(
+        report,
+        "Config file: {}",
+        config_manager.config_path().display()
+    );
+    let _ = writeln!(report, "Settings file: {settings_path}");
+    let _ = writeln!(report, "Config status: {config_status}");
+    let _ = writeln!(report, "Dev mode: {}", settings.dev_mode);
+    let _ = writeln!(report);
+    let _ = writeln!(report, "Log:");
+    for line in log_lines {
+        let _ = writeln!(report, "{line}");
+    }
+    report
+}
+
+/// Describes the OS from /etc/os-release and the kernel release.
+fn os_description() -> String {
+    let distro = fs::read_to_string("/etc/os-release")
+        .ok()
+        .and_then(|content| {
+            content.lines().find_map(|line| {
+                line.strip_prefix("PRETTY_NAME=")
+                    .map(|v| v.trim_matches('"').to_string())
+            })
+        })
+        .unwrap_or_else(|| "unknown distribution".to_string());
+    let kernel = command_output("uname", &["-sr"]).unwrap_or_else(|e| format!("unknown ({e})"));
+    format!("{distro}, {kernel}")
+}
+
+/// Gets the PipeWire version reported by `pw-cli --version`.
+fn pipewire_version() -> String {
+    command_output("pw-cli", &["--version"])
+        .map(|out| out.lines().collect::<Vec<_>>().join("; "))
+        .unwrap_or_else(|e| format!("unavailable ({e})"))
+}
+
+/// Runs a command and returns its trimmed standard output.
+fn command_output(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!("exit status {}", output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
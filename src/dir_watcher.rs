@@ -0,0 +1,147 @@
+//! Watches the IR directory for added, removed or modified WAV files.
+//!
+//! Changes come from file system notifications through `notify`, debounced
+//! so that a bulk copy is reported once, after it settled.
+
+use log::warn;
+use notify_debouncer_mini::notify::{self, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{Config, DebounceEventResult, Debouncer, new_debouncer_opt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::file_manager::FileManager;
+
+/// How long the files have to stay unchanged before a change is reported,
+/// so bulk copies settle before a rescan starts.
+const SETTLE_TIME: Duration = Duration::from_secs(1);
+
+/// Watches a directory with file system notifications. Dropping it stops
+/// watching.
+pub struct DirWatcher {
+    dir: PathBuf,
+    depth: Option<u32>,
+    follow_symlinks: bool,
+    /// Set by the debouncer when a change settled, cleared by `take_change`
+    changed: Arc<AtomicBool>,
+    /// None if the directory could not be watched
+    _debouncer: Option<Debouncer<RecommendedWatcher>>,
+}
+
+impl DirWatcher {
+    /// Starts watching `dir` down to `depth` levels of subdirectories, and
+    /// into symlinked directories if `follow_symlinks` is set, the same as
+    /// the scan. The files present now are not reported.
+    pub fn new(dir: PathBuf, depth: Option<u32>, follow_symlinks: bool) -> DirWatcher {
+        let changed = Arc::new(AtomicBool::new(false));
+        let debouncer = match Self::start(&dir, depth, follow_symlinks, Arc::clone(&changed)) {
+            Ok(debouncer) => Some(debouncer),
+            Err(e) => {
+                warn!("Failed to start watching {}: {}", dir.display(), e);
+                None
+            }
+        };
+        DirWatcher {
+            dir,
+            depth,
+            follow_symlinks,
+            changed,
+            _debouncer: debouncer,
+        }
+    }
+
+    /// Subscribes to the changes under `dir`, setting `changed` for those
+    /// the scan would see.
+    fn start(
+        dir: &Path,
+        depth: Option<u32>,
+        follow_symlinks: bool,
+        changed: Arc<AtomicBool>,
+    ) -> notify::Result<Debouncer<RecommendedWatcher>> {
+        let root = dir.to_path_buf();
+        let config = Config::default()
+            .with_timeout(SETTLE_TIME)
+            .with_notify_config(notify::Config::default().with_follow_symlinks(follow_symlinks));
+        let mut debouncer =
+            new_debouncer_opt(config, move |result: DebounceEventResult| match result {
+                Ok(events) => {
+                    if events
+                        .iter()
+                        .any(|event| Self::is_relevant(&root, depth, &event.path))
+                    {
+                        changed.store(true, Ordering::Relaxed);
+                    }
+                }
+                Err(e) => warn!("Watching {} failed: {}", root.display(), e),
+            })?;
+        let mode = match depth {
+            Some(0) => RecursiveMode::NonRecursive,
+            _ => RecursiveMode::Recursive,
+        };
+        debouncer.watcher().watch(dir, mode)?;
+        Ok(debouncer)
+    }
+
+    /// Checks whether a change of `path` can affect a scan of `root`: an IR
+    /// file, or a directory moved in or out with its files, no deeper than
+    /// the scan goes.
+    fn is_relevant(root: &Path, depth: Option<u32>, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(root) else {
+            return false;
+        };
+        let is_ir_file = FileManager::is_ir_file(path);
+        // Subdirectories the scan has to enter to find the file or directory
+        let components = relative.components().count();
+        let levels = if is_ir_file {
+            components.saturating_sub(1)
+        } else {
+            components
+        };
+        if depth.is_some_and(|depth| levels > depth as usize) {
+            return false;
+        }
+        // A removed directory can't be told from a removed file by looking
+        is_ir_file || path.is_dir() || path.extension().is_none()
+    }
+
+    /// Directory being watched.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Levels of subdirectories being watched, None = unlimited.
+    pub fn depth(&self) -> Option<u32> {
+        self.depth
+    }
+
+    /// Whether symlinked directories are watched.
+    pub fn follow_symlinks(&self) -> bool {
+        self.follow_symlinks
+    }
+
+    /// Returns true once after the WAV files in the directory changed.
+    pub fn take_change(&self) -> bool {
+        self.changed.swap(false, Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relevant_changes_follow_the_scan_depth() {
+        let root = Path::new("/ir");
+        let relevant = |depth, path: &str| DirWatcher::is_relevant(root, depth, Path::new(path));
+        assert!(relevant(None, "/ir/a/b/c.wav"));
+        assert!(relevant(Some(0), "/ir/c.wav"));
+        assert!(!relevant(Some(0), "/ir/a/c.wav"));
+        assert!(relevant(Some(1), "/ir/a/c.wav"));
+        // A directory the scan enters, and one it doesn't
+        assert!(relevant(Some(1), "/ir/a"));
+        assert!(!relevant(Some(1), "/ir/a/b"));
+        assert!(!relevant(None, "/ir/readme.txt"));
+        assert!(!relevant(None, "/elsewhere/c.wav"));
+    }
+}
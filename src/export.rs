@@ -0,0 +1,129 @@
+//! Export of scanned file info, for cataloguing IR collections.
+
+use anyhow::{Context, Result};
+use csv::WriterBuilder;
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+
+use crate::file_manager::{WavFileData, WaveSampleRate, format_checksum};
+
+/// Writes the files with their metadata as CSV with a header row.
+/// Uses semicolons like the embedded descriptions database.
+pub fn write_files_csv<'a, W, I>(writer: W, files: I) -> Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a WavFileData>,
+{
+    let mut csv = WriterBuilder::new().delimiter(b';').from_writer(writer);
+    csv.write_record([
+        "Path",
+        "SampleRate",
+        "Checksum",
+        "HRTF",
+        "Configuration",
+        "Description",
+        "Source",
+        "Credits",
+        "Points",
+    ])?;
+    for wave in files {
+        let sample_rate = match wave.sample_rate {
+            WaveSampleRate::Damaged => "Damaged".to_string(),
+            rate => rate
+                .hz()
+                .map(|hz| hz.to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+        };
+        let checksum = if wave.checksum == 0 {
+            String::new()
+        } else {
+            format_checksum(wave.checksum)
+        };
+        let metadata = wave.metadata.as_deref();
+        let field = |get: fn(&crate::descriptions::HRTFMetadata) -> String| {
+            metadata.map(get).unwrap_or_default()
+        };
+        csv.write_record([
+            wave.path.to_string_lossy().to_string(),
+            sample_rate,
+            checksum,
+            field(|m| m.hrtf.clone()),
+            field(|m| {
+                m.configuration
+                    .map(|c| c.as_str().to_string())
+                    .unwrap_or_default()
+            }),
+            field(|m| m.description.clone()),
+            field(|m| m.source.clone()),
+            field(|m| m.credits.clone()),
+            field(|m| m.points.map(|p| p.to_string()).unwrap_or_default()),
+        ])?;
+    }
+    csv.flush().context("Failed to write CSV")?;
+    Ok(())
+}
+
+/// A file as written to the JSON export. `WavFileData` holds its metadata
+/// in an `Rc`, so it is copied into this plain record instead.
+#[derive(Serialize)]
+struct FileRecord<'a> {
+    relative_path: &'a Path,
+    /// Rate in Hz, "damaged" for unreadable files, null if unknown
+    sample_rate: serde_json::Value,
+    channels: u16,
+    /// Empty for damaged files
+    checksum: String,
+    metadata: Option<MetadataRecord<'a>>,
+}
+
+/// HRTF description of a file in the JSON export.
+#[derive(Serialize)]
+struct MetadataRecord<'a> {
+    hrtf: &'a str,
+    configuration: Option<&'static str>,
+    description: &'a str,
+    source: &'a str,
+    credits: &'a str,
+    points: Option<u32>,
+}
+
+impl<'a> FileRecord<'a> {
+    fn of(wave: &'a WavFileData) -> FileRecord<'a> {
+        let sample_rate = match wave.sample_rate {
+            WaveSampleRate::Damaged => "damaged".into(),
+            rate => rate.hz().into(),
+        };
+        let checksum = if wave.checksum == 0 {
+            String::new()
+        } else {
+            format_checksum(wave.checksum)
+        };
+        FileRecord {
+            relative_path: &wave.relative_path,
+            sample_rate,
+            channels: wave.channels,
+            checksum,
+            metadata: wave.metadata.as_deref().map(|m| MetadataRecord {
+                hrtf: &m.hrtf,
+                configuration: m.configuration.map(|c| c.as_str()),
+                description: &m.description,
+                source: &m.source,
+                credits: &m.credits,
+                points: m.points,
+            }),
+        }
+    }
+}
+
+/// Writes the files with their metadata as a pretty-printed JSON array.
+pub fn write_files_json<'a, W, I>(mut writer: W, files: I) -> Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a WavFileData>,
+{
+    let records: Vec<FileRecord> = files.into_iter().map(FileRecord::of).collect();
+    serde_json::to_writer_pretty(&mut writer, &records).context("Failed to write JSON")?;
+    writeln!(writer).context("Failed to write JSON")?;
+    Ok(())
+}
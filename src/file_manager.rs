@@ -1,25 +1,32 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow, bail};
+use log::{info, warn};
 use rayon::prelude::*;
 use std::cell::RefCell;
-use std::mem;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, ErrorKind, Read};
 use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::SystemTime;
 use std::{
     fs,
     path::{Path, PathBuf},
 };
 
 use crate::descriptions::HRTFMetadata;
+use crate::scan_cache::{CacheEntry, FileStamp, ScanCache};
 use crate::settings::AppSettings;
 use crate::wav_file_index::WavFileIndex;
-use xxhash_rust::xxh3::xxh3_128;
+use xxhash_rust::xxh3::Xxh3;
 
 pub struct FileManager {
     settings: Rc<RefCell<AppSettings>>,
-    /// Temporary storage for scan time.
-    scanning_wave_data: Vec<WavFileData>,
-    /// Wavefile dir that was scanned last time.
-    current_wavefile_dir: Option<PathBuf>,
     descriptions: crate::descriptions::Descriptions,
+    /// Results of earlier scans, loaded at the first rescan.
+    /// None if it couldn't be loaded.
+    cache: Option<ScanCache>,
 }
 
 // All about Wav file
@@ -30,6 +37,164 @@ pub struct WavFileData {
     pub sample_rate: WaveSampleRate,
     pub metadata: Option<Rc<HRTFMetadata>>,
     pub checksum: u128,
+    /// Last modification time, None if it can't be read
+    pub modified: Option<SystemTime>,
+    /// Number of channels from the `fmt ` chunk, 0 for damaged files
+    pub channels: u16,
+    /// Bits per sample from the `fmt ` chunk, 0 for damaged files
+    pub bit_depth: u16,
+    /// Length of the audio in milliseconds, 0 if unknown
+    pub duration_ms: u32,
+    /// Size of the file in bytes, 0 if unknown
+    pub size_bytes: u64,
+    /// Found in one of the additional IR directories, not the main one
+    pub extra_directory: bool,
+}
+
+impl WavFileData {
+    /// Path that identifies the file in favorites and notes: relative to the
+    /// WAV directory, or absolute for a file of an additional directory, as
+    /// its relative path may repeat one of another directory.
+    pub fn library_path(&self) -> &Path {
+        if self.extra_directory {
+            &self.path
+        } else {
+            &self.relative_path
+        }
+    }
+}
+
+/// Info read from a WAV file during the scan.
+struct WavProbe {
+    sample_rate: WaveSampleRate,
+    checksum: u128,
+    channels: u16,
+    bit_depth: u16,
+    duration_ms: u32,
+}
+
+impl WavProbe {
+    /// Probe of a file that couldn't be read or is not a valid WAV.
+    fn damaged() -> WavProbe {
+        WavProbe {
+            sample_rate: WaveSampleRate::Damaged,
+            checksum: 0,
+            channels: 0,
+            bit_depth: 0,
+            duration_ms: 0,
+        }
+    }
+}
+
+/// Fields of a `fmt ` chunk needed by the scan.
+struct FmtChunk {
+    channels: u16,
+    sample_rate: u32,
+    /// Bytes per frame, all channels together
+    block_align: u16,
+    /// Bits per sample that carry audio, which WAVE_FORMAT_EXTENSIBLE files
+    /// may store in a larger container
+    bits_per_sample: u16,
+}
+
+impl FmtChunk {
+    /// WAVE_FORMAT_EXTENSIBLE, the real format is in the SubFormat GUID
+    const FORMAT_EXTENSIBLE: u16 = 0xfffe;
+
+    /// Parses the body of a `fmt ` chunk. Returns None if it is too short
+    /// for its format tag.
+    fn parse(fmt: &[u8]) -> Option<FmtChunk> {
+        let read_u16 = |at: usize| Some(u16::from_le_bytes([*fmt.get(at)?, *fmt.get(at + 1)?]));
+        let format = read_u16(0)?;
+        let channels = read_u16(2)?;
+        let sample_rate = u32::from_le_bytes(fmt.get(4..8)?.try_into().ok()?);
+        let block_align = read_u16(12)?;
+        let mut bits_per_sample = read_u16(14)?;
+        if format == Self::FORMAT_EXTENSIBLE {
+            // cbSize, then wValidBitsPerSample, dwChannelMask and the
+            // 16-byte SubFormat GUID
+            if read_u16(16)? < 22 || fmt.len() < 40 {
+                return None;
+            }
+            let valid_bits = read_u16(18)?;
+            if valid_bits > 0 {
+                bits_per_sample = valid_bits;
+            }
+        }
+        Some(FmtChunk {
+            channels,
+            sample_rate,
+            block_align,
+            bits_per_sample,
+        })
+    }
+}
+
+/// A WAV file found in the directory: absolute path, path relative to the
+/// scanned directory and modification time.
+type ListedFile = (PathBuf, PathBuf, Option<SystemTime>);
+
+/// A scanned file, before the descriptions are attached.
+struct ScannedFile {
+    path: PathBuf,
+    relative_path: PathBuf,
+    modified: Option<SystemTime>,
+    size_bytes: u64,
+    extra_directory: bool,
+    probe: WavProbe,
+}
+
+/// Result of a scan, passed from the worker thread to `FileManager::finish_scan`.
+pub struct ScanOutput {
+    files: Vec<ScannedFile>,
+    /// The cache the scan used, handed back to the file manager
+    cache: Option<ScanCache>,
+}
+
+/// Message sent by a background scan to its owner.
+enum ScanMessage {
+    Progress(ScanProgress),
+    Finished(Result<ScanOutput>),
+}
+
+/// A rescan running on a worker thread. Dropping it cancels the scan.
+pub struct BackgroundScan {
+    receiver: Receiver<ScanMessage>,
+    cancelled: Arc<AtomicBool>,
+    progress: ScanProgress,
+}
+
+impl BackgroundScan {
+    /// Takes the messages sent so far. Returns the result once the scan is over.
+    pub fn poll(&mut self) -> Option<Result<ScanOutput>> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(ScanMessage::Progress(progress)) => self.progress = progress,
+                Ok(ScanMessage::Finished(result)) => return Some(result),
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => {
+                    return Some(Err(anyhow!("The scan thread stopped unexpectedly")));
+                }
+            }
+        }
+    }
+
+    /// Asks the worker to stop. It skips the remaining files and its
+    /// result is discarded.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Progress as of the last `poll`.
+    pub fn progress(&self) -> ScanProgress {
+        self.progress
+    }
+}
+
+impl Drop for BackgroundScan {
+    fn drop(&mut self) {
+        self.cancel();
+    }
 }
 
 // Detected sample rate of Wav file
@@ -38,11 +203,96 @@ pub enum WaveSampleRate {
     F48000,
     F44100,
     F96000,
+    F88200,
+    F176400,
+    F192000,
     #[default]
     Unknown,
     Damaged,
 }
 
+impl WaveSampleRate {
+    /// Sample rate in Hz, None for unknown or damaged files.
+    pub fn hz(self) -> Option<u32> {
+        match self {
+            WaveSampleRate::F48000 => Some(48000),
+            WaveSampleRate::F44100 => Some(44100),
+            WaveSampleRate::F96000 => Some(96000),
+            WaveSampleRate::F88200 => Some(88200),
+            WaveSampleRate::F176400 => Some(176400),
+            WaveSampleRate::F192000 => Some(192000),
+            WaveSampleRate::Unknown | WaveSampleRate::Damaged => None,
+        }
+    }
+
+    /// Known sample rate for a rate in Hz, Unknown for any other rate.
+    pub fn from_hz(hz: u32) -> WaveSampleRate {
+        match hz {
+            44100 => WaveSampleRate::F44100,
+            48000 => WaveSampleRate::F48000,
+            96000 => WaveSampleRate::F96000,
+            88200 => WaveSampleRate::F88200,
+            176400 => WaveSampleRate::F176400,
+            192000 => WaveSampleRate::F192000,
+            _ => WaveSampleRate::Unknown,
+        }
+    }
+}
+
+/// Progress of the checksum computation during a scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanProgress {
+    /// Number of files processed so far
+    pub done: usize,
+    /// Number of files found in the directory
+    pub total: usize,
+}
+
+/// Size of the file start read for format detection. Chunk headers past it
+/// are not seen, which only affects the duration.
+const HEADER_READ_SIZE: u64 = 64 * 1024;
+
+/// Size of the buffer used to hash files.
+const HASH_BUFFER_SIZE: usize = 256 * 1024;
+
+/// Computes the xxh3_128 checksum of a file, reading it in chunks instead
+/// of loading it into memory as a whole.
+pub fn stream_checksum(path: &Path) -> io::Result<u128> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Xxh3::new();
+    let mut buffer = vec![0; HASH_BUFFER_SIZE];
+    loop {
+        match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buffer[..n]),
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(hasher.digest128())
+}
+
+/// Reads up to `len` bytes from the start of a file.
+pub fn read_file_start(path: &Path, len: u64) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    fs::File::open(path)?.take(len).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Length of `frames` at `sample_rate`, in milliseconds. A file shorter
+/// than 1 ms counts as 1 ms, since 0 means the length is unknown.
+fn duration_ms(frames: u64, sample_rate: u32) -> u32 {
+    if frames == 0 || sample_rate == 0 {
+        return 0;
+    }
+    u32::try_from((frames * 1000 / u64::from(sample_rate)).max(1)).unwrap_or(u32::MAX)
+}
+
+/// Formats a checksum as a zero-padded lowercase hex string.
+pub fn format_checksum(checksum: u128) -> String {
+    format!("{:032x}", checksum)
+}
+
 impl FileManager {
     pub fn new(
         settings: Rc<RefCell<AppSettings>>,
@@ -50,57 +300,124 @@ impl FileManager {
     ) -> FileManager {
         FileManager {
             settings,
-            scanning_wave_data: Vec::new(),
-            current_wavefile_dir: None,
             descriptions,
+            cache: None,
         }
     }
 
+    /// Replaces the descriptions attached to the files by the next scan.
+    pub fn set_descriptions(&mut self, descriptions: crate::descriptions::Descriptions) {
+        self.descriptions = descriptions;
+    }
+
     /// Searches for WAV files inside the wavefile_dir, and read info from the files it found.
     pub fn rescan_configured_directory(&mut self) -> Result<WavFileIndex> {
-        // Detect WAV files
-        self.scanning_wave_data.clear();
-        self.current_wavefile_dir = self.settings.borrow().get_wav_directory();
-        let working_path = match self.current_wavefile_dir.clone() {
-            Some(dir) => dir,
-            None => return Ok(WavFileIndex::new()), // No directory configured, nothing to scan
-        };
-        self.scan_directory(&working_path)?;
+        self.rescan_configured_directory_with_progress(|_| {})
+    }
+
+    /// Same as `rescan_configured_directory`, but calls `on_progress` after each
+    /// file is hashed. The callback is invoked from the rayon worker threads.
+    pub fn rescan_configured_directory_with_progress<F>(
+        &mut self,
+        on_progress: F,
+    ) -> Result<WavFileIndex>
+    where
+        F: Fn(ScanProgress) + Sync,
+    {
+        let job = self.prepare_scan();
+        let output = job.run(&AtomicBool::new(false), on_progress)?;
+        Ok(self.finish_scan(output))
+    }
 
-        // Detect sample rates and compute checksums
-        // This will store intermediate results
-        struct FileMetadataRecord {
-            samplerate: WaveSampleRate,
-            checksum: u128,
+    /// Starts a rescan of the configured directory on a worker thread.
+    /// Its result is turned into an index with `finish_scan`.
+    pub fn start_background_scan(&mut self) -> BackgroundScan {
+        let job = self.prepare_scan();
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let job_cancelled = Arc::clone(&cancelled);
+        thread::spawn(move || {
+            let progress_sender = sender.clone();
+            let result = job.run(&job_cancelled, |progress| {
+                let _ = progress_sender.send(ScanMessage::Progress(progress));
+            });
+            // The receiver is gone if the scan was cancelled
+            let _ = sender.send(ScanMessage::Finished(result));
+        });
+        BackgroundScan {
+            receiver,
+            cancelled,
+            progress: ScanProgress { done: 0, total: 0 },
         }
-        // Copy all file paths, keeping the order
-        let paths: Vec<PathBuf> = self
-            .scanning_wave_data
-            .iter()
-            .map(|w| w.path.clone())
-            .collect();
-        // Multithreaded scan of files to collect metadata
-        let metarecords: Vec<FileMetadataRecord> = paths
-            .par_iter()
-            .map(|path| {
-                let (samplerate, checksum) = Self::detect_sample_rate_and_checksum(path);
-                FileMetadataRecord {
-                    samplerate,
-                    checksum,
+    }
+
+    /// Collects what a scan needs from the settings. The cache is lent to
+    /// the scan and comes back with its output.
+    fn prepare_scan(&mut self) -> ScanJob {
+        let mut dirs = self.settings.borrow().wav_directories();
+        // A single file stands for the directory it lives in.
+        if let Some(path) = dirs.first_mut()
+            && path.is_file()
+            && let Some(parent) = path.parent()
+        {
+            *path = parent.to_path_buf();
+        }
+        if self.cache.is_none() {
+            self.cache = match ScanCache::load(&self.settings.borrow()) {
+                Ok(cache) => Some(cache),
+                Err(e) => {
+                    warn!("Scan cache is not available: {:#}", e);
+                    None
+                }
+            };
+        }
+        ScanJob {
+            dirs,
+            depth: self.settings.borrow().scan_depth,
+            follow_symlinks: self.settings.borrow().follow_symlinks,
+            cache: self.cache.take(),
+        }
+    }
+
+    /// Builds the file index from the output of a scan and attaches the
+    /// known descriptions.
+    pub fn finish_scan(&mut self, output: ScanOutput) -> WavFileIndex {
+        if output.cache.is_some() {
+            self.cache = output.cache;
+        }
+        let mut wavdata: Vec<WavFileData> = output
+            .files
+            .into_iter()
+            .map(|file| {
+                let stem = file.path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+                // Exact names win, close ones are only a fallback
+                let metadata = self.descriptions.get_rc(stem).or_else(|| {
+                    let (hrir, metadata) = self.descriptions.get_rc_fuzzy(stem)?;
+                    info!(
+                        "Using the description of {} for {}",
+                        hrir,
+                        file.path.display()
+                    );
+                    Some(metadata)
+                });
+                WavFileData {
+                    metadata,
+                    sample_rate: file.probe.sample_rate,
+                    checksum: file.probe.checksum,
+                    channels: file.probe.channels,
+                    bit_depth: file.probe.bit_depth,
+                    duration_ms: file.probe.duration_ms,
+                    size_bytes: file.size_bytes,
+                    extra_directory: file.extra_directory,
+                    modified: file.modified,
+                    relative_path: file.relative_path,
+                    path: file.path,
                 }
             })
             .collect();
-        // Copy collected metadta back to wave data
-        self.scanning_wave_data
-            .iter_mut()
-            .zip(metarecords.iter())
-            .for_each(|d| {
-                d.0.sample_rate = d.1.samplerate;
-                d.0.checksum = d.1.checksum;
-            });
 
         // Sort entries: HeSuVi entries first, then alphabetically by path
-        self.scanning_wave_data.sort_by(|a, b| {
+        wavdata.sort_by(|a, b| {
             let a_is_hesuvi = a.path.to_string_lossy().contains("HeSuVi/");
             let b_is_hesuvi = b.path.to_string_lossy().contains("HeSuVi/");
 
@@ -111,86 +428,626 @@ impl FileManager {
             }
         });
 
-        // Populate metadata from descriptions
-        for wave in &mut self.scanning_wave_data {
-            let stem = wave.path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
-            wave.metadata = self.descriptions.get_rc(stem);
-        }
-
         //Construct WaveFileIndex and return it
-        let wavdata = mem::take(&mut self.scanning_wave_data);
         let mut wav_index = WavFileIndex::from_vec(wavdata);
         wav_index.shrink_to_fit();
-        Ok(wav_index)
+        wav_index
+    }
+
+    /// Forgets all cached scan results, so the next rescan reads every file.
+    pub fn clear_cache(&mut self) -> Result<()> {
+        match &mut self.cache {
+            Some(cache) => cache.clear(),
+            None => ScanCache::load(&self.settings.borrow())?.clear(),
+        }
+    }
+
+    /// Turns a cached scan result back into a probe.
+    fn probe_from_cache(entry: &CacheEntry) -> WavProbe {
+        WavProbe {
+            sample_rate: WaveSampleRate::from_hz(entry.sample_rate),
+            checksum: entry.checksum,
+            channels: entry.channels,
+            bit_depth: entry.bit_depth,
+            duration_ms: entry.duration_ms,
+        }
+    }
+
+    /// Checks whether the path has a `.wav` extension (case-insensitive).
+    pub fn is_wav_file(path: &Path) -> bool {
+        Self::has_extension(path, "wav")
+    }
+
+    /// Checks whether the path has a `.flac` extension (case-insensitive).
+    pub fn is_flac_file(path: &Path) -> bool {
+        Self::has_extension(path, "flac")
+    }
+
+    /// Checks whether the scan picks up the file: WAV, and FLAC when built
+    /// with the `flac` feature.
+    pub fn is_ir_file(path: &Path) -> bool {
+        Self::is_wav_file(path) || (cfg!(feature = "flac") && Self::is_flac_file(path))
+    }
+
+    /// Checks the file extension, ignoring case.
+    fn has_extension(path: &Path, extension: &str) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+    }
+
+    /// Computes the checksum of a single WAV file, the same way the scan does.
+    /// Returns None if the file can't be read or is not a valid WAV.
+    pub fn file_checksum(path: &Path) -> Option<u128> {
+        Self::file_checksum_and_channels(path).map(|(checksum, _)| checksum)
+    }
+
+    /// Checksum and channel count of a single IR file, None if it is
+    /// damaged.
+    pub fn file_checksum_and_channels(path: &Path) -> Option<(u128, u16)> {
+        let probe = Self::probe_file(path);
+        match probe.sample_rate {
+            WaveSampleRate::Damaged => None,
+            _ => Some((probe.checksum, probe.channels)),
+        }
+    }
+
+    /// Reads an IR file of any supported format.
+    fn probe_file(path: &Path) -> WavProbe {
+        #[cfg(feature = "flac")]
+        if Self::is_flac_file(path) {
+            return Self::probe_flac_file(path);
+        }
+        Self::probe_wav_file(path)
+    }
+
+    /// Reads the STREAMINFO block of a FLAC file and computes its checksum.
+    #[cfg(feature = "flac")]
+    fn probe_flac_file(path: &Path) -> WavProbe {
+        let damaged = WavProbe::damaged();
+        let Ok(data) = read_file_start(path, HEADER_READ_SIZE) else {
+            return damaged;
+        };
+        let Some(info) = Self::parse_flac_streaminfo(&data) else {
+            return damaged;
+        };
+        let Ok(checksum) = stream_checksum(path) else {
+            return damaged;
+        };
+        WavProbe { checksum, ..info }
     }
 
-    fn detect_sample_rate_and_checksum(path: &Path) -> (WaveSampleRate, u128) {
-        // Read entire file
-        let data = match std::fs::read(path) {
+    /// Extracts the format from the STREAMINFO block, which the FLAC format
+    /// requires to be the first metadata block. The checksum is left at 0.
+    #[cfg(feature = "flac")]
+    fn parse_flac_streaminfo(data: &[u8]) -> Option<WavProbe> {
+        // "fLaC", then a block header: type in the low 7 bits, 24-bit size
+        if data.get(0..4)? != b"fLaC" || data.get(4)? & 0x7f != 0 {
+            return None;
+        }
+        let info = data.get(8..8 + 34)?;
+        // 20 bits sample rate, 3 bits channels - 1, 5 bits bits per sample - 1,
+        // 36 bits total samples
+        let packed = u64::from_be_bytes(info[10..18].try_into().ok()?);
+        let sample_rate = (packed >> 44) as u32;
+        let channels = ((packed >> 41) & 0x7) as u16 + 1;
+        let bit_depth = ((packed >> 36) & 0x1f) as u16 + 1;
+        let total_samples = packed & 0xf_ffff_ffff;
+        let duration_ms = duration_ms(total_samples, sample_rate);
+        Some(WavProbe {
+            sample_rate: WaveSampleRate::from_hz(sample_rate),
+            checksum: 0,
+            channels,
+            bit_depth,
+            duration_ms,
+        })
+    }
+
+    /// Reads a WAV file and extracts its format info and checksum.
+    /// Only the start of the file is kept in memory.
+    fn probe_wav_file(path: &Path) -> WavProbe {
+        let damaged = WavProbe::damaged();
+
+        // Read the start of the file, enough for the format chunks
+        let data = match read_file_start(path, HEADER_READ_SIZE) {
             Ok(data) => data,
-            Err(_) => return (WaveSampleRate::Damaged, 0),
+            Err(_) => return damaged,
         };
 
         // Check length
-        if data.len() < 28 {
-            return (WaveSampleRate::Damaged, 0);
+        if data.len() < 12 {
+            return damaged;
         }
 
         // Verify WAV header
         if &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
-            return (WaveSampleRate::Damaged, 0);
+            return damaged;
         }
 
-        // Extract sample rate
-        let sample_rate = u32::from_le_bytes([data[24], data[25], data[26], data[27]]);
-        let wave_sample_rate = match sample_rate {
-            44100 => WaveSampleRate::F44100,
-            48000 => WaveSampleRate::F48000,
-            96000 => WaveSampleRate::F96000,
-            _ => WaveSampleRate::Unknown,
+        // Canonical files start with the fmt chunk right after the header,
+        // others have LIST, bext, JUNK etc. chunks before it
+        let fmt = Self::find_chunk(&data, b"fmt ")
+            .and_then(|(offset, size)| data.get(offset..(offset + size as usize).min(data.len())))
+            .and_then(FmtChunk::parse);
+        let Some(fmt) = fmt else {
+            return damaged;
+        };
+
+        let sample_rate = fmt.sample_rate;
+        let wave_sample_rate = WaveSampleRate::from_hz(sample_rate);
+        let channels = fmt.channels;
+        let bit_depth = fmt.bits_per_sample;
+        let frame_size = if fmt.block_align > 0 {
+            u64::from(fmt.block_align)
+        } else {
+            u64::from(channels) * u64::from(bit_depth).div_ceil(8)
+        };
+        let duration_ms = match Self::find_chunk(&data, b"data") {
+            Some((_, size)) if frame_size > 0 => {
+                duration_ms(u64::from(size) / frame_size, sample_rate)
+            }
+            _ => 0,
         };
 
-        // Compute xxh3 hash
-        let hash = xxh3_128(&data);
+        // Compute xxh3 hash over the whole file
+        let hash = match stream_checksum(path) {
+            Ok(hash) => hash,
+            Err(_) => return damaged,
+        };
 
-        (wave_sample_rate, hash)
+        WavProbe {
+            sample_rate: wave_sample_rate,
+            checksum: hash,
+            channels,
+            bit_depth,
+            duration_ms,
+        }
     }
 
-    fn scan_directory(&mut self, path: &Path) -> Result<()> {
+    /// Walks the RIFF chunks and returns the offset of the body and the
+    /// declared size of the first chunk with the given four-CC.
+    fn find_chunk(data: &[u8], id: &[u8; 4]) -> Option<(usize, u32)> {
+        let mut pos = 12;
+        while pos + 8 <= data.len() {
+            let size =
+                u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]]);
+            if &data[pos..pos + 4] == id {
+                return Some((pos + 8, size));
+            }
+            // Chunks are padded to an even size
+            pos += 8 + size as usize + (size as usize & 1);
+        }
+        None
+    }
+
+    /// Checks whether a scan error was caused by the directory being missing
+    /// or unreadable (e.g. an unmounted drive), rather than by its content.
+    pub fn is_access_error(error: &anyhow::Error) -> bool {
+        error.chain().any(|cause| {
+            cause.downcast_ref::<std::io::Error>().is_some_and(|e| {
+                matches!(e.kind(), ErrorKind::NotFound | ErrorKind::PermissionDenied)
+            })
+        })
+    }
+}
+
+/// Scan of one directory, detached from the settings so it can run on
+/// another thread.
+struct ScanJob {
+    /// Directories to scan, the WAV directory first. Empty if none is
+    /// configured
+    dirs: Vec<PathBuf>,
+    /// Levels of subdirectories to enter, None = unlimited
+    depth: Option<u32>,
+    follow_symlinks: bool,
+    cache: Option<ScanCache>,
+}
+
+impl ScanJob {
+    /// Lists the WAV files and reads their format and checksum. Files
+    /// unchanged since the last scan are taken from the cache.
+    /// Once `cancelled` is set, the remaining files are skipped and an
+    /// error is returned without updating the cache.
+    fn run<F>(mut self, cancelled: &AtomicBool, on_progress: F) -> Result<ScanOutput>
+    where
+        F: Fn(ScanProgress) + Sync,
+    {
+        let dirs = std::mem::take(&mut self.dirs);
+        let Some(main_dir) = dirs.first() else {
+            // No directory configured, nothing to scan
+            return Ok(ScanOutput {
+                files: Vec::new(),
+                cache: self.cache,
+            });
+        };
+        // Paths are relative to the directory a file was found in. The
+        // visited set is shared, so nested directories are listed once.
+        let mut listed = Vec::new();
+        let mut roots = Vec::new();
+        let mut visited = HashSet::new();
+        for (root, dir) in dirs.iter().enumerate() {
+            let mut walk = DirWalk {
+                base_dir: dir,
+                follow_symlinks: self.follow_symlinks,
+                cancelled,
+                visited,
+                listed: Vec::new(),
+            };
+            let result = walk.walk(dir, self.depth);
+            visited = walk.visited;
+            match result {
+                Ok(()) => {}
+                // Only the WAV directory is required to be there
+                Err(e) if root == 0 || cancelled.load(Ordering::Relaxed) => return Err(e),
+                Err(e) => {
+                    warn!("Skipping IR directory {}: {:#}", dir.display(), e);
+                    continue;
+                }
+            }
+            roots.extend(std::iter::repeat_n(root, walk.listed.len()));
+            listed.extend(walk.listed);
+        }
+
+        // Multithreaded scan of files to collect metadata.
+        let total = listed.len();
+        let done = AtomicUsize::new(0);
+        let cache = self.cache.as_ref();
+        let records: Vec<(WavProbe, Option<FileStamp>)> = listed
+            .par_iter()
+            .map(|(path, _, _)| {
+                if cancelled.load(Ordering::Relaxed) {
+                    return (WavProbe::damaged(), None);
+                }
+                let stamp = fs::metadata(path).ok().and_then(|m| FileStamp::of(&m));
+                let cached = stamp
+                    .as_ref()
+                    .and_then(|stamp| cache?.get(path, stamp))
+                    .map(FileManager::probe_from_cache);
+                let probe = cached.unwrap_or_else(|| FileManager::probe_file(path));
+                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                on_progress(ScanProgress { done, total });
+                (probe, stamp)
+            })
+            .collect();
+        if cancelled.load(Ordering::Relaxed) {
+            bail!("Scan of {} was cancelled", main_dir.display());
+        }
+        self.update_cache(&dirs, &listed, &records);
+
+        // A file found in several directories is listed from the first one,
+        // copies inside one directory are all kept
+        let mut first_root = HashMap::new();
+        let files = listed
+            .into_iter()
+            .zip(records)
+            .zip(roots)
+            .filter(|((_, (probe, _)), root)| {
+                probe.checksum == 0 || *first_root.entry(probe.checksum).or_insert(*root) == *root
+            })
+            .map(
+                |(((path, relative_path, modified), (probe, stamp)), root)| ScannedFile {
+                    path,
+                    relative_path,
+                    modified,
+                    size_bytes: stamp.map_or(0, |stamp| stamp.size()),
+                    extra_directory: root != 0,
+                    probe,
+                },
+            )
+            .collect();
+        Ok(ScanOutput {
+            files,
+            cache: self.cache,
+        })
+    }
+
+    /// Stores the results of a scan of `dirs` in the cache and saves it.
+    /// Damaged files are not cached, they are cheap to read again.
+    fn update_cache(
+        &mut self,
+        dirs: &[PathBuf],
+        listed: &[ListedFile],
+        records: &[(WavProbe, Option<FileStamp>)],
+    ) {
+        let Some(cache) = &mut self.cache else {
+            return;
+        };
+        let entries = listed
+            .iter()
+            .zip(records)
+            .filter(|(_, (probe, _))| probe.sample_rate != WaveSampleRate::Damaged)
+            .filter_map(|((path, _, _), (probe, stamp))| {
+                Some(CacheEntry {
+                    path: path.clone(),
+                    stamp: (*stamp)?,
+                    sample_rate: probe.sample_rate.hz().unwrap_or(0),
+                    checksum: probe.checksum,
+                    channels: probe.channels,
+                    bit_depth: probe.bit_depth,
+                    duration_ms: probe.duration_ms,
+                })
+            })
+            .collect();
+        cache.replace_dirs(dirs, entries);
+        if let Err(e) = cache.save() {
+            warn!("Failed to save scan cache: {:#}", e);
+        }
+    }
+}
+
+/// State of the directory walk of one scan.
+struct DirWalk<'a> {
+    base_dir: &'a Path,
+    follow_symlinks: bool,
+    cancelled: &'a AtomicBool,
+    /// Canonical paths of the directories entered so far, so symlink
+    /// loops are entered only once
+    visited: HashSet<PathBuf>,
+    listed: Vec<ListedFile>,
+}
+
+impl DirWalk<'_> {
+    /// Recursively collects the WAV files under `path`, with their paths
+    /// relative to `base_dir`. Subdirectories are skipped once `depth`
+    /// reaches zero. Stops with an error once `cancelled` is set.
+    fn walk(&mut self, path: &Path, depth: Option<u32>) -> Result<()> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            bail!("Scan of {} was cancelled", self.base_dir.display());
+        }
+        if !self.visited.insert(fs::canonicalize(path)?) {
+            return Ok(());
+        }
         for entry in fs::read_dir(path)? {
             let entry = entry?;
             let path = entry.path();
+            if entry.file_type()?.is_symlink() && !self.follow_symlinks {
+                continue;
+            }
             if path.is_dir() {
-                self.scan_directory(&path)?;
+                match depth {
+                    Some(0) => continue,
+                    _ => self.walk(&path, depth.map(|d| d - 1))?,
+                }
             } else {
-                // Only store files that end with .wav (case-insensitive)
-                let ext = match path.extension() {
-                    Some(ext) => ext,
-                    None => continue,
-                };
-                let ext_str = match ext.to_str() {
-                    Some(s) => s,
-                    None => continue,
-                };
-                if !ext_str.eq_ignore_ascii_case("wav") {
+                // Only store files that end with .wav or .flac (case-insensitive)
+                if !FileManager::is_ir_file(&path) {
                     continue;
                 }
-                // Compute relative path relative to current_wavefile_dir
-                let relative_path = match &self.current_wavefile_dir {
-                    Some(base_dir) => path
-                        .strip_prefix(base_dir)
-                        .map(|p| p.to_path_buf())
-                        .unwrap_or_else(|_| path.clone()),
-                    None => path.clone(),
-                };
-                // Store absolute path with detected sample rate
-                self.scanning_wave_data.push(WavFileData {
-                    path,
-                    relative_path,
-                    ..Default::default()
-                });
+                let relative_path = path
+                    .strip_prefix(self.base_dir)
+                    .map(|p| p.to_path_buf())
+                    .unwrap_or_else(|_| path.clone());
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                self.listed.push((path, relative_path, modified));
             }
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::descriptions::Descriptions;
+
+    #[test]
+    fn test_rescan_inaccessible_directory() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let missing = temp.path().join("unmounted_share");
+
+        let mut settings = AppSettings::default();
+        settings.set_wav_directory(Some(missing));
+        let settings = Rc::new(RefCell::new(settings));
+        let descriptions = Descriptions::new().expect("Failed to load descriptions");
+        let mut file_manager = FileManager::new(settings, descriptions);
+
+        // read_dir fails on a missing directory, which must be reported as an access error
+        let Err(err) = file_manager.rescan_configured_directory() else {
+            panic!("Scanning a missing directory should fail");
+        };
+        assert!(FileManager::is_access_error(&err));
+
+        // Errors unrelated to file access are not reported as such
+        assert!(!FileManager::is_access_error(&anyhow::anyhow!(
+            "Other error"
+        )));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_survives_symlink_loop() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let ir_dir = temp.path().join("irs");
+        fs::create_dir(&ir_dir).unwrap();
+        fs::write(ir_dir.join("a.wav"), synthetic_wav(&[], 48000)).unwrap();
+        // A link back to the directory itself
+        std::os::unix::fs::symlink(&ir_dir, ir_dir.join("loop")).unwrap();
+
+        for follow_symlinks in [false, true] {
+            let mut settings = AppSettings::default();
+            settings.config_dir = Some(temp.path().join("config"));
+            settings.follow_symlinks = follow_symlinks;
+            settings.set_wav_directory(Some(ir_dir.clone()));
+            let settings = Rc::new(RefCell::new(settings));
+            let descriptions = Descriptions::new().expect("Failed to load descriptions");
+            let mut file_manager = FileManager::new(settings, descriptions);
+
+            let index = file_manager
+                .rescan_configured_directory()
+                .expect("Scan should succeed");
+            assert_eq!(index.len(), 1, "follow_symlinks = {follow_symlinks}");
+        }
+    }
+
+    #[test]
+    fn test_scan_merges_several_directories() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let main_dir = temp.path().join("hesuvi");
+        let extra_dir = temp.path().join("mine");
+        fs::create_dir(&main_dir).unwrap();
+        fs::create_dir(&extra_dir).unwrap();
+        fs::write(main_dir.join("a.wav"), synthetic_wav(&[], 48000)).unwrap();
+        fs::write(extra_dir.join("b.wav"), synthetic_wav(&[], 44100)).unwrap();
+        // The same file in both directories
+        fs::write(extra_dir.join("a_copy.wav"), synthetic_wav(&[], 48000)).unwrap();
+
+        let mut settings = AppSettings::default();
+        settings.config_dir = Some(temp.path().join("config"));
+        settings.set_wav_directory(Some(main_dir.clone()));
+        settings.extra_wav_directories = vec![extra_dir.clone(), temp.path().join("missing")];
+        let settings = Rc::new(RefCell::new(settings));
+        let descriptions = Descriptions::new().expect("Failed to load descriptions");
+        let mut file_manager = FileManager::new(settings, descriptions);
+
+        let index = file_manager
+            .rescan_configured_directory()
+            .expect("A missing extra directory should not fail the scan");
+        let mut paths: Vec<_> = index
+            .iter()
+            .map(|w| w.library_path().to_path_buf())
+            .collect();
+        paths.sort();
+        assert_eq!(paths, [extra_dir.join("b.wav"), PathBuf::from("a.wav")]);
+    }
+
+    #[cfg(feature = "flac")]
+    #[test]
+    fn test_parse_flac_streaminfo() {
+        let mut data = b"fLaC".to_vec();
+        // Last metadata block, type STREAMINFO, 34 bytes
+        data.extend_from_slice(&[0x80, 0, 0, 34]);
+        let mut info = [0u8; 34];
+        // 48000 Hz, 8 channels, 24 bits, 96000 samples
+        let packed: u64 = (48000 << 44) | (7 << 41) | (23 << 36) | 96000;
+        info[10..18].copy_from_slice(&packed.to_be_bytes());
+        data.extend_from_slice(&info);
+
+        let probe = FileManager::parse_flac_streaminfo(&data).expect("Valid STREAMINFO");
+        assert_eq!(probe.sample_rate, WaveSampleRate::F48000);
+        assert_eq!(probe.channels, 8);
+        assert_eq!(probe.bit_depth, 24);
+        assert_eq!(probe.duration_ms, 2000);
+
+        assert!(FileManager::parse_flac_streaminfo(b"RIFF....WAVE").is_none());
+    }
+
+    #[test]
+    fn test_short_files_have_a_duration() {
+        assert_eq!(duration_ms(48000, 48000), 1000);
+        assert_eq!(duration_ms(24, 48000), 1);
+        assert_eq!(duration_ms(0, 48000), 0);
+        assert_eq!(duration_ms(24, 0), 0);
+    }
+
+    #[test]
+    fn test_stream_checksum_matches_xxh3() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = temp.path().join("large.bin");
+        // Several hash buffers long, with a partial last buffer
+        let data: Vec<u8> = (0..HASH_BUFFER_SIZE * 3 + 1234)
+            .map(|i| (i * 31 % 251) as u8)
+            .collect();
+        fs::write(&path, &data).unwrap();
+
+        let streamed = stream_checksum(&path).expect("Hashing should succeed");
+        assert_eq!(streamed, xxhash_rust::xxh3::xxh3_128(&data));
+    }
+
+    /// Builds a 16-bit PCM WAV file with the given chunks before `fmt `.
+    fn synthetic_wav(leading_chunks: &[(&[u8; 4], &[u8])], sample_rate: u32) -> Vec<u8> {
+        let channels: u16 = 2;
+        let block_align = channels * 2;
+        let mut body = b"WAVE".to_vec();
+        for (id, content) in leading_chunks {
+            body.extend_from_slice(*id);
+            body.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            body.extend_from_slice(content);
+            if content.len() % 2 == 1 {
+                body.push(0);
+            }
+        }
+        body.extend_from_slice(b"fmt ");
+        body.extend_from_slice(&16u32.to_le_bytes());
+        body.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        body.extend_from_slice(&channels.to_le_bytes());
+        body.extend_from_slice(&sample_rate.to_le_bytes());
+        body.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        body.extend_from_slice(&block_align.to_le_bytes());
+        body.extend_from_slice(&16u16.to_le_bytes());
+        body.extend_from_slice(b"data");
+        body.extend_from_slice(&8u32.to_le_bytes());
+        body.extend_from_slice(&[0; 8]);
+
+        let mut file = b"RIFF".to_vec();
+        file.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        file.extend_from_slice(&body);
+        file
+    }
+
+    #[test]
+    fn test_probe_finds_fmt_after_other_chunks() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+
+        let canonical = temp.path().join("canonical.wav");
+        fs::write(&canonical, synthetic_wav(&[], 48000)).unwrap();
+        let probe = FileManager::probe_wav_file(&canonical);
+        assert_eq!(probe.sample_rate, WaveSampleRate::F48000);
+        assert_eq!(probe.channels, 2);
+        assert_eq!(probe.bit_depth, 16);
+
+        // LIST and an odd-sized JUNK chunk push fmt away from byte 12
+        let list: &[u8] = b"INFOISFT\x05\0\0\0test\0";
+        let junk: &[u8] = &[0; 27];
+        let with_list = temp.path().join("with_list.wav");
+        fs::write(
+            &with_list,
+            synthetic_wav(&[(b"LIST", list), (b"JUNK", junk)], 44100),
+        )
+        .unwrap();
+        let probe = FileManager::probe_wav_file(&with_list);
+        assert_eq!(probe.sample_rate, WaveSampleRate::F44100);
+        assert_eq!(probe.channels, 2);
+        assert_ne!(probe.checksum, 0);
+
+        // Extensible format: 24 valid bits in 32-bit containers
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&0xfffeu16.to_le_bytes());
+        fmt.extend_from_slice(&14u16.to_le_bytes());
+        fmt.extend_from_slice(&48000u32.to_le_bytes());
+        fmt.extend_from_slice(&(48000u32 * 14 * 4).to_le_bytes());
+        fmt.extend_from_slice(&(14u16 * 4).to_le_bytes());
+        fmt.extend_from_slice(&32u16.to_le_bytes());
+        fmt.extend_from_slice(&22u16.to_le_bytes()); // cbSize
+        fmt.extend_from_slice(&24u16.to_le_bytes()); // wValidBitsPerSample
+        fmt.extend_from_slice(&0u32.to_le_bytes()); // dwChannelMask
+        fmt.extend_from_slice(&1u16.to_le_bytes()); // SubFormat: PCM
+        fmt.extend_from_slice(b"\0\0\0\0\x10\0\x80\0\0\xaa\0\x38\x9b\x71");
+        let mut body = b"WAVEfmt ".to_vec();
+        body.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+        body.extend_from_slice(&fmt);
+        body.extend_from_slice(b"data");
+        body.extend_from_slice(&(48000u32 * 14 * 4).to_le_bytes());
+        let mut data = b"RIFF".to_vec();
+        data.extend_from_slice(&(body.len() as u32 + 48000 * 14 * 4).to_le_bytes());
+        data.extend_from_slice(&body);
+        data.resize(data.len() + 48000 * 14 * 4, 0);
+        let extensible = temp.path().join("extensible.wav");
+        fs::write(&extensible, data).unwrap();
+        let probe = FileManager::probe_wav_file(&extensible);
+        assert_eq!(probe.sample_rate, WaveSampleRate::F48000);
+        assert_eq!(probe.channels, 14);
+        assert_eq!(probe.bit_depth, 24);
+        assert_eq!(probe.duration_ms, 1000);
+
+        // No fmt chunk at all
+        let broken = temp.path().join("broken.wav");
+        let mut data = synthetic_wav(&[], 48000);
+        data[12..16].copy_from_slice(b"junk");
+        data.truncate(36);
+        fs::write(&broken, data).unwrap();
+        assert_eq!(
+            FileManager::probe_wav_file(&broken).sample_rate,
+            WaveSampleRate::Damaged
+        );
+    }
+}
@@ -0,0 +1,23 @@
+//! Core logic of Irate Goose, usable without the GUI.
+//!
+//! - `file_manager` scans a directory for IR (WAV) files and computes their checksums.
+//! - `config_manager` writes and removes the PipeWire virtual surround config.
+//! - `descriptions` provides metadata for well-known HRTF files.
+//! - `export` writes file info to CSV.
+//! - `dir_watcher` notices changes in the IR directory.
+//! - `preview` plays an IR file without creating a device.
+//! - `settings` and `notes` load and save the application's own files.
+//!
+//! The `irate_goose` binary builds its GUI on top of these modules.
+
+pub mod config_manager;
+pub mod descriptions;
+pub mod diagnostics;
+pub mod dir_watcher;
+pub mod export;
+pub mod file_manager;
+pub mod notes;
+pub mod preview;
+mod scan_cache;
+pub mod settings;
+pub mod wav_file_index;
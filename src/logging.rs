@@ -1,33 +1,132 @@
-use anyhow::Result;
+use anyhow::{Context, Result, anyhow};
+use log4rs::Handle;
 use log4rs::append::Append;
+use log4rs::append::console::Target;
+use log4rs::append::rolling_file::RollingFileAppender;
+use log4rs::append::rolling_file::policy::compound::CompoundPolicy;
+use log4rs::append::rolling_file::policy::compound::roll::fixed_window::FixedWindowRoller;
+use log4rs::append::rolling_file::policy::compound::trigger::size::SizeTrigger;
 use log4rs::config::{Appender, Config, Root};
+use log4rs::encode::Encode;
 use log4rs::encode::pattern::PatternEncoder;
+use log4rs::encode::writer::simple::SimpleWriter;
 use log4rs::filter::threshold::ThresholdFilter;
-use std::sync::{Arc, Mutex};
+use log4rs::filter::{Filter, Response};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::settings::{AppSettings, DEFAULT_LOG_BUFFER_LINES};
+
+/// The most recent log lines with their levels, up to a capacity. The
+/// oldest line is dropped when a new one does not fit.
+#[derive(Debug)]
+pub struct LogBuffer {
+    lines: VecDeque<(log::Level, String)>,
+    capacity: usize,
+}
+
+/// Log buffer shared by the memory appender and the UI.
+pub type SharedLogBuffer = Arc<Mutex<LogBuffer>>;
+
+impl LogBuffer {
+    /// Creates an empty buffer that keeps up to `capacity` lines.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Creates an empty buffer of the default capacity, shared.
+    pub fn shared() -> SharedLogBuffer {
+        Arc::new(Mutex::new(Self::new(DEFAULT_LOG_BUFFER_LINES)))
+    }
+
+    /// Changes the capacity, dropping the oldest lines that no longer fit.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.trim();
+    }
+
+    /// Adds a line, dropping the oldest one if the buffer is full.
+    pub fn push(&mut self, level: log::Level, line: String) {
+        self.lines.push_back((level, line));
+        self.trim();
+    }
+
+    /// Drops the oldest lines beyond the capacity.
+    fn trim(&mut self) {
+        let excess = self.lines.len().saturating_sub(self.capacity);
+        self.lines.drain(..excess);
+    }
+
+    /// Copies the lines, oldest first.
+    pub fn to_vec(&self) -> Vec<String> {
+        self.lines.iter().map(|(_, line)| line.clone()).collect()
+    }
+
+    /// Copies the lines that pass `filter` with their levels, oldest first.
+    pub fn entries(&self, filter: log::LevelFilter) -> Vec<(log::Level, String)> {
+        self.lines
+            .iter()
+            .filter(|(level, _)| *level <= filter)
+            .cloned()
+            .collect()
+    }
+
+    /// Drops all lines.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Checks whether the buffer holds no lines.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// The most recent line.
+    pub fn last(&self) -> Option<&String> {
+        self.lines.back().map(|(_, line)| line)
+    }
+}
+
+/// Layout of a log line in the Log tab and the log file
+const LINE_PATTERN: &str = "{d(%Y-%m-%d %H:%M:%S)} {l} - {m}";
 
 /// A custom log4rs appender that stores log lines in a shared buffer.
 #[derive(Debug)]
 pub struct MemoryAppender {
-    buffer: Arc<Mutex<Vec<String>>>,
+    buffer: SharedLogBuffer,
+    encoder: PatternEncoder,
 }
 
 impl MemoryAppender {
-    pub fn new(buffer: Arc<Mutex<Vec<String>>>) -> Self {
-        Self { buffer }
+    pub fn new(buffer: SharedLogBuffer) -> Self {
+        Self {
+            buffer,
+            encoder: PatternEncoder::new(LINE_PATTERN),
+        }
     }
 }
 
+/// Checks whether a log record comes from our own crate.
+fn is_own_record(record: &log::Record) -> bool {
+    record
+        .module_path()
+        .map(|p| p.starts_with("irate_goose"))
+        .unwrap_or(false)
+}
+
 impl Append for MemoryAppender {
     fn append(&self, record: &log::Record) -> Result<()> {
         // Only store logs from our own crate
-        if record
-            .module_path()
-            .map(|p| p.starts_with("irate_goose"))
-            .unwrap_or(false)
-        {
-            let formatted = format!("{}", record.args());
+        if is_own_record(record) {
+            let mut writer = SimpleWriter(Vec::new());
+            self.encoder.encode(&mut writer, record)?;
+            let formatted = String::from_utf8_lossy(&writer.0).into_owned();
             if let Ok(mut guard) = self.buffer.lock() {
-                guard.push(formatted);
+                guard.push(record.level(), formatted);
             }
         }
         Ok(())
@@ -36,17 +135,97 @@ impl Append for MemoryAppender {
     fn flush(&self) {}
 }
 
+/// Passes only the records of our own crate, like the memory appender.
+#[derive(Debug)]
+struct OwnCrateFilter;
+
+impl Filter for OwnCrateFilter {
+    fn filter(&self, record: &log::Record) -> Response {
+        if is_own_record(record) {
+            Response::Neutral
+        } else {
+            Response::Reject
+        }
+    }
+}
+
+/// Size at which the log file rolls over
+const LOG_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Rolled-over log files kept next to the current one
+const LOG_FILE_COUNT: u32 = 3;
+
+/// What the logging configuration is rebuilt from when the log file is
+/// turned on or off.
+struct LoggingState {
+    handle: Handle,
+    buffer: SharedLogBuffer,
+    use_console: bool,
+}
+
+/// Set by `init_logging`
+static LOGGING: OnceLock<LoggingState> = OnceLock::new();
+
 /// Initializes log4rs with a console appender and a memory appender.
-/// The memory appender writes into the provided buffer.
-pub fn init_logging(buffer: Arc<Mutex<Vec<String>>>) -> Result<()> {
+/// The memory appender writes into the provided buffer. Without `use_console`
+/// only the memory appender is used, so log lines don't break a terminal UI.
+/// A log file is added later with `set_log_file`, once the settings are known.
+pub fn init_logging(buffer: SharedLogBuffer, use_console: bool) -> Result<()> {
+    let config = build_config(&buffer, use_console, None)?;
+    let handle = log4rs::init_config(config)?;
+    let _ = LOGGING.set(LoggingState {
+        handle,
+        buffer,
+        use_console,
+    });
+    Ok(())
+}
+
+/// Starts writing the log to `path`, rolling it over at `LOG_FILE_SIZE`,
+/// or stops writing it with None.
+pub fn set_log_file(path: Option<&Path>) -> Result<()> {
+    let state = LOGGING
+        .get()
+        .ok_or_else(|| anyhow!("Logging is not initialized"))?;
+    let config = build_config(&state.buffer, state.use_console, path)?;
+    state.handle.set_config(config);
+    Ok(())
+}
+
+/// Turns the log file on or off to match the `log_to_file` setting.
+/// Failures are logged, logging to the other appenders goes on.
+pub fn apply_log_file_setting(settings: &AppSettings) {
+    let path = match settings.log_to_file.then(|| settings.log_file_path()) {
+        None => None,
+        Some(Ok(path)) => Some(path),
+        Some(Err(e)) => {
+            log::error!("Can't write the log to a file: {e:#}");
+            None
+        }
+    };
+    if let Err(e) = set_log_file(path.as_deref()) {
+        log::error!("Can't write the log to a file: {e:#}");
+    }
+}
+
+/// Builds the logging configuration with the memory appender, the
+/// console appender if `use_console`, and a rolling file appender if
+/// `log_file` is set.
+fn build_config(
+    buffer: &SharedLogBuffer,
+    use_console: bool,
+    log_file: Option<&Path>,
+) -> Result<Config> {
     // Determine console log level from environment variable, default to Warn
     let console_level = std::env::var("RUST_LOG")
         .ok()
         .and_then(|s| s.parse::<log::LevelFilter>().ok())
         .unwrap_or(log::LevelFilter::Warn);
 
-    // Console appender with default pattern
+    // Console appender with default pattern. Writes to stderr, so the
+    // output of command line commands can be piped.
     let console = log4rs::append::console::ConsoleAppender::builder()
+        .target(Target::Stderr)
         .encoder(Box::new(PatternEncoder::new("{d} {l} {t} - {m}{n}")))
         .build();
 
@@ -55,19 +234,34 @@ pub fn init_logging(buffer: Arc<Mutex<Vec<String>>>) -> Result<()> {
         .build("console", Box::new(console));
 
     // Memory appender using the shared buffer
-    let memory = MemoryAppender::new(buffer);
+    let memory = MemoryAppender::new(Arc::clone(buffer));
     let memory_appender = Appender::builder().build("memory", Box::new(memory));
 
-    let config = Config::builder()
-        .appender(console_appender)
-        .appender(memory_appender)
-        .build(
-            Root::builder()
-                .appender("console")
-                .appender("memory")
-                .build(log::LevelFilter::Info),
-        )?;
-
-    log4rs::init_config(config)?;
-    Ok(())
+    let mut builder = Config::builder().appender(memory_appender);
+    let mut root = Root::builder().appender("memory");
+    if use_console {
+        builder = builder.appender(console_appender);
+        root = root.appender("console");
+    }
+    if let Some(path) = log_file {
+        // Rolled files are named irate_goose.1.log, irate_goose.2.log, ...
+        let pattern = path.with_extension("{}.log");
+        let roller = FixedWindowRoller::builder()
+            .build(&pattern.to_string_lossy(), LOG_FILE_COUNT)
+            .map_err(|e| anyhow!("Invalid log file name: {e}"))?;
+        let policy =
+            CompoundPolicy::new(Box::new(SizeTrigger::new(LOG_FILE_SIZE)), Box::new(roller));
+        let file = RollingFileAppender::builder()
+            .encoder(Box::new(PatternEncoder::new(&format!(
+                "{LINE_PATTERN}{{n}}"
+            ))))
+            .build(path, Box::new(policy))
+            .with_context(|| format!("Failed to open log file {}", path.display()))?;
+        let file_appender = Appender::builder()
+            .filter(Box::new(OwnCrateFilter))
+            .build("file", Box::new(file));
+        builder = builder.appender(file_appender);
+        root = root.appender("file");
+    }
+    Ok(builder.build(root.build(log::LevelFilter::Info))?)
 }
@@ -1,74 +1,87 @@
+mod app_context;
 mod app_gui;
-mod config_manager;
-mod descriptions;
-mod file_manager;
+mod cli;
+mod commands;
 mod logging;
-mod settings;
-mod wav_file_index;
+mod tui;
+
+// Core modules come from the library, under the same paths
+use irate_goose::{
+    config_manager, descriptions, diagnostics, dir_watcher, export, file_manager, notes, preview,
+    settings, wav_file_index,
+};
 
 use log::error;
-use std::cell::RefCell;
 use std::fs;
 use std::process::Command;
-use std::rc::Rc;
 use walkdir::WalkDir;
 
-use crate::descriptions::Descriptions;
-use crate::settings::AppSettings;
+use crate::app_context::AppContext;
+use crate::cli::CliArgs;
 use app_gui::AppGUI;
-use config_manager::ConfigManager;
+use clap::Parser;
 use eframe::{egui::ViewportBuilder, icon_data::from_png_bytes};
-use file_manager::FileManager;
 
 fn main() {
+    let args = CliArgs::parse();
+
     // Create shared log buffer
-    let log_buffer = std::sync::Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+    let log_buffer = logging::LogBuffer::shared();
     let buffer_for_logging = std::sync::Arc::clone(&log_buffer);
 
     // Initialize log4rs with console and memory appenders
-    if let Err(e) = logging::init_logging(buffer_for_logging) {
+    if let Err(e) = logging::init_logging(buffer_for_logging, !args.tui) {
         eprintln!("Failed to initialize logging: {}", e);
         std::process::exit(1);
     }
 
     migrate_app_entry();
 
-    let mut temp_settings = AppSettings::default();
-    temp_settings.dev_mode = cfg!(debug_assertions);
-
-    // Load application settings using the temp settings to determine path
-    let loaded_settings = match temp_settings.load() {
+    let context = match AppContext::build(&args) {
         Ok(v) => v,
         Err(e) => {
-            log::warn!("Failed to load settings: {}, using defaults", e);
-            temp_settings
+            let err = format!("Can not start Irate Goose. Reason: {e:#}");
+            if args.command.is_some() {
+                // Scripts read the result from stdout, there's no one to show a dialog to
+                println!("Error: {err}");
+                std::process::exit(1);
+            }
+            if args.tui {
+                eprintln!("{err}");
+            }
+            show_warning(&err);
+            std::process::exit(1);
         }
     };
 
-    let settings = Rc::new(RefCell::new(loaded_settings));
+    // The buffer size and the log file are only known once the settings are loaded
+    if let Ok(mut buffer) = log_buffer.lock() {
+        buffer.set_capacity(context.settings.borrow().log_buffer_lines);
+    }
+    logging::apply_log_file_setting(&context.settings.borrow());
 
-    // Descriptions, loads HRTF descriptions from embedded CSV
-    let descriptions = match Descriptions::new() {
-        Ok(v) => v,
-        Err(e) => {
-            let err = format!("Can not load HRTF descriptions. Reason: {e}");
-            show_warning(&err);
+    if let Some(command) = &args.command {
+        let mut context = context;
+        if let Err(e) = commands::run(&mut context, command) {
+            println!("Error: {e:#}");
             std::process::exit(1);
         }
-    };
-
-    // File manager, scans for WAV files.
-    let mut file_manager = FileManager::new(settings.clone(), descriptions);
+        return;
+    }
 
-    // Config manager, writes and deletes the PipeWire config
-    let config_manager = match ConfigManager::new(settings.clone()) {
-        Ok(v) => v,
-        Err(e) => {
-            let err = format!("Can not process config file. Reason: {e}");
-            show_warning(&err);
+    if args.tui {
+        if let Err(e) = tui::run(context) {
+            eprintln!("Terminal interface failed: {e:#}");
             std::process::exit(1);
         }
-    };
+        return;
+    }
+    let AppContext {
+        settings,
+        mut file_manager,
+        config_manager,
+        initial_path,
+    } = context;
 
     // Load icon from embedded PNG bytes (same as used in goose.rs)
     let icon_bytes = include_bytes!("../data/IrateGoose256.png");
@@ -99,6 +112,7 @@ fn main() {
                 &mut file_manager,
                 &config_manager,
                 log_buffer,
+                initial_path.clone(),
             )))
         }),
     );
@@ -0,0 +1,117 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::settings::{AppSettings, checksum_hex};
+
+/// A free-text note the user wrote about an IR file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileNote {
+    /// xxh3_128 checksum of the file
+    #[serde(with = "checksum_hex")]
+    checksum: u128,
+    /// Path relative to the WAV directory, absolute for files of additional
+    /// directories (see `WavFileData::library_path`). Used when the checksum
+    /// changes
+    relative_path: PathBuf,
+    text: String,
+}
+
+impl FileNote {
+    /// Checks whether this note belongs to the given file.
+    fn matches(&self, checksum: u128, relative_path: &Path) -> bool {
+        (checksum != 0 && self.checksum == checksum) || self.relative_path == relative_path
+    }
+}
+
+/// Serialized form of the notes file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NotesFile {
+    #[serde(default)]
+    notes: Vec<FileNote>,
+}
+
+/// User notes for IR files, stored in their own file next to the settings.
+/// Notes are found by checksum, falling back to the relative path, so they
+/// survive both moving and replacing a file.
+pub struct NotesStore {
+    /// Full path to the notes file
+    path: PathBuf,
+    notes: Vec<FileNote>,
+}
+
+impl NotesStore {
+    /// Loads the notes from the notes file of the given settings.
+    /// A missing file gives an empty store.
+    pub fn load(settings: &AppSettings) -> Result<NotesStore> {
+        let path = settings.notes_path()?;
+        let notes = if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read notes file: {}", path.display()))?;
+            let file: NotesFile = toml::from_str(&content)
+                .with_context(|| format!("Failed to parse notes TOML: {}", path.display()))?;
+            file.notes
+        } else {
+            Vec::new()
+        };
+        Ok(NotesStore { path, notes })
+    }
+
+    /// Gets the note of a file, if there is one.
+    pub fn get(&self, checksum: u128, relative_path: &Path) -> Option<&str> {
+        self.notes
+            .iter()
+            .find(|n| n.matches(checksum, relative_path))
+            .map(|n| n.text.as_str())
+    }
+
+    /// Sets the note of a file. An empty text removes the note.
+    pub fn set(&mut self, checksum: u128, relative_path: &Path, text: &str) {
+        self.notes.retain(|n| !n.matches(checksum, relative_path));
+        if !text.trim().is_empty() {
+            self.notes.push(FileNote {
+                checksum,
+                relative_path: relative_path.to_path_buf(),
+                text: text.to_string(),
+            });
+        }
+    }
+
+    /// Writes the notes to the notes file.
+    pub fn save(&self) -> Result<()> {
+        let file = NotesFile {
+            notes: self.notes.clone(),
+        };
+        let toml_string =
+            toml::to_string_pretty(&file).context("Failed to serialize notes to TOML")?;
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        std::fs::write(&self.path, toml_string)
+            .with_context(|| format!("Failed to write notes to: {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notes_of_same_name_in_two_directories() {
+        let mut store = NotesStore {
+            path: PathBuf::from("notes.toml"),
+            notes: Vec::new(),
+        };
+        // The same relative name in the main and an additional directory
+        let main = Path::new("a.wav");
+        let extra = Path::new("/mine/a.wav");
+        store.set(1, main, "main");
+        store.set(2, extra, "extra");
+        assert_eq!(store.get(1, main), Some("main"));
+        assert_eq!(store.get(2, extra), Some("extra"));
+
+        // A changed checksum still finds the note by path
+        assert_eq!(store.get(3, extra), Some("extra"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Test playback of an IR file without creating a device.
+//!
+//! A click train is sent around the seven speakers of a 7.1 layout, each
+//! click convolved with the ear pair of its speaker, and the stereo result
+//! is played with `pw-play` on the default output. PipeWire ships it, and
+//! the device needs PipeWire anyway, so playback needs no audio library of
+//! its own.
+
+use anyhow::{Context, Result, anyhow, bail};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+/// Channel count of the HeSuVi layout, the only one the device supports.
+pub const PREVIEW_CHANNELS: u16 = 14;
+
+/// HeSuVi channels of each speaker as (left ear, right ear), in the order
+/// the preview visits them: FL, FC, FR, SR, RR, RL, SL.
+const SPEAKER_CHANNELS: [(usize, usize); 7] =
+    [(0, 1), (6, 13), (8, 7), (10, 9), (12, 11), (4, 5), (2, 3)];
+
+/// Clicks played from each speaker
+const CLICKS_PER_SPEAKER: usize = 3;
+
+/// Time between two clicks, in milliseconds
+const CLICK_INTERVAL_MS: usize = 120;
+
+/// Time each speaker gets, in milliseconds
+const SPEAKER_DURATION_MS: usize = 500;
+
+/// Peak level of the rendered clip
+const PEAK_LEVEL: f32 = 0.5;
+
+/// Stereo audio, one `[left, right]` pair per frame.
+pub struct PreviewClip {
+    pub sample_rate: u32,
+    pub frames: Vec<[f32; 2]>,
+}
+
+/// Checks whether a file with this channel count can be previewed.
+pub fn supports_channels(channels: u16) -> bool {
+    channels == PREVIEW_CHANNELS
+}
+
+/// Renders the preview of an IR file and plays it. Blocks until playback
+/// is over, so call it from a worker thread.
+pub fn play(ir_path: &Path) -> Result<()> {
+    let clip = render(ir_path)?;
+    // A fresh file that only we can open, removed when it is dropped
+    let mut clip_file = tempfile::Builder::new()
+        .prefix("irate_goose_preview")
+        .suffix(".wav")
+        .tempfile()
+        .context("Failed to create the preview file")?;
+    write_wav(&clip, clip_file.as_file_mut())
+        .with_context(|| format!("Failed to write {}", clip_file.path().display()))?;
+    let status = Command::new("pw-play")
+        .arg(clip_file.path())
+        .status()
+        .context("Failed to run pw-play")?;
+    if !status.success() {
+        bail!("pw-play exited with {status}");
+    }
+    Ok(())
+}
+
+/// Convolves the click train with the IR file.
+pub fn render(ir_path: &Path) -> Result<PreviewClip> {
+    let (sample_rate, channels) = read_channels(ir_path)?;
+    if channels.len() != usize::from(PREVIEW_CHANNELS) {
+        bail!(
+            "Preview needs a {PREVIEW_CHANNELS}-channel file, this one has {} channels",
+            channels.len()
+        );
+    }
+    let rate = sample_rate as usize;
+    let ir_length = channels[0].len();
+    let speaker_frames = SPEAKER_DURATION_MS * rate / 1000;
+    let click_frames = CLICK_INTERVAL_MS * rate / 1000;
+    let mut frames = vec![[0.0f32; 2]; SPEAKER_CHANNELS.len() * speaker_frames + ir_length];
+
+    // The clicks are single impulses, so the convolution is a sum of
+    // shifted copies of the impulse response
+    for (speaker, &(left, right)) in SPEAKER_CHANNELS.iter().enumerate() {
+        for click in 0..CLICKS_PER_SPEAKER {
+            let start = speaker * speaker_frames + click * click_frames;
+            let ears = channels[left].iter().zip(&channels[right]);
+            for (frame, (l, r)) in frames[start..].iter_mut().zip(ears) {
+                frame[0] += l;
+                frame[1] += r;
+            }
+        }
+    }
+
+    let peak = frames
+        .iter()
+        .flat_map(|frame| frame.iter())
+        .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+    if peak > 0.0 {
+        let gain = PEAK_LEVEL / peak;
+        frames
+            .iter_mut()
+            .flat_map(|frame| frame.iter_mut())
+            .for_each(|sample| *sample *= gain);
+    }
+    Ok(PreviewClip {
+        sample_rate,
+        frames,
+    })
+}
+
+/// Reads all samples of a WAV file as floats, one vector per channel.
+/// Supports integer PCM of 16, 24 and 32 bits and 32-bit float.
+fn read_channels(path: &Path) -> Result<(u32, Vec<Vec<f32>>)> {
+    let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if data.get(0..4) != Some(b"RIFF".as_slice()) || data.get(8..12) != Some(b"WAVE".as_slice()) {
+        bail!("{} is not a WAV file", path.display());
+    }
+    let fmt = find_chunk(&data, b"fmt ").ok_or_else(|| anyhow!("No fmt chunk"))?;
+    let samples = find_chunk(&data, b"data").ok_or_else(|| anyhow!("No data chunk"))?;
+    if fmt.len() < 16 {
+        bail!("The fmt chunk is too short");
+    }
+    let mut format = u16::from_le_bytes([fmt[0], fmt[1]]);
+    let channel_count = usize::from(u16::from_le_bytes([fmt[2], fmt[3]]));
+    let sample_rate = u32::from_le_bytes([fmt[4], fmt[5], fmt[6], fmt[7]]);
+    let bits = u16::from_le_bytes([fmt[14], fmt[15]]);
+    // WAVE_FORMAT_EXTENSIBLE keeps the real format in the sub-format GUID
+    if format == 0xfffe && fmt.len() >= 26 {
+        format = u16::from_le_bytes([fmt[24], fmt[25]]);
+    }
+    let decode: fn(&[u8]) -> f32 = match (format, bits) {
+        (1, 16) => |b| f32::from(i16::from_le_bytes([b[0], b[1]])) / 32768.0,
+        (1, 24) => |b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0,
+        (1, 32) => |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0,
+        (3, 32) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        _ => bail!("Unsupported sample format {format} with {bits} bits"),
+    };
+    if channel_count == 0 || sample_rate == 0 {
+        bail!("The fmt chunk has no channels or no sample rate");
+    }
+
+    let sample_size = usize::from(bits / 8);
+    let mut channels = vec![Vec::new(); channel_count];
+    for frame in samples.chunks_exact(sample_size * channel_count) {
+        for (channel, sample) in channels.iter_mut().zip(frame.chunks_exact(sample_size)) {
+            channel.push(decode(sample));
+        }
+    }
+    Ok((sample_rate, channels))
+}
+
+/// Finds the body of a RIFF chunk, clipped to the end of the data.
+fn find_chunk<'a>(data: &'a [u8], id: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut offset = 12;
+    while let Some(header) = data.get(offset..offset + 8) {
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let body = offset + 8;
+        if &header[0..4] == id {
+            return data.get(body..(body + size).min(data.len()));
+        }
+        // Chunks are padded to an even size
+        offset = body + size + (size & 1);
+    }
+    None
+}
+
+/// Writes the clip as a 16-bit stereo WAV file.
+fn write_wav(clip: &PreviewClip, file: &mut fs::File) -> Result<()> {
+    let data_size = (clip.frames.len() * 4) as u32;
+    let mut out = Vec::with_capacity(44 + data_size as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_size).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&2u16.to_le_bytes()); // Stereo
+    out.extend_from_slice(&clip.sample_rate.to_le_bytes());
+    out.extend_from_slice(&(clip.sample_rate * 4).to_le_bytes()); // Bytes per second
+    out.extend_from_slice(&4u16.to_le_bytes()); // Block align
+    out.extend_from_slice(&16u16.to_le_bytes()); // Bits per sample
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_size.to_le_bytes());
+    for sample in clip.frames.iter().flat_map(|frame| frame.iter()) {
+        let value = (sample.clamp(-1.0, 1.0) * 32767.0) as i16;
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    file.write_all(&out)?;
+    Ok(())
+}
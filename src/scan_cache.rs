@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::settings::{AppSettings, checksum_hex};
+
+/// Version of the cache file format. A cache with another version is
+/// discarded as a whole.
+const CACHE_VERSION: u32 = 2;
+
+/// Modification time and size of a file, used to tell whether a cached
+/// result is still valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct FileStamp {
+    modified_secs: u64,
+    modified_nanos: u32,
+    size: u64,
+}
+
+impl FileStamp {
+    /// Takes the stamp from file metadata. None if the modification time
+    /// is not available.
+    pub(crate) fn of(metadata: &Metadata) -> Option<FileStamp> {
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(FileStamp {
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+            size: metadata.len(),
+        })
+    }
+
+    /// Size of the file in bytes.
+    pub(crate) fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// Scan result of one file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CacheEntry {
+    /// Absolute path of the file
+    pub(crate) path: PathBuf,
+    pub(crate) stamp: FileStamp,
+    /// Sample rate in Hz, 0 for rates the application doesn't know
+    pub(crate) sample_rate: u32,
+    #[serde(with = "checksum_hex")]
+    pub(crate) checksum: u128,
+    pub(crate) channels: u16,
+    pub(crate) bit_depth: u16,
+    pub(crate) duration_ms: u32,
+}
+
+/// Serialized form of the cache file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    entries: Vec<CacheEntry>,
+}
+
+/// Scan results of previous rescans, stored in their own file next to the
+/// settings, so unchanged files are not read and hashed again.
+pub(crate) struct ScanCache {
+    /// Full path to the cache file
+    path: PathBuf,
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+impl ScanCache {
+    /// Loads the cache file of the given settings. A missing, unreadable or
+    /// outdated file gives an empty cache, since it only costs a full rescan.
+    pub(crate) fn load(settings: &AppSettings) -> Result<ScanCache> {
+        let path = settings.scan_cache_path()?;
+        let file = match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str::<CacheFile>(&content).unwrap_or_else(|e| {
+                info!("Discarding unreadable scan cache {}: {}", path.display(), e);
+                CacheFile::default()
+            }),
+            Err(_) => CacheFile::default(),
+        };
+        let entries = if file.version == CACHE_VERSION {
+            file.entries
+                .into_iter()
+                .map(|entry| (entry.path.clone(), entry))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        Ok(ScanCache { path, entries })
+    }
+
+    /// Gets the cached result of a file, if the file didn't change since.
+    pub(crate) fn get(&self, path: &Path, stamp: &FileStamp) -> Option<&CacheEntry> {
+        self.entries.get(path).filter(|entry| entry.stamp == *stamp)
+    }
+
+    /// Replaces all entries of files inside `dirs` with the results of
+    /// their latest scan, dropping the files that are gone.
+    pub(crate) fn replace_dirs(&mut self, dirs: &[PathBuf], entries: Vec<CacheEntry>) {
+        self.entries
+            .retain(|path, _| !dirs.iter().any(|dir| path.starts_with(dir)));
+        self.entries
+            .extend(entries.into_iter().map(|entry| (entry.path.clone(), entry)));
+    }
+
+    /// Writes the cache to the cache file.
+    pub(crate) fn save(&self) -> Result<()> {
+        let mut entries: Vec<CacheEntry> = self.entries.values().cloned().collect();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        let file = CacheFile {
+            version: CACHE_VERSION,
+            entries,
+        };
+        let toml_string =
+            toml::to_string(&file).context("Failed to serialize scan cache to TOML")?;
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        std::fs::write(&self.path, toml_string)
+            .with_context(|| format!("Failed to write scan cache to: {}", self.path.display()))
+    }
+
+    /// Forgets all entries and deletes the cache file.
+    pub(crate) fn clear(&mut self) -> Result<()> {
+        self.entries.clear();
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)
+                .with_context(|| format!("Failed to delete scan cache: {}", self.path.display()))?;
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,227 @@
-use anyhow::{Context, Result};
-use eframe::egui::ThemePreference;
+use anyhow::{Context, Result, bail};
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Default virtual device name used when no custom name is provided.
 pub const DEFAULT_VIRTUAL_DEVICE_NAME: &str = "Virtual Surround Sink";
 
+/// Default systemd user units restarted to apply a new PipeWire config.
+pub const DEFAULT_RESTART_UNITS: [&str; 3] = ["wireplumber", "pipewire", "pipewire-pulse"];
+
+/// Default number of log lines kept in memory for the Log tab.
+pub const DEFAULT_LOG_BUFFER_LINES: usize = 5000;
+
+/// Range of the output gain of the device, in dB.
+pub const OUTPUT_GAIN_RANGE: std::ops::RangeInclusive<f32> = -24.0..=24.0;
+
+/// Layout version of the settings file written by this version.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// A settings layout upgrade, as (description, upgrade function).
+type Migration = (&'static str, fn(&mut toml::Table));
+
+/// Upgrades of the settings layout. Entry `n` turns a file of schema
+/// version `n` into version `n + 1`.
+const MIGRATIONS: [Migration; 2] = [
+    (
+        "add schema_version to an unversioned file",
+        migrate_unversioned,
+    ),
+    (
+        "keep the device identifier of devices created before it followed the device name",
+        migrate_pinned_device_id,
+    ),
+];
+
+/// Files written before the schema version was added have the layout of
+/// version 1, so there's nothing to convert.
+fn migrate_unversioned(_table: &mut toml::Table) {}
+
+/// Before version 2 a missing identifier meant the default device, and the
+/// device name did not affect the config file name.
+fn migrate_pinned_device_id(table: &mut toml::Table) {
+    table
+        .entry("device_id")
+        .or_insert_with(|| toml::Value::String(String::new()));
+}
+
+/// How many recently scanned directories are remembered.
+pub const MAX_RECENT_DIRECTORIES: usize = 8;
+
+/// UI theme preference. Variant names match egui's `ThemePreference`, so
+/// settings files written by older versions load unchanged.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AppTheme {
+    Light,
+    Dark,
+    /// Follow the system color scheme
+    #[default]
+    System,
+    /// Dark theme with maximum contrast, for accessibility
+    HighContrast,
+}
+
+/// Language of the user interface.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Russian,
+}
+
+impl Language {
+    /// All supported languages, in the order shown in the UI.
+    pub const ALL: [Language; 2] = [Language::English, Language::Russian];
+
+    /// Name of the language in the language itself.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Russian => "Русский",
+        }
+    }
+}
+
+/// Row density of the file table.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TableDensity {
+    /// Smaller rows, to fit more files on small screens
+    Compact,
+    #[default]
+    Comfortable,
+}
+
+impl TableDensity {
+    /// Height of a file table row, in points.
+    pub fn row_height(self) -> f32 {
+        match self {
+            TableDensity::Compact => 16.0,
+            TableDensity::Comfortable => 20.0,
+        }
+    }
+
+    /// Height of the file table header, in points.
+    pub fn header_height(self) -> f32 {
+        match self {
+            TableDensity::Compact => 18.0,
+            TableDensity::Comfortable => 20.0,
+        }
+    }
+}
+
+/// Speaker layout of the virtual device.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChannelLayout {
+    #[serde(rename = "5.1")]
+    Surround51,
+    #[default]
+    #[serde(rename = "7.1")]
+    Surround71,
+}
+
+impl ChannelLayout {
+    /// All layouts, in the order they are offered.
+    pub const ALL: [ChannelLayout; 2] = [ChannelLayout::Surround51, ChannelLayout::Surround71];
+
+    /// Name of the layout, as shown to the user.
+    pub fn name(self) -> &'static str {
+        match self {
+            ChannelLayout::Surround51 => "5.1",
+            ChannelLayout::Surround71 => "7.1",
+        }
+    }
+}
+
+/// Tab of the main window.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tab {
+    #[default]
+    Files,
+    Options,
+    Log,
+    Help,
+}
+
+/// Column the file table is sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortColumn {
+    Path,
+    Modified,
+    Channels,
+    Points,
+    Size,
+    Description,
+}
+
+/// A file the user marked as favorite. The checksum identifies the file
+/// content; the relative path keeps the favorite when the file is replaced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FavoriteFile {
+    /// xxh3_128 checksum of the file
+    #[serde(with = "checksum_hex")]
+    pub checksum: u128,
+    /// Path relative to the WAV directory, absolute for files of additional
+    /// directories (see `WavFileData::library_path`)
+    pub relative_path: PathBuf,
+}
+
+impl FavoriteFile {
+    /// Checks whether this favorite refers to the given file.
+    fn matches(&self, checksum: u128, relative_path: &Path) -> bool {
+        (checksum != 0 && self.checksum == checksum) || self.relative_path == relative_path
+    }
+}
+
+/// A named device setup, so users can flip between e.g. one HRIR for games
+/// and another for music.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Name shown in the profile selector, unique among the profiles
+    pub name: String,
+    /// Name of the virtual device
+    pub device_name: String,
+    /// xxh3_128 checksum of the IR file, 0 if none was chosen
+    #[serde(with = "checksum_hex")]
+    pub checksum: u128,
+}
+
+/// Two files compared side by side, identified by checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComparedPair {
+    /// The selected file
+    #[serde(with = "checksum_hex")]
+    pub first: u128,
+    /// The file it is compared with
+    #[serde(with = "checksum_hex")]
+    pub second: u128,
+}
+
+/// Serializes checksums as hex strings, since TOML integers are limited to 64 bits.
+pub(crate) mod checksum_hex {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(checksum: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{checksum:032x}"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        u128::from_str_radix(&text, 16).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Deserializes a value, falling back to its default if the stored one is
+/// not valid, e.g. a variant written by a newer version.
+fn or_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned + Default,
+{
+    let value = toml::Value::deserialize(deserializer)?;
+    Ok(T::deserialize(value).unwrap_or_default())
+}
+
 /// Application settings for IrateGoose (NOT PipeWire settings).
 /// These settings control the application behavior, such as WAV directory
 /// preferences and virtual device naming, and are stored separately from
@@ -13,62 +229,339 @@ pub const DEFAULT_VIRTUAL_DEVICE_NAME: &str = "Virtual Surround Sink";
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct AppSettings {
+    /// Layout version of the file, see `SCHEMA_VERSION`
+    pub schema_version: u32,
+
     /// Path to the WAV files directory
     wav_directory: Option<PathBuf>,
 
+    /// More directories scanned together with the WAV directory
+    pub extra_wav_directories: Vec<PathBuf>,
+
     /// Virtual device name for PipeWire
     pub virtual_device_name: String,
 
-    /// UI theme preference (Light, Dark, or follow system)
-    pub theme_preference: ThemePreference,
+    /// Identifier of the virtual device, used in its node and config file
+    /// names so several devices can coexist. Empty for the default device;
+    /// None = derived from the device name, see `ConfigManager::device_id`
+    pub device_id: Option<String>,
+
+    /// UI theme preference (Light, Dark, High contrast, or follow system)
+    #[serde(deserialize_with = "or_default")]
+    pub theme_preference: AppTheme,
+
+    /// Language of the user interface
+    #[serde(deserialize_with = "or_default")]
+    pub language: Language,
+
+    /// Row density of the file table
+    #[serde(deserialize_with = "or_default")]
+    pub table_density: TableDensity,
 
     /// Selected output sink (audio device) node.name; None = Auto (let PipeWire decide)
     pub output_device: Option<String>,
 
+    /// Directory that holds the `hrir` folder with the installed IR file;
+    /// None = next to the PipeWire config file
+    pub hrir_directory: Option<PathBuf>,
+
+    /// Copy the IR file into the `hrir` folder; false = the config refers
+    /// to the original file
+    pub copy_ir_file: bool,
+
+    /// CSV file in the format of the embedded descriptions database whose
+    /// entries replace or extend it; None = embedded database only
+    pub descriptions_file: Option<PathBuf>,
+
+    /// Systemd user units restarted to apply config changes. Units that do
+    /// not exist on the system are skipped.
+    pub restart_units: Vec<String>,
+
+    /// Units the user added to the list of restarted units, kept listed in
+    /// the Options tab when they are unchecked
+    pub custom_units: Vec<String>,
+
+    /// Restart the services after every config change; when off, the user
+    /// reloads PipeWire themselves
+    pub restart_services: bool,
+
+    /// Write the config as soon as another file is selected
+    pub auto_apply: bool,
+
+    /// Speaker layout of the virtual device
+    #[serde(deserialize_with = "or_default")]
+    pub channel_layout: ChannelLayout,
+
+    /// Number of log lines kept for the Log tab; older ones are dropped
+    pub log_buffer_lines: usize,
+
+    /// Also write the log to a file that rolls over at a size limit
+    pub log_to_file: bool,
+
+    /// Gain of the convolvers of the device, in dB, to level-match quiet
+    /// or loud IR files. Clamped to `OUTPUT_GAIN_RANGE` when used.
+    pub output_gain_db: f32,
+
+    /// Rescan the IR directory when WAV files in it change
+    pub watch_directory: bool,
+
+    /// How many levels of subdirectories are scanned; None = unlimited
+    pub scan_depth: Option<u32>,
+
+    /// Whether the scan enters symlinked directories and files
+    pub follow_symlinks: bool,
+
+    /// List only one file of each group of identical files
+    pub hide_duplicates: bool,
+
+    /// Show the measurement point count of HRTFs as a table column
+    pub show_points_column: bool,
+
+    /// Show the file size column in the file table
+    pub show_size_column: bool,
+
+    /// Column the file table is sorted by; None = scan order
+    #[serde(deserialize_with = "or_default")]
+    pub sort_column: Option<SortColumn>,
+
+    /// Whether the file table is sorted in descending order
+    pub sort_descending: bool,
+
+    /// Tab shown on startup, the one open when the app was closed
+    #[serde(deserialize_with = "or_default")]
+    pub selected_tab: Tab,
+
+    /// Sample rate the file list is filtered by, in Hz; None = all rates
+    pub sample_rate_filter: Option<u32>,
+
+    /// Directories that scanned successfully, most recent first
+    pub recent_directories: Vec<PathBuf>,
+
+    /// Files the user marked with a star
+    pub favorites: Vec<FavoriteFile>,
+
+    /// List favorites above all other files
+    pub favorites_first: bool,
+
+    /// Ask before removing the virtual device
+    pub confirm_device_removal: bool,
+
+    /// Named device setups
+    pub profiles: Vec<Profile>,
+
+    /// Index of the profile in use; None = no profile
+    pub active_profile: Option<usize>,
+
+    /// Last compared pair of files, restored on startup
+    pub compared_pair: Option<ComparedPair>,
+
     /// Development mode flag (runtime only, not persisted)
     #[serde(skip)]
     pub dev_mode: bool,
+
+    /// Overrides the user config directory, from `--config-dir`
+    /// (runtime only, not persisted)
+    #[serde(skip)]
+    pub config_dir: Option<PathBuf>,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
+            schema_version: SCHEMA_VERSION,
             wav_directory: None,
+            extra_wav_directories: Vec::new(),
             virtual_device_name: DEFAULT_VIRTUAL_DEVICE_NAME.to_string(),
-            theme_preference: ThemePreference::System,
+            device_id: None,
+            theme_preference: AppTheme::System,
+            language: Language::English,
+            table_density: TableDensity::Comfortable,
             output_device: None,
+            hrir_directory: None,
+            copy_ir_file: true,
+            descriptions_file: None,
+            restart_units: DEFAULT_RESTART_UNITS.map(String::from).to_vec(),
+            custom_units: Vec::new(),
+            restart_services: true,
+            auto_apply: false,
+            channel_layout: ChannelLayout::Surround71,
+            output_gain_db: 0.0,
+            log_buffer_lines: DEFAULT_LOG_BUFFER_LINES,
+            log_to_file: false,
+            watch_directory: false,
+            scan_depth: None,
+            follow_symlinks: false,
+            hide_duplicates: false,
+            show_points_column: true,
+            show_size_column: false,
+            sort_column: None,
+            sort_descending: false,
+            selected_tab: Tab::Files,
+            sample_rate_filter: Some(48000),
+            recent_directories: Vec::new(),
+            favorites: Vec::new(),
+            favorites_first: true,
+            confirm_device_removal: true,
+            profiles: Vec::new(),
+            active_profile: None,
+            compared_pair: None,
             dev_mode: false,
+            config_dir: None,
         }
     }
 }
 
 impl AppSettings {
     /// Loads settings from a TOML string
-    fn load_from_str(toml_str: &str) -> Result<Self> {
-        let settings: AppSettings =
+    pub fn load_from_str(toml_str: &str) -> Result<Self> {
+        Self::load_and_migrate(toml_str).map(|(settings, _)| settings)
+    }
+
+    /// Loads settings from a TOML string, upgrading an older layout first.
+    /// Also returns the descriptions of the migrations that ran.
+    fn load_and_migrate(toml_str: &str) -> Result<(Self, Vec<&'static str>)> {
+        let mut table: toml::Table =
             toml::from_str(toml_str).context("Failed to parse settings TOML")?;
+        let migrations = Self::migrate(&mut table);
+        let settings: AppSettings = toml::Value::Table(table)
+            .try_into()
+            .context("Failed to parse settings TOML")?;
+        if settings.schema_version > SCHEMA_VERSION {
+            warn!(
+                "Settings file has schema version {}, newer than {}; unknown values are ignored",
+                settings.schema_version, SCHEMA_VERSION
+            );
+        }
+        Ok((settings, migrations))
+    }
 
-        Ok(settings)
+    /// Runs the migrations a settings table of an older layout needs.
+    /// Returns their descriptions, empty if the table is up to date.
+    fn migrate(table: &mut toml::Table) -> Vec<&'static str> {
+        let version = table
+            .get("schema_version")
+            .and_then(toml::Value::as_integer)
+            .unwrap_or(0);
+        let mut applied = Vec::new();
+        for (from, (description, migration)) in MIGRATIONS.iter().enumerate() {
+            if from as i64 >= version {
+                migration(table);
+                applied.push(*description);
+            }
+        }
+        if !applied.is_empty() {
+            table.insert(
+                "schema_version".to_string(),
+                toml::Value::Integer(SCHEMA_VERSION.into()),
+            );
+        }
+        applied
     }
 
     /// Saves settings to a TOML string
-    fn save_to_str(&self) -> Result<String> {
+    pub fn save_to_str(&self) -> Result<String> {
         let toml_string =
             toml::to_string_pretty(self).context("Failed to serialize settings to TOML")?;
 
         Ok(toml_string)
     }
 
+    /// Gets the root directory for all configuration: the `--config-dir`
+    /// override if given, else the user config directory (`$XDG_CONFIG_HOME`
+    /// or `~/.config`).
+    pub fn config_root(&self) -> Result<PathBuf> {
+        match &self.config_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => dirs::config_dir().context("Could not determine config directory"),
+        }
+    }
+
+    /// Gets the directory PipeWire reads the user config from: the
+    /// `--config-dir` override if given, else the user config directory.
+    /// Inside Flatpak that one belongs to the sandbox, so the host's is
+    /// used, which needs the `xdg-config/pipewire` permission.
+    pub fn pipewire_config_root(&self) -> Result<PathBuf> {
+        let flatpak_id = std::env::var("FLATPAK_ID").ok();
+        let in_flatpak = flatpak_id.is_some() || Path::new("/.flatpak-info").exists();
+        if self.config_dir.is_some() || !in_flatpak {
+            return self.config_root();
+        }
+        let host_config = std::env::var_os("HOST_XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| dirs::home_dir().map(|home| home.join(".config")))
+            .context("Could not determine the config directory of the host")?;
+        // Without the permission the directory is not there in the sandbox,
+        // and a config written anyway never reaches PipeWire
+        let pipewire_dir = host_config.join("pipewire");
+        if !pipewire_dir.is_dir() {
+            bail!(
+                "Irate Goose runs in Flatpak without access to {}. Grant it with: \
+                 flatpak override --user --filesystem=xdg-config/pipewire:create {}",
+                pipewire_dir.display(),
+                flatpak_id.as_deref().unwrap_or("<app id>")
+            );
+        }
+        Ok(host_config)
+    }
+
+    /// Output gain as the linear factor the convolvers multiply by.
+    pub fn output_gain_factor(&self) -> f32 {
+        let db = self
+            .output_gain_db
+            .clamp(*OUTPUT_GAIN_RANGE.start(), *OUTPUT_GAIN_RANGE.end());
+        10f32.powf(db / 20.0)
+    }
+
+    /// Gets the path of the settings file that `load` and `save` use
+    pub fn settings_path(&self) -> Result<PathBuf> {
+        self.default_settings_path()
+    }
+
     /// Gets the default settings file path
     fn default_settings_path(&self) -> Result<PathBuf> {
-        if self.dev_mode {
+        self.app_file_path("settings")
+    }
+
+    /// Gets the path of the per-file notes store
+    pub fn notes_path(&self) -> Result<PathBuf> {
+        self.app_file_path("notes")
+    }
+
+    /// Gets the path of the cache of scan results
+    pub fn scan_cache_path(&self) -> Result<PathBuf> {
+        self.app_file_path("scan_cache")
+    }
+
+    /// Gets the path of the log file written when `log_to_file` is on.
+    /// Logs go to the cache directory, unless the config directory was
+    /// overridden.
+    pub fn log_file_path(&self) -> Result<PathBuf> {
+        match (self.dev_mode, &self.config_dir) {
+            (true, _) => Ok(self
+                .app_file_path("log")?
+                .with_file_name("irate_goose_dev.log")),
+            (false, Some(dir)) => Ok(dir.join("irate_goose").join("irate_goose.log")),
+            (false, None) => Ok(dirs::cache_dir()
+                .context("Could not determine cache directory")?
+                .join("irate_goose")
+                .join("irate_goose.log")),
+        }
+    }
+
+    /// Gets the path of an application data file named `<name>.toml`
+    fn app_file_path(&self, name: &str) -> Result<PathBuf> {
+        let dev_file = format!("irate_goose_dev_{name}.toml");
+        match (self.dev_mode, &self.config_dir) {
+            // In dev mode, use a file in the overridden config directory
+            (true, Some(dir)) => Ok(dir.join(dev_file)),
             // In dev mode, use a file in the current directory
-            Ok(std::env::current_dir()?.join("irate_goose_dev_settings.toml"))
-        } else {
+            (true, None) => Ok(std::env::current_dir()?.join(dev_file)),
             // In normal mode, use the standard config directory
-            let config_dir = dirs::config_dir().context("Could not determine config directory")?;
-
-            Ok(config_dir.join("irate_goose").join("settings.toml"))
+            (false, _) => Ok(self
+                .config_root()?
+                .join("irate_goose")
+                .join(format!("{name}.toml"))),
         }
     }
 
@@ -88,22 +581,32 @@ impl AppSettings {
         Ok(())
     }
 
-    /// Read settings from a file
-    fn read_settings_from_file(path: &std::path::Path) -> Result<Self> {
+    /// Read settings from a file, with the migrations that ran on it
+    fn read_settings_from_file(path: &std::path::Path) -> Result<(Self, Vec<&'static str>)> {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("Failed to read settings file: {}", path.display()))?;
 
-        Self::load_from_str(&content)
+        Self::load_and_migrate(&content)
             .with_context(|| format!("Failed to parse settings TOML: {}", path.display()))
     }
 
     /// Load settings from the default settings file
-    /// Uses self.dev_mode to determine which file to load from
-    /// The loaded settings will have the same dev_mode as self
+    /// Uses self.dev_mode and self.config_dir to determine which file to load from
+    /// The loaded settings will have the same dev_mode and config_dir as self
     pub fn load(&self) -> Result<Self> {
         let path = self.default_settings_path()?;
-        let mut settings = Self::read_settings_from_file(&path)?;
+        let (mut settings, migrations) = Self::read_settings_from_file(&path)?;
         settings.dev_mode = self.dev_mode;
+        settings.config_dir = self.config_dir.clone();
+        if !migrations.is_empty() {
+            for migration in &migrations {
+                info!("Settings migration: {}", migration);
+            }
+            // Rewrite the file so the migrations run only once
+            if let Err(e) = settings.write_settings_to_file(&path) {
+                warn!("Failed to save migrated settings: {:#}", e);
+            }
+        }
         Ok(settings)
     }
 
@@ -126,6 +629,66 @@ impl AppSettings {
     pub fn is_wav_directory_set(&self) -> bool {
         self.wav_directory.is_some()
     }
+
+    /// Directories to scan: the WAV directory first, then the additional
+    /// ones. Empty while no WAV directory is set.
+    pub fn wav_directories(&self) -> Vec<PathBuf> {
+        let Some(main) = &self.wav_directory else {
+            return Vec::new();
+        };
+        let mut dirs = vec![main.clone()];
+        for dir in &self.extra_wav_directories {
+            if !dirs.contains(dir) {
+                dirs.push(dir.clone());
+            }
+        }
+        dirs
+    }
+
+    /// Index of the profile in use, None if there is none or the stored
+    /// index is out of range.
+    pub fn active_profile(&self) -> Option<usize> {
+        self.active_profile.filter(|&i| i < self.profiles.len())
+    }
+
+    /// The profile in use, for changing it.
+    pub fn active_profile_mut(&mut self) -> Option<&mut Profile> {
+        let index = self.active_profile()?;
+        self.profiles.get_mut(index)
+    }
+
+    /// Moves the directory to the top of the recent directories, dropping
+    /// the oldest ones past `MAX_RECENT_DIRECTORIES`.
+    /// Returns false if it already was on top.
+    pub fn add_recent_directory(&mut self, dir: PathBuf) -> bool {
+        if self.recent_directories.first() == Some(&dir) {
+            return false;
+        }
+        self.recent_directories.retain(|d| *d != dir);
+        self.recent_directories.insert(0, dir);
+        self.recent_directories.truncate(MAX_RECENT_DIRECTORIES);
+        true
+    }
+
+    /// Checks whether the file is a favorite, by checksum or by relative path.
+    pub fn is_favorite(&self, checksum: u128, relative_path: &Path) -> bool {
+        self.favorites
+            .iter()
+            .any(|f| f.matches(checksum, relative_path))
+    }
+
+    /// Marks the file as favorite, or removes the mark if it is already set.
+    pub fn toggle_favorite(&mut self, checksum: u128, relative_path: &Path) {
+        if self.is_favorite(checksum, relative_path) {
+            self.favorites
+                .retain(|f| !f.matches(checksum, relative_path));
+        } else {
+            self.favorites.push(FavoriteFile {
+                checksum,
+                relative_path: relative_path.to_path_buf(),
+            });
+        }
+    }
 }
 
 #[cfg(test)]
@@ -135,9 +698,11 @@ mod tests {
     #[test]
     fn test_load_from_str_and_save_to_str() {
         // Create a settings instance with some values
-        let mut settings = AppSettings::default();
-        settings.wav_directory = Some(std::path::PathBuf::from("/test/path/to/wav"));
-        settings.virtual_device_name = "Test Virtual Device".to_string();
+        let settings = AppSettings {
+            wav_directory: Some(std::path::PathBuf::from("/test/path/to/wav")),
+            virtual_device_name: "Test Virtual Device".to_string(),
+            ..Default::default()
+        };
 
         // Save to string
         let saved_str = settings
@@ -174,4 +739,83 @@ mod tests {
             default_settings.virtual_device_name
         );
     }
+
+    #[test]
+    fn test_unknown_tab_falls_back_to_files() {
+        let loaded =
+            AppSettings::load_from_str("selected_tab = \"Mixer\"\nsample_rate_filter = 44100\n")
+                .expect("An unknown tab must not break loading");
+        assert_eq!(loaded.selected_tab, Tab::Files);
+        assert_eq!(loaded.sample_rate_filter, Some(44100));
+
+        let loaded = AppSettings::load_from_str("selected_tab = \"Log\"\n").unwrap();
+        assert_eq!(loaded.selected_tab, Tab::Log);
+    }
+
+    #[test]
+    fn test_unknown_enum_values_fall_back_to_defaults() {
+        let loaded = AppSettings::load_from_str(
+            "theme_preference = \"Sepia\"\nlanguage = \"Klingon\"\n\
+             table_density = \"Dense\"\nsort_column = \"Loudness\"\nshow_size_column = true\n",
+        )
+        .expect("Unknown values must not break loading");
+        assert_eq!(loaded.theme_preference, AppTheme::System);
+        assert_eq!(loaded.language, Language::English);
+        assert_eq!(loaded.table_density, TableDensity::Comfortable);
+        assert_eq!(loaded.sort_column, None);
+        assert!(loaded.show_size_column);
+    }
+
+    #[test]
+    fn test_unversioned_file_is_migrated() {
+        let (loaded, migrations) =
+            AppSettings::load_and_migrate("virtual_device_name = \"Old Device\"\n").unwrap();
+        assert_eq!(migrations.len(), MIGRATIONS.len());
+        assert_eq!(loaded.schema_version, SCHEMA_VERSION);
+        assert_eq!(loaded.virtual_device_name, "Old Device");
+        // The renamed device keeps the config file of the default device
+        assert_eq!(loaded.device_id.as_deref(), Some(""));
+
+        // A current file needs no migration
+        let saved = loaded.save_to_str().unwrap();
+        let (_, migrations) = AppSettings::load_and_migrate(&saved).unwrap();
+        assert!(migrations.is_empty());
+    }
+
+    #[test]
+    fn test_output_gain_factor_is_clamped() {
+        let mut settings = AppSettings::default();
+        assert_eq!(settings.output_gain_factor(), 1.0);
+        settings.output_gain_db = -6.0;
+        assert!((settings.output_gain_factor() - 0.501_187).abs() < 1e-5);
+        settings.output_gain_db = 100.0;
+        assert!((settings.output_gain_factor() - 15.848_93).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_favorites_of_same_name_in_two_directories() {
+        use crate::file_manager::WavFileData;
+        let main = WavFileData {
+            path: PathBuf::from("/hesuvi/a.wav"),
+            relative_path: PathBuf::from("a.wav"),
+            checksum: 1,
+            ..Default::default()
+        };
+        let extra = WavFileData {
+            path: PathBuf::from("/mine/a.wav"),
+            relative_path: PathBuf::from("a.wav"),
+            checksum: 2,
+            extra_directory: true,
+            ..Default::default()
+        };
+        let mut settings = AppSettings::default();
+        settings.toggle_favorite(main.checksum, main.library_path());
+        assert!(settings.is_favorite(main.checksum, main.library_path()));
+        assert!(!settings.is_favorite(extra.checksum, extra.library_path()));
+
+        settings.toggle_favorite(extra.checksum, extra.library_path());
+        settings.toggle_favorite(main.checksum, main.library_path());
+        assert!(!settings.is_favorite(main.checksum, main.library_path()));
+        assert!(settings.is_favorite(extra.checksum, extra.library_path()));
+    }
 }
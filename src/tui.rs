@@ -0,0 +1,401 @@
+//! Minimal keyboard-only terminal interface, for headless machines and SSH
+//! sessions. Uses the same scan and config logic as the GUI.
+
+use anyhow::Result;
+use log::{error, info, warn};
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::app_context::AppContext;
+use crate::app_gui::strings::{Msg, preflight_error_text, set_language, tr};
+use crate::config_manager::{ConfigManager, PreflightError};
+use crate::file_manager::{FileManager, WaveSampleRate};
+use crate::settings::AppSettings;
+use crate::wav_file_index::WavFileIndex;
+
+/// State of the terminal interface.
+struct TuiApp {
+    settings: Rc<RefCell<AppSettings>>,
+    file_manager: FileManager,
+    config_manager: ConfigManager,
+    wav_files: WavFileIndex,
+    /// Indices into `wav_files` of the files matching the filter
+    visible: Vec<usize>,
+    list_state: ListState,
+    filter: String,
+    /// True while keys go to the filter line instead of the list
+    editing_filter: bool,
+    /// Checksum of the installed IR file, None if no config is installed
+    config_installed: Option<u128>,
+    /// File waiting for the user to confirm a warning before it is written
+    pending_write: Option<(PathBuf, u128)>,
+    /// True while the removal of the device waits for the user to confirm
+    pending_delete: bool,
+    /// Last result shown on the status line
+    status: String,
+    should_quit: bool,
+}
+
+/// Runs the terminal interface until the user quits.
+/// Settings are read but never saved: the directory from the command line
+/// is only used for this session.
+pub fn run(context: AppContext) -> Result<()> {
+    let AppContext {
+        settings,
+        file_manager,
+        config_manager,
+        initial_path,
+    } = context;
+    set_language(settings.borrow().language);
+
+    // A single WAV file stands for its directory and is pre-selected
+    let mut file_to_select = None;
+    if let Some(path) = initial_path {
+        if path.is_file() {
+            file_to_select = Some(path.clone());
+        }
+        settings.borrow_mut().set_wav_directory(Some(path));
+    }
+
+    let config_installed = match config_manager.config_exists() {
+        Ok(v) => v,
+        Err(e) => {
+            error!("Error checking config: {}", e);
+            None
+        }
+    };
+
+    let mut app = TuiApp {
+        settings,
+        file_manager,
+        config_manager,
+        wav_files: WavFileIndex::new(),
+        visible: Vec::new(),
+        list_state: ListState::default(),
+        filter: String::new(),
+        editing_filter: false,
+        config_installed,
+        pending_write: None,
+        pending_delete: false,
+        status: String::new(),
+        should_quit: false,
+    };
+    app.rescan();
+    if let Some(file) = file_to_select {
+        app.select_path(&file);
+    }
+
+    let mut terminal = ratatui::init();
+    let result = app.event_loop(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl TuiApp {
+    /// Draws the interface and handles keys until `should_quit` is set.
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.should_quit {
+            terminal.draw(|frame| self.render(frame))?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.on_key(key.code);
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles a single key press.
+    fn on_key(&mut self, code: KeyCode) {
+        if let Some((path, checksum)) = self.pending_write.take() {
+            if code == KeyCode::Char('y') {
+                self.write_file(&path, checksum);
+            } else {
+                self.status.clear();
+            }
+            return;
+        }
+        if std::mem::take(&mut self.pending_delete) {
+            if code == KeyCode::Char('y') {
+                self.delete_config();
+            } else {
+                self.status.clear();
+            }
+            return;
+        }
+        if self.editing_filter {
+            match code {
+                KeyCode::Enter | KeyCode::Esc => self.editing_filter = false,
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.apply_filter();
+                }
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.apply_filter();
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Char('/') => self.editing_filter = true,
+            KeyCode::Char('r') => self.rescan(),
+            KeyCode::Char('d') => self.on_delete_key(),
+            KeyCode::Enter => self.write_config(),
+            KeyCode::Down | KeyCode::Char('j') => self.list_state.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.list_state.select_previous(),
+            KeyCode::PageDown => self.list_state.scroll_down_by(10),
+            KeyCode::PageUp => self.list_state.scroll_up_by(10),
+            KeyCode::Home => self.list_state.select_first(),
+            KeyCode::End => self.list_state.select_last(),
+            _ => {}
+        }
+    }
+
+    /// Rescans the configured IR directory and reapplies the filter.
+    fn rescan(&mut self) {
+        match self.file_manager.rescan_configured_directory() {
+            Ok(index) => {
+                self.wav_files = index;
+                self.status =
+                    tr(Msg::TuiFilesFound).replace("{}", &self.wav_files.len().to_string());
+            }
+            Err(e) => {
+                error!("Failed to rescan directory: {}", e);
+                self.status = tr(Msg::RescanErrorMessage).replace("{}", &format!("{e:#}"));
+            }
+        }
+        self.apply_filter();
+    }
+
+    /// Rebuilds the visible rows from the filter text, keeping the selected
+    /// file selected if it is still visible.
+    fn apply_filter(&mut self) {
+        let selected = self.selected_checksum();
+        let needle = self.filter.to_lowercase();
+        self.visible = self
+            .wav_files
+            .iter()
+            .enumerate()
+            .filter(|(_, wave)| {
+                needle.is_empty()
+                    || wave
+                        .relative_path
+                        .to_string_lossy()
+                        .to_lowercase()
+                        .contains(&needle)
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        let row = selected
+            .and_then(|checksum| {
+                self.visible.iter().position(|&index| {
+                    self.wav_files
+                        .get_by_index(index)
+                        .is_some_and(|w| w.checksum == checksum)
+                })
+            })
+            .or(if self.visible.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+        self.list_state.select(row);
+    }
+
+    /// Selects the row of the file at `path`, clearing the filter if needed.
+    fn select_path(&mut self, path: &Path) {
+        let find = |app: &Self| {
+            app.visible.iter().position(|&index| {
+                app.wav_files
+                    .get_by_index(index)
+                    .is_some_and(|w| w.path == path)
+            })
+        };
+        if find(self).is_none() {
+            self.filter.clear();
+            self.apply_filter();
+        }
+        if let Some(row) = find(self) {
+            self.list_state.select(Some(row));
+        }
+    }
+
+    /// Checksum of the file in the selected row.
+    fn selected_checksum(&self) -> Option<u128> {
+        self.selected_file().map(|(_, checksum)| checksum)
+    }
+
+    /// Path and checksum of the file in the selected row.
+    fn selected_file(&self) -> Option<(PathBuf, u128)> {
+        let row = self.list_state.selected()?;
+        let index = *self.visible.get(row)?;
+        let wave = self.wav_files.get_by_index(index)?;
+        Some((wave.path.clone(), wave.checksum))
+    }
+
+    /// Creates or updates the virtual device with the selected file, after
+    /// the checks of `ConfigManager::preflight`. A file whose channels don't
+    /// match the layout of the device waits for confirmation.
+    fn write_config(&mut self) {
+        let Some(wave) = self
+            .selected_checksum()
+            .and_then(|checksum| self.wav_files.get_by_checksum(checksum))
+        else {
+            self.status = tr(Msg::SelectFileToProceed).to_string();
+            return;
+        };
+        let (path, checksum) = (wave.path.clone(), wave.checksum);
+        let file = wave.relative_path.display().to_string();
+        let result = self.config_manager.preflight(&path, wave.channels, false);
+        let Err(error) = result else {
+            return self.write_file(&path, checksum);
+        };
+        warn!("Config not written: {}", error);
+        let (_, message) = preflight_error_text(&error, &file);
+        self.status = message;
+        if let PreflightError::ChannelMismatch { .. } = error {
+            self.status.push(' ');
+            self.status.push_str(tr(Msg::TuiConfirmWrite));
+            self.pending_write = Some((path, checksum));
+        }
+    }
+
+    /// Writes the config of the file at `path` and reports the result.
+    fn write_file(&mut self, path: &Path, checksum: u128) {
+        match self.config_manager.write_config(path, checksum) {
+            Ok(()) => {
+                info!("Config written using {}", path.display());
+                self.config_installed = self.config_manager.config_exists().ok().flatten();
+                self.status = tr(Msg::TuiDeviceWritten).replace("{}", &path.display().to_string());
+            }
+            Err(e) => {
+                error!("Failed to write config: {}", e);
+                self.status = tr(Msg::TuiError).replace("{}", &format!("{e:#}"));
+            }
+        }
+    }
+
+    /// Removes the virtual device, after the user confirms unless the
+    /// `confirm_device_removal` setting is off.
+    fn on_delete_key(&mut self) {
+        if !self.settings.borrow().confirm_device_removal {
+            return self.delete_config();
+        }
+        let device_name = self.settings.borrow().virtual_device_name.clone();
+        self.status = format!(
+            "{} {}",
+            tr(Msg::RemoveDeviceMessage).replace("{}", &device_name),
+            tr(Msg::TuiConfirmRemove)
+        );
+        self.pending_delete = true;
+    }
+
+    /// Removes the virtual device.
+    fn delete_config(&mut self) {
+        match self.config_manager.delete_config() {
+            Ok(()) => {
+                info!("Config deleted");
+                self.config_installed = None;
+                self.status = tr(Msg::TuiDeviceRemoved).to_string();
+            }
+            Err(e) => {
+                error!("Failed to delete config: {}", e);
+                self.status = tr(Msg::TuiError).replace("{}", &format!("{e:#}"));
+            }
+        }
+    }
+
+    /// Draws the device line, the file list, the filter and the status line.
+    fn render(&mut self, frame: &mut Frame) {
+        let [device_area, list_area, filter_area, status_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(3),
+            Constraint::Length(1),
+            Constraint::Length(3),
+        ])
+        .areas(frame.area());
+
+        let device_line = match self.config_installed {
+            None => tr(Msg::NoConfigInstalled).to_string(),
+            Some(checksum) => match self.wav_files.get_by_checksum(checksum) {
+                Some(wave) => format!(
+                    "{} {}",
+                    tr(Msg::CurrentIrFile),
+                    wave.relative_path.display()
+                ),
+                None if checksum == 0 => tr(Msg::ConfiguredFileDamaged).to_string(),
+                None => tr(Msg::ConfiguredFileNotFound).to_string(),
+            },
+        };
+        frame.render_widget(
+            Paragraph::new(device_line).style(Style::new().add_modifier(Modifier::BOLD)),
+            device_area,
+        );
+
+        let items: Vec<ListItem> = self
+            .visible
+            .iter()
+            .filter_map(|&index| self.wav_files.get_by_index(index))
+            .map(|wave| {
+                let marker = if Some(wave.checksum) == self.config_installed {
+                    "● "
+                } else {
+                    "  "
+                };
+                let rate = match wave.sample_rate {
+                    WaveSampleRate::Damaged => tr(Msg::DamagedTag).to_string(),
+                    rate => rate
+                        .hz()
+                        .map(|hz| hz.to_string())
+                        .unwrap_or_else(|| "?".to_string()),
+                };
+                let description = wave.metadata.as_ref().map(|m| m.hrtf.as_str());
+                let mut line = format!("{marker}{} [{rate}]", wave.relative_path.display());
+                if let Some(description) = description {
+                    line.push_str(" — ");
+                    line.push_str(description);
+                }
+                ListItem::new(line)
+            })
+            .collect();
+        let title = format!(
+            " {} ({}/{}) ",
+            tr(Msg::LocatedIrFiles),
+            self.visible.len(),
+            self.wav_files.len()
+        );
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        let cursor = if self.editing_filter { "_" } else { "" };
+        frame.render_widget(
+            Paragraph::new(format!(
+                "{} {}{cursor}",
+                tr(Msg::TuiFilterLabel),
+                self.filter
+            )),
+            filter_area,
+        );
+
+        let status = format!("{}\n{}", self.status, tr(Msg::TuiKeysHint));
+        frame.render_widget(
+            Paragraph::new(status).wrap(Wrap { trim: true }),
+            status_area,
+        );
+    }
+}
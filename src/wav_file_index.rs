@@ -1,15 +1,19 @@
 #![allow(dead_code)]
 use crate::file_manager::WavFileData;
 use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::Path;
 
 /// Indexed storage for WAV file data with fast lookup by checksum.
 ///
 /// Maintains a vector of `WavFileData` items and a hash map from non‑zero checksums
 /// to their positions in the vector. Zero checksums are not indexed.
+/// Also counts how many items share each file name (basename).
 #[derive(Clone, Default)]
 pub struct WavFileIndex {
     items: Vec<WavFileData>,
     checksum_index: HashMap<u128, usize>,
+    file_name_counts: HashMap<OsString, usize>,
 }
 
 impl WavFileIndex {
@@ -18,6 +22,7 @@ impl WavFileIndex {
         Self {
             items: Vec::new(),
             checksum_index: HashMap::new(),
+            file_name_counts: HashMap::new(),
         }
     }
 
@@ -28,14 +33,19 @@ impl WavFileIndex {
     /// If a duplicate non‑zero checksum appears, the later item's position overwrites the earlier one.
     pub fn from_vec(items: Vec<WavFileData>) -> Self {
         let mut checksum_index = HashMap::new();
+        let mut file_name_counts = HashMap::new();
         for (idx, item) in items.iter().enumerate() {
             if item.checksum != 0 {
                 checksum_index.insert(item.checksum, idx);
             }
+            if let Some(name) = item.path.file_name() {
+                *file_name_counts.entry(name.to_os_string()).or_insert(0) += 1;
+            }
         }
         Self {
             items,
             checksum_index,
+            file_name_counts,
         }
     }
 
@@ -43,6 +53,7 @@ impl WavFileIndex {
     pub fn clear(&mut self) {
         self.items.clear();
         self.checksum_index.clear();
+        self.file_name_counts.clear();
     }
 
     /// Adds a `WavFileData` item to the index.
@@ -53,6 +64,12 @@ impl WavFileIndex {
     /// overwritten (duplicates are not expected in normal operation).
     pub fn add(&mut self, item: WavFileData) {
         let idx = self.items.len();
+        if let Some(name) = item.path.file_name() {
+            *self
+                .file_name_counts
+                .entry(name.to_os_string())
+                .or_insert(0) += 1;
+        }
         self.items.push(item);
         if self.items[idx].checksum != 0 {
             self.checksum_index.insert(self.items[idx].checksum, idx);
@@ -64,6 +81,11 @@ impl WavFileIndex {
         self.items.len()
     }
 
+    /// Returns true if there are no stored items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
     /// Returns a reference to the item at the given index, if it exists.
     pub fn get_by_index(&self, index: usize) -> Option<&WavFileData> {
         self.items.get(index)
@@ -81,6 +103,12 @@ impl WavFileIndex {
             .and_then(|&idx| self.items.get(idx))
     }
 
+    /// Returns references to the two items with the given checksums, or `None`
+    /// if either of them is missing. Lets callers hold both items at once.
+    pub fn get_pair(&self, first: u128, second: u128) -> Option<(&WavFileData, &WavFileData)> {
+        Some((self.get_by_checksum(first)?, self.get_by_checksum(second)?))
+    }
+
     /// Returns the index (position) of the item with the given checksum, if it exists.
     ///
     /// Returns `None` for `checksum == 0` or if the checksum is not present in the index.
@@ -91,6 +119,34 @@ impl WavFileIndex {
         self.checksum_index.get(&checksum).copied()
     }
 
+    /// Checks whether another stored item has the same file name as `path`.
+    ///
+    /// The installed device file keeps the source file name, so such files
+    /// can't be told apart by the installed name.
+    pub fn has_file_name_collision(&self, path: &Path) -> bool {
+        path.file_name()
+            .and_then(|name| self.file_name_counts.get(name))
+            .is_some_and(|&count| count > 1)
+    }
+
+    /// Groups the items that share a non‑zero checksum, i.e. byte-identical
+    /// copies of one file. Each group holds two or more indices in ascending
+    /// order, and groups are ordered by their first index.
+    pub fn duplicate_groups(&self) -> Vec<Vec<usize>> {
+        let mut by_checksum: HashMap<u128, Vec<usize>> = HashMap::new();
+        for (idx, item) in self.items.iter().enumerate() {
+            if item.checksum != 0 {
+                by_checksum.entry(item.checksum).or_default().push(idx);
+            }
+        }
+        let mut groups: Vec<Vec<usize>> = by_checksum
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .collect();
+        groups.sort_by_key(|group| group[0]);
+        groups
+    }
+
     /// Returns an iterator over the stored items.
     pub fn iter(&self) -> std::slice::Iter<'_, WavFileData> {
         self.items.iter()
@@ -115,6 +171,7 @@ impl WavFileIndex {
     pub fn shrink_to_fit(&mut self) {
         self.items.shrink_to_fit();
         self.checksum_index.shrink_to_fit();
+        self.file_name_counts.shrink_to_fit();
     }
 }
 